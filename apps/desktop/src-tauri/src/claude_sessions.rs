@@ -0,0 +1,598 @@
+//! Claude Code session transcripts, linked to Tiki work items.
+//!
+//! Claude Code writes one JSONL file per session under
+//! `~/.claude/projects/<encoded project path>/<session id>.jsonl`. A session
+//! belongs to an issue if it invoked a `/tiki:*` command for it; sessions
+//! without any `/tiki:*` invocation fall back to overlapping the issue's
+//! active window in state.json.
+
+use crate::claude_tokens::{self, UsageRecord};
+use crate::fs_utils;
+use crate::state::{TikiState, WorkContext};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+/// Longest text kept per transcript entry; tool output can run to megabytes.
+const MAX_ENTRY_CHARS: usize = 20_000;
+const DEFAULT_TRANSCRIPT_LIMIT: usize = 500;
+
+// --- Locating session files ---
+
+/// `$CLAUDE_CONFIG_DIR` or `~/.claude`.
+pub(crate) fn claude_home() -> Option<PathBuf> {
+    std::env::var_os("CLAUDE_CONFIG_DIR")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|h| h.join(".claude")))
+}
+
+/// Claude Code names a project's directory after its path with every
+/// non-alphanumeric character replaced by `-`.
+pub(crate) fn encode_project_dir(project: &Path) -> String {
+    project
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+fn session_files(claude_home: &Path, project: &Path) -> Vec<PathBuf> {
+    let dir = claude_home.join("projects").join(encode_project_dir(project));
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("jsonl"))
+        .collect();
+    files.sort();
+    files
+}
+
+// --- Session summaries ---
+
+/// A `/tiki:*` command found in a session, e.g. `/tiki:execute 42`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TikiInvocation {
+    /// Command name without the `/tiki:` prefix, e.g. "execute".
+    pub command: String,
+    pub args: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+}
+
+impl TikiInvocation {
+    /// Issue numbers named in the arguments (`42`, `#42`).
+    fn issues(&self) -> Vec<u32> {
+        self.args
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter_map(|a| a.trim_start_matches('#').parse().ok())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionAssociation {
+    /// The session ran a `/tiki:*` command for the issue.
+    Invocation,
+    /// No `/tiki:*` command; the session overlaps the issue's active window.
+    TimeWindow,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeSessionInfo {
+    pub session_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<String>,
+    pub user_messages: usize,
+    pub assistant_messages: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
+    /// Claude Code's own summary line, when it wrote one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    pub invocations: Vec<TikiInvocation>,
+    /// How the session was linked to the requested issue.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub association: Option<SessionAssociation>,
+    /// Per-message token usage, read by `claude_tokens`.
+    #[serde(skip)]
+    pub(crate) usage: Vec<UsageRecord>,
+}
+
+/// Text of a message's `content`, which is either a string or a list of
+/// blocks; only `text` blocks count.
+fn content_text(content: &serde_json::Value) -> String {
+    match content {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("text"))
+            .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+fn tag_value<'a>(text: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = text.find(&open)? + open.len();
+    let end = text[start..].find(&close)? + start;
+    Some(text[start..end].trim())
+}
+
+/// Recognize a slash command in a user message: either Claude Code's
+/// `<command-name>/tiki:x</command-name><command-args>..</command-args>`
+/// expansion or a prompt that starts with `/tiki:x` (as passed to `claude -p`
+/// or by a supervised run).
+fn parse_invocation(text: &str) -> Option<(String, String)> {
+    let (name, args) = match tag_value(text, "command-name") {
+        Some(name) => (name, tag_value(text, "command-args").unwrap_or("")),
+        None => {
+            let line = text.trim_start().lines().next()?;
+            match line.split_once(char::is_whitespace) {
+                Some((name, args)) => (name, args.trim()),
+                None => (line, ""),
+            }
+        }
+    };
+    let command = name.strip_prefix("/tiki:")?;
+    (!command.is_empty()).then(|| (command.to_string(), args.to_string()))
+}
+
+fn summarize_session(path: &Path) -> Result<ClaudeSessionInfo, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut info = ClaudeSessionInfo {
+        session_id: path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        started_at: None,
+        ended_at: None,
+        user_messages: 0,
+        assistant_messages: 0,
+        git_branch: None,
+        summary: None,
+        invocations: Vec::new(),
+        association: None,
+        usage: Vec::new(),
+    };
+    let mut counted = HashSet::new();
+
+    for line in BufReader::new(file).lines() {
+        let Ok(line) = line else { break };
+        // A line being written right now may be cut short; skip it.
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        let timestamp = entry.get("timestamp").and_then(|t| t.as_str()).map(str::to_string);
+        if let Some(ref ts) = timestamp {
+            if info.started_at.is_none() {
+                info.started_at = Some(ts.clone());
+            }
+            info.ended_at = Some(ts.clone());
+        }
+        if info.git_branch.is_none() {
+            info.git_branch = entry.get("gitBranch").and_then(|b| b.as_str()).map(str::to_string);
+        }
+
+        match entry.get("type").and_then(|t| t.as_str()) {
+            Some("summary") => {
+                info.summary = entry.get("summary").and_then(|s| s.as_str()).map(str::to_string);
+            }
+            Some("assistant") => {
+                info.assistant_messages += 1;
+                let Some((message_id, model, tokens)) = claude_tokens::line_usage(&entry) else {
+                    continue;
+                };
+                if counted.insert(message_id.clone()) {
+                    let invocation = info.invocations.last().map(|i| (i.command.clone(), i.issues()));
+                    info.usage.push(UsageRecord { message_id, timestamp, model, tokens, invocation });
+                }
+            }
+            Some("user") => {
+                let content = entry.pointer("/message/content").cloned().unwrap_or_default();
+                // Tool results come back as user messages; they aren't prompts.
+                let is_tool_result = content
+                    .as_array()
+                    .is_some_and(|b| b.iter().any(|b| b.get("type").and_then(|t| t.as_str()) == Some("tool_result")));
+                if is_tool_result {
+                    continue;
+                }
+                info.user_messages += 1;
+                if let Some((command, args)) = parse_invocation(&content_text(&content)) {
+                    info.invocations.push(TikiInvocation { command, args, timestamp });
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(info)
+}
+
+/// Summaries are cached per file and reused while its size and mtime hold.
+type SummaryCache = HashMap<PathBuf, (SystemTime, u64, ClaudeSessionInfo)>;
+
+fn summary_cache() -> &'static Mutex<SummaryCache> {
+    static CACHE: OnceLock<Mutex<SummaryCache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn cached_summary(path: &Path) -> Result<ClaudeSessionInfo, String> {
+    let meta = std::fs::metadata(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let stamp = (meta.modified().unwrap_or(SystemTime::UNIX_EPOCH), meta.len());
+    if let Ok(cache) = summary_cache().lock() {
+        if let Some((mtime, len, info)) = cache.get(path) {
+            if (*mtime, *len) == stamp {
+                return Ok(info.clone());
+            }
+        }
+    }
+    let info = summarize_session(path)?;
+    if let Ok(mut cache) = summary_cache().lock() {
+        cache.insert(path.to_path_buf(), (stamp.0, stamp.1, info.clone()));
+    }
+    Ok(info)
+}
+
+// --- Association ---
+
+pub(crate) fn parse_time(ts: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(ts).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// When the issue was being worked on, from its active state.json entry.
+fn issue_window(state: &TikiState, issue: u32) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    match state.active_work.get(&format!("issue:{}", issue))? {
+        WorkContext::Issue(ctx) => {
+            let start = parse_time(&ctx.created_at)?;
+            let end = ctx
+                .last_activity
+                .as_deref()
+                .and_then(parse_time)
+                .unwrap_or_else(Utc::now);
+            Some((start, end.max(start)))
+        }
+        WorkContext::Release(_) => None,
+    }
+}
+
+fn associate(
+    session: &ClaudeSessionInfo,
+    issue: u32,
+    window: Option<(DateTime<Utc>, DateTime<Utc>)>,
+) -> Option<SessionAssociation> {
+    if session.invocations.iter().any(|i| i.issues().contains(&issue)) {
+        return Some(SessionAssociation::Invocation);
+    }
+    if !session.invocations.is_empty() {
+        return None;
+    }
+    let (from, to) = window?;
+    let start = session.started_at.as_deref().and_then(parse_time)?;
+    let end = session.ended_at.as_deref().and_then(parse_time).unwrap_or(start);
+    (start <= to && end >= from).then_some(SessionAssociation::TimeWindow)
+}
+
+pub(crate) fn issue_sessions(
+    claude_home: &Path,
+    project: &Path,
+    issue: u32,
+) -> Result<Vec<ClaudeSessionInfo>, String> {
+    let state = fs_utils::read_json_resilient::<TikiState>(&project.join(".tiki").join("state.json"))
+        .unwrap_or_else(|e| {
+            log::warn!("Couldn't read state.json for session matching: {}", e);
+            None
+        });
+    let window = state.as_ref().and_then(|s| issue_window(s, issue));
+
+    let mut sessions = Vec::new();
+    for path in session_files(claude_home, project) {
+        let mut info = match cached_summary(&path) {
+            Ok(info) => info,
+            Err(e) => {
+                log::warn!("Skipping Claude session {}", e);
+                continue;
+            }
+        };
+        if let Some(association) = associate(&info, issue, window) {
+            info.association = Some(association);
+            sessions.push(info);
+        }
+    }
+    sessions.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    Ok(sessions)
+}
+
+/// Claude Code sessions for an issue in this project, most recent first.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn list_issue_sessions(
+    issue: u32,
+    project_path: Option<String>,
+) -> Result<Vec<ClaudeSessionInfo>, String> {
+    let home = claude_home().ok_or("Couldn't locate the Claude config directory")?;
+    issue_sessions(&home, &fs_utils::project_root(project_path.as_deref())?, issue)
+}
+
+// --- Transcript ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TranscriptEntryKind {
+    Text,
+    Thinking,
+    ToolUse,
+    ToolResult,
+    Summary,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptEntry {
+    /// "user", "assistant" or "system".
+    pub role: String,
+    pub kind: TranscriptEntryKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub is_error: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTranscript {
+    pub session_id: String,
+    pub entries: Vec<TranscriptEntry>,
+    /// Entries in the whole transcript, for paging with `offset`.
+    pub total: usize,
+}
+
+fn entry(role: &str, kind: TranscriptEntryKind, timestamp: &Option<String>, text: String) -> TranscriptEntry {
+    let truncated = text.chars().count() > MAX_ENTRY_CHARS;
+    TranscriptEntry {
+        role: role.to_string(),
+        kind,
+        timestamp: timestamp.clone(),
+        text: if truncated { text.chars().take(MAX_ENTRY_CHARS).collect() } else { text },
+        tool_name: None,
+        is_error: false,
+        truncated,
+    }
+}
+
+/// Flatten one JSONL line into display entries, one per content block.
+fn transcript_entries(line: &serde_json::Value) -> Vec<TranscriptEntry> {
+    let timestamp = line.get("timestamp").and_then(|t| t.as_str()).map(str::to_string);
+    let role = match line.get("type").and_then(|t| t.as_str()) {
+        Some("summary") => {
+            let text = line.get("summary").and_then(|s| s.as_str()).unwrap_or("").to_string();
+            return vec![entry("system", TranscriptEntryKind::Summary, &timestamp, text)];
+        }
+        Some(role @ ("user" | "assistant" | "system")) => role,
+        _ => return Vec::new(),
+    };
+    let content = line
+        .pointer("/message/content")
+        .or_else(|| line.get("content"))
+        .cloned()
+        .unwrap_or_default();
+
+    let blocks = match content {
+        serde_json::Value::String(text) => {
+            return vec![entry(role, TranscriptEntryKind::Text, &timestamp, text)];
+        }
+        serde_json::Value::Array(blocks) => blocks,
+        _ => return Vec::new(),
+    };
+    blocks
+        .iter()
+        .filter_map(|block| {
+            let text_of = |key: &str| block.get(key).and_then(|t| t.as_str()).unwrap_or("").to_string();
+            match block.get("type").and_then(|t| t.as_str())? {
+                "text" => Some(entry(role, TranscriptEntryKind::Text, &timestamp, text_of("text"))),
+                "thinking" => Some(entry(role, TranscriptEntryKind::Thinking, &timestamp, text_of("thinking"))),
+                "tool_use" => {
+                    let input = block.get("input").map(|i| i.to_string()).unwrap_or_default();
+                    let mut e = entry(role, TranscriptEntryKind::ToolUse, &timestamp, input);
+                    e.tool_name = block.get("name").and_then(|n| n.as_str()).map(str::to_string);
+                    Some(e)
+                }
+                "tool_result" => {
+                    let text = block.get("content").map(content_text).unwrap_or_default();
+                    let mut e = entry(role, TranscriptEntryKind::ToolResult, &timestamp, text);
+                    e.is_error = block.get("is_error").and_then(|v| v.as_bool()).unwrap_or(false);
+                    Some(e)
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn read_transcript(
+    claude_home: &Path,
+    project: &Path,
+    session_id: &str,
+    offset: usize,
+    limit: usize,
+) -> Result<SessionTranscript, String> {
+    // Session IDs are UUIDs; refuse anything that could walk out of the directory.
+    if session_id.is_empty() || !session_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid session id '{}'", session_id));
+    }
+    let path = session_files(claude_home, project)
+        .into_iter()
+        .find(|p| p.file_stem().and_then(|s| s.to_str()) == Some(session_id))
+        .ok_or_else(|| format!("Session '{}' not found for this project", session_id))?;
+
+    let file = std::fs::File::open(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut entries = Vec::new();
+    let mut total = 0;
+    for line in BufReader::new(file).lines() {
+        let Ok(line) = line else { break };
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        for e in transcript_entries(&value) {
+            if total >= offset && entries.len() < limit {
+                entries.push(e);
+            }
+            total += 1;
+        }
+    }
+    Ok(SessionTranscript {
+        session_id: session_id.to_string(),
+        entries,
+        total,
+    })
+}
+
+/// Read a session's transcript as flat entries (prompts, replies, tool calls
+/// and their results), paged.
+/// - offset: Entries to skip. Defaults to 0
+/// - limit: Maximum entries to return. Defaults to 500
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn read_session_transcript(
+    session_id: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    project_path: Option<String>,
+) -> Result<SessionTranscript, String> {
+    let home = claude_home().ok_or("Couldn't locate the Claude config directory")?;
+    read_transcript(
+        &home,
+        &fs_utils::project_root(project_path.as_deref())?,
+        &session_id,
+        offset.unwrap_or(0),
+        limit.unwrap_or(DEFAULT_TRANSCRIPT_LIMIT),
+    )
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A fresh, not yet created directory under the system temp dir.
+    pub(crate) fn temp_dir(tag: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!("tiki-sessions-{}-{}", tag, nanos))
+    }
+
+    /// Write a session transcript for `project` under `home`, one JSON line each.
+    pub(crate) fn write_session(home: &Path, project: &Path, id: &str, lines: &[serde_json::Value]) {
+        let dir = home.join("projects").join(encode_project_dir(project));
+        std::fs::create_dir_all(&dir).unwrap();
+        let body: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        std::fs::write(dir.join(format!("{}.jsonl", id)), body.join("\n")).unwrap();
+    }
+
+    fn user(ts: &str, content: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({ "type": "user", "timestamp": ts, "message": { "role": "user", "content": content } })
+    }
+
+    #[test]
+    fn encodes_project_dirs_like_claude_code() {
+        assert_eq!(encode_project_dir(Path::new("/Users/me/my.app")), "-Users-me-my-app");
+    }
+
+    #[test]
+    fn recognizes_tiki_invocations() {
+        assert_eq!(
+            parse_invocation("<command-message>tiki:execute is running…</command-message>\n<command-name>/tiki:execute</command-name>\n<command-args>42</command-args>"),
+            Some(("execute".to_string(), "42".to_string()))
+        );
+        assert_eq!(parse_invocation("/tiki:yolo #7 --fast"), Some(("yolo".to_string(), "#7 --fast".to_string())));
+        assert_eq!(parse_invocation("please run /tiki:execute 42"), None);
+        assert_eq!(parse_invocation("/review 42"), None);
+
+        let inv = TikiInvocation { command: "yolo".into(), args: "#7 --fast".into(), timestamp: None };
+        assert_eq!(inv.issues(), vec![7]);
+    }
+
+    #[test]
+    fn lists_sessions_for_an_issue() {
+        let home = temp_dir("home");
+        let project = temp_dir("project");
+        std::fs::create_dir_all(project.join(".tiki")).unwrap();
+        std::fs::write(
+            project.join(".tiki").join("state.json"),
+            r#"{"schemaVersion":1,"activeWork":{"issue:42":{"type":"issue","issue":{"number":42},"status":"failed","createdAt":"2026-03-01T10:00:00Z","lastActivity":"2026-03-01T12:00:00Z"}}}"#,
+        )
+        .unwrap();
+
+        write_session(&home, &project, "aaa", &[
+            user("2026-02-01T09:00:00Z", serde_json::json!("/tiki:execute 42")),
+            serde_json::json!({ "type": "assistant", "timestamp": "2026-02-01T09:01:00Z", "message": { "content": [{ "type": "text", "text": "ok" }] } }),
+        ]);
+        // No /tiki: command, but inside issue 42's active window.
+        write_session(&home, &project, "bbb", &[user("2026-03-01T11:00:00Z", serde_json::json!("fix the flaky test"))]);
+        // Inside the window, but explicitly for another issue.
+        write_session(&home, &project, "ccc", &[user("2026-03-01T11:30:00Z", serde_json::json!("/tiki:execute 43"))]);
+        // Outside the window.
+        write_session(&home, &project, "ddd", &[user("2026-04-01T11:00:00Z", serde_json::json!("hello"))]);
+
+        let sessions = issue_sessions(&home, &project, 42).unwrap();
+        let found: Vec<(&str, Option<SessionAssociation>)> =
+            sessions.iter().map(|s| (s.session_id.as_str(), s.association)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("bbb", Some(SessionAssociation::TimeWindow)),
+                ("aaa", Some(SessionAssociation::Invocation)),
+            ]
+        );
+        assert_eq!(sessions[1].assistant_messages, 1);
+
+        std::fs::remove_dir_all(&home).unwrap();
+        std::fs::remove_dir_all(&project).unwrap();
+    }
+
+    #[test]
+    fn reads_transcript_entries() {
+        let home = temp_dir("home");
+        let project = temp_dir("project");
+        write_session(&home, &project, "s-1", &[
+            user("2026-03-01T10:00:00Z", serde_json::json!("/tiki:execute 42")),
+            serde_json::json!({ "type": "assistant", "timestamp": "2026-03-01T10:00:05Z", "message": { "content": [
+                { "type": "text", "text": "Running tests" },
+                { "type": "tool_use", "name": "Bash", "input": { "command": "cargo test" } }
+            ] } }),
+            user("2026-03-01T10:00:09Z", serde_json::json!([{ "type": "tool_result", "is_error": true, "content": [{ "type": "text", "text": "1 failed" }] }])),
+        ]);
+
+        let t = read_transcript(&home, &project, "s-1", 0, 10).unwrap();
+        assert_eq!(t.total, 4);
+        assert_eq!(t.entries[2].kind, TranscriptEntryKind::ToolUse);
+        assert_eq!(t.entries[2].tool_name.as_deref(), Some("Bash"));
+        assert_eq!(t.entries[3].text, "1 failed");
+        assert!(t.entries[3].is_error);
+
+        let page = read_transcript(&home, &project, "s-1", 3, 10).unwrap();
+        assert_eq!(page.entries.len(), 1);
+        assert!(read_transcript(&home, &project, "../s-1", 0, 10).is_err());
+
+        std::fs::remove_dir_all(&home).unwrap();
+    }
+}
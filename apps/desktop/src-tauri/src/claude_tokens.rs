@@ -0,0 +1,432 @@
+//! Token and cost accounting from local Claude Code session logs.
+//!
+//! Every assistant message in a session JSONL carries `message.usage`
+//! (input, output and cache tokens). Claude Code writes one line per content
+//! block, repeating the same usage, so messages are counted once by
+//! `message.id`. Usage is attributed to the issue and step named by the most
+//! recent `/tiki:*` invocation in the session; sessions linked to an issue by
+//! time window fall back to its pipeline history for the step.
+//!
+//! Costs are estimates from list prices and ignore batch or plan discounts.
+
+use crate::claude_sessions::{
+    claude_home, issue_sessions, parse_time, SessionAssociation,
+};
+use crate::commands::load_tiki_releases;
+use crate::fs_utils;
+use crate::state::{PipelineStepRecord, TikiState, WorkContext};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// Step name for usage that can't be tied to a pipeline step.
+const UNATTRIBUTED_STEP: &str = "unattributed";
+
+// --- Token counts ---
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenCounts {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+}
+
+impl TokenCounts {
+    fn add(&mut self, other: &TokenCounts) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+    }
+}
+
+/// One assistant message's usage, as recorded while summarizing a session.
+#[derive(Debug, Clone)]
+pub(crate) struct UsageRecord {
+    pub message_id: String,
+    pub timestamp: Option<String>,
+    pub model: String,
+    pub tokens: TokenCounts,
+    /// Command and issue numbers of the latest `/tiki:*` invocation before
+    /// this message, if any.
+    pub invocation: Option<(String, Vec<u32>)>,
+}
+
+/// Usage from an assistant JSONL line: `(message id, model, tokens)`.
+pub(crate) fn line_usage(line: &serde_json::Value) -> Option<(String, String, TokenCounts)> {
+    let message = line.get("message")?;
+    let usage = message.get("usage")?;
+    let count = |key: &str| usage.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    let tokens = TokenCounts {
+        input_tokens: count("input_tokens"),
+        output_tokens: count("output_tokens"),
+        cache_creation_tokens: count("cache_creation_input_tokens"),
+        cache_read_tokens: count("cache_read_input_tokens"),
+    };
+    // Older logs lack message.id; fall back to the line's own uuid.
+    let id = message
+        .get("id")
+        .or_else(|| line.get("requestId"))
+        .or_else(|| line.get("uuid"))
+        .and_then(|v| v.as_str())?
+        .to_string();
+    let model = message
+        .get("model")
+        .and_then(|m| m.as_str())
+        .unwrap_or("unknown")
+        .to_string();
+    Some((id, model, tokens))
+}
+
+// --- Pricing ---
+
+/// USD per million tokens: (input, output). Cache writes bill at 1.25x input
+/// and cache reads at 0.1x input.
+fn model_price(model: &str) -> Option<(f64, f64)> {
+    let m = model.to_lowercase();
+    let price = if m.contains("opus-4-5") {
+        (5.0, 25.0)
+    } else if m.contains("opus") {
+        (15.0, 75.0)
+    } else if m.contains("sonnet") {
+        (3.0, 15.0)
+    } else if m.contains("haiku-4") {
+        (1.0, 5.0)
+    } else if m.contains("3-5-haiku") {
+        (0.8, 4.0)
+    } else if m.contains("haiku") {
+        (0.25, 1.25)
+    } else {
+        return None;
+    };
+    Some(price)
+}
+
+fn estimate_cost(model: &str, tokens: &TokenCounts) -> Option<f64> {
+    let (input, output) = model_price(model)?;
+    let per_token = |price: f64| price / 1_000_000.0;
+    Some(
+        tokens.input_tokens as f64 * per_token(input)
+            + tokens.cache_creation_tokens as f64 * per_token(input * 1.25)
+            + tokens.cache_read_tokens as f64 * per_token(input * 0.1)
+            + tokens.output_tokens as f64 * per_token(output),
+    )
+}
+
+// --- Rollups ---
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageTotals {
+    pub messages: usize,
+    pub tokens: TokenCounts,
+    /// Sum over messages from models with a known price.
+    pub estimated_cost_usd: f64,
+}
+
+impl UsageTotals {
+    fn add(&mut self, record: &UsageRecord) {
+        self.messages += 1;
+        self.tokens.add(&record.tokens);
+        self.estimated_cost_usd += estimate_cost(&record.model, &record.tokens).unwrap_or(0.0);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTokenUsage {
+    pub session_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    pub totals: UsageTotals,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueTokenUsage {
+    pub issue: u32,
+    pub totals: UsageTotals,
+    /// Keyed by lowercase step ("execute", "plan", ...) or "unattributed".
+    pub by_step: BTreeMap<String, UsageTotals>,
+    pub by_model: BTreeMap<String, UsageTotals>,
+    pub sessions: Vec<SessionTokenUsage>,
+    /// Models seen without a known price; their cost isn't included.
+    pub unpriced_models: Vec<String>,
+}
+
+fn note_unpriced(models: &mut Vec<String>, model: &str) {
+    if model_price(model).is_none() && !models.iter().any(|m| m == model) {
+        models.push(model.to_string());
+    }
+}
+
+/// A message counted towards an issue.
+struct Attributed {
+    session_id: String,
+    session_started_at: Option<String>,
+    step: String,
+    record: UsageRecord,
+}
+
+fn pipeline_history(state: Option<&TikiState>, issue: u32) -> Vec<PipelineStepRecord> {
+    match state.and_then(|s| s.active_work.get(&format!("issue:{}", issue))) {
+        Some(WorkContext::Issue(ctx)) => ctx.pipeline_history.clone().unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// The pipeline step running at `at`, from the issue's step history.
+fn step_at(history: &[PipelineStepRecord], at: DateTime<Utc>) -> Option<String> {
+    history
+        .iter()
+        .rev()
+        .find(|r| {
+            let started = parse_time(&r.started_at).is_some_and(|s| s <= at);
+            let not_done = r
+                .completed_at
+                .as_deref()
+                .and_then(parse_time)
+                .map_or(true, |c| at <= c);
+            started && not_done
+        })
+        .map(|r| r.step.to_lowercase())
+}
+
+/// Every message in the issue's sessions that belongs to the issue, with
+/// the step it ran under.
+fn attribute(claude_home: &Path, project: &Path, issue: u32) -> Result<Vec<Attributed>, String> {
+    let state = fs_utils::read_json_resilient::<TikiState>(&project.join(".tiki").join("state.json"))
+        .unwrap_or(None);
+    let history = pipeline_history(state.as_ref(), issue);
+
+    let mut attributed = Vec::new();
+    for session in issue_sessions(claude_home, project, issue)? {
+        let by_invocation = session.association == Some(SessionAssociation::Invocation);
+        for record in &session.usage {
+            let step = match record.invocation {
+                Some((ref command, ref issues)) => {
+                    if !issues.contains(&issue) {
+                        continue;
+                    }
+                    command.to_lowercase()
+                }
+                // Before the session's first /tiki:* command.
+                None if by_invocation => continue,
+                None => record
+                    .timestamp
+                    .as_deref()
+                    .and_then(parse_time)
+                    .and_then(|at| step_at(&history, at))
+                    .unwrap_or_else(|| UNATTRIBUTED_STEP.to_string()),
+            };
+            attributed.push(Attributed {
+                session_id: session.session_id.clone(),
+                session_started_at: session.started_at.clone(),
+                step,
+                record: record.clone(),
+            });
+        }
+    }
+    Ok(attributed)
+}
+
+fn issue_usage(issue: u32, attributed: &[Attributed]) -> IssueTokenUsage {
+    let mut usage = IssueTokenUsage {
+        issue,
+        ..Default::default()
+    };
+    for a in attributed {
+        usage.totals.add(&a.record);
+        usage.by_step.entry(a.step.clone()).or_default().add(&a.record);
+        usage.by_model.entry(a.record.model.clone()).or_default().add(&a.record);
+        note_unpriced(&mut usage.unpriced_models, &a.record.model);
+        match usage.sessions.iter_mut().find(|s| s.session_id == a.session_id) {
+            Some(s) => s.totals.add(&a.record),
+            None => {
+                let mut totals = UsageTotals::default();
+                totals.add(&a.record);
+                usage.sessions.push(SessionTokenUsage {
+                    session_id: a.session_id.clone(),
+                    started_at: a.session_started_at.clone(),
+                    totals,
+                });
+            }
+        }
+    }
+    usage
+}
+
+/// Token usage and estimated cost for an issue, from local Claude Code logs.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn get_issue_token_usage(
+    issue: u32,
+    project_path: Option<String>,
+) -> Result<IssueTokenUsage, String> {
+    let home = claude_home().ok_or("Couldn't locate the Claude config directory")?;
+    let attributed = attribute(&home, &fs_utils::project_root(project_path.as_deref())?, issue)?;
+    Ok(issue_usage(issue, &attributed))
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueUsageSummary {
+    pub issue: u32,
+    pub title: String,
+    pub totals: UsageTotals,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseTokenUsage {
+    pub version: String,
+    /// Messages shared by several issues (e.g. one session working on two)
+    /// count once here, so this can be less than the sum of `by_issue`.
+    pub totals: UsageTotals,
+    pub by_issue: Vec<IssueUsageSummary>,
+    pub by_step: BTreeMap<String, UsageTotals>,
+    pub unpriced_models: Vec<String>,
+}
+
+fn release_usage(
+    claude_home: &Path,
+    project: &Path,
+    version: &str,
+) -> Result<ReleaseTokenUsage, String> {
+    let tiki = project.join(".tiki");
+    let release = load_tiki_releases(Some(tiki.to_string_lossy().into_owned()), Some(true))?
+        .into_iter()
+        .find(|r| r.version == version)
+        .ok_or_else(|| format!("Release '{}' not found", version))?;
+
+    let mut rollup = ReleaseTokenUsage {
+        version: release.version.clone(),
+        ..Default::default()
+    };
+    let mut seen: HashSet<(String, String)> = HashSet::new();
+    for item in &release.issues {
+        let attributed = attribute(claude_home, project, item.number)?;
+        for a in &attributed {
+            if seen.insert((a.session_id.clone(), a.record.message_id.clone())) {
+                rollup.totals.add(&a.record);
+                rollup.by_step.entry(a.step.clone()).or_default().add(&a.record);
+                note_unpriced(&mut rollup.unpriced_models, &a.record.model);
+            }
+        }
+        rollup.by_issue.push(IssueUsageSummary {
+            issue: item.number,
+            title: item.title.clone(),
+            totals: issue_usage(item.number, &attributed).totals,
+        });
+    }
+    Ok(rollup)
+}
+
+/// Token usage and estimated cost for every issue in a release.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn get_release_token_usage(
+    version: String,
+    project_path: Option<String>,
+) -> Result<ReleaseTokenUsage, String> {
+    let home = claude_home().ok_or("Couldn't locate the Claude config directory")?;
+    release_usage(&home, &fs_utils::project_root(project_path.as_deref())?, &version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude_sessions::tests::{temp_dir, write_session};
+
+    fn prompt(ts: &str, text: &str) -> serde_json::Value {
+        serde_json::json!({ "type": "user", "timestamp": ts, "message": { "role": "user", "content": text } })
+    }
+
+    fn reply(ts: &str, id: &str, input: u64, output: u64) -> serde_json::Value {
+        serde_json::json!({ "type": "assistant", "timestamp": ts, "message": {
+            "id": id,
+            "model": "claude-sonnet-4-5",
+            "content": [{ "type": "text", "text": "ok" }],
+            "usage": { "input_tokens": input, "output_tokens": output, "cache_read_input_tokens": 1000 }
+        } })
+    }
+
+    #[test]
+    fn estimates_cost_from_list_prices() {
+        let tokens = TokenCounts {
+            input_tokens: 1_000_000,
+            output_tokens: 1_000_000,
+            cache_creation_tokens: 1_000_000,
+            cache_read_tokens: 1_000_000,
+        };
+        let cost = estimate_cost("claude-sonnet-4-5-20250929", &tokens).unwrap();
+        assert!((cost - (3.0 + 15.0 + 3.75 + 0.3)).abs() < 1e-9);
+        assert_eq!(estimate_cost("gpt-4", &tokens), None);
+    }
+
+    #[test]
+    fn finds_the_step_running_at_a_time() {
+        let history = vec![
+            PipelineStepRecord {
+                step: "PLAN".into(),
+                started_at: "2026-03-01T10:00:00Z".into(),
+                completed_at: Some("2026-03-01T10:30:00Z".into()),
+            },
+            PipelineStepRecord {
+                step: "EXECUTE".into(),
+                started_at: "2026-03-01T10:30:00Z".into(),
+                completed_at: None,
+            },
+        ];
+        let at = |ts: &str| parse_time(ts).unwrap();
+        assert_eq!(step_at(&history, at("2026-03-01T10:10:00Z")).as_deref(), Some("plan"));
+        assert_eq!(step_at(&history, at("2026-03-01T11:00:00Z")).as_deref(), Some("execute"));
+        assert_eq!(step_at(&history, at("2026-03-01T09:00:00Z")), None);
+    }
+
+    #[test]
+    fn attributes_usage_to_issue_and_step() {
+        let home = temp_dir("home");
+        let project = temp_dir("project");
+        std::fs::create_dir_all(project.join(".tiki")).unwrap();
+
+        write_session(&home, &project, "s-1", &[
+            reply("2026-03-01T09:59:00Z", "msg_0", 50, 5),
+            prompt("2026-03-01T10:00:00Z", "/tiki:plan 42"),
+            // Two content blocks of one message repeat its usage.
+            reply("2026-03-01T10:00:05Z", "msg_1", 100, 10),
+            reply("2026-03-01T10:00:06Z", "msg_1", 100, 10),
+            prompt("2026-03-01T10:05:00Z", "/tiki:execute 42"),
+            reply("2026-03-01T10:05:05Z", "msg_2", 200, 20),
+            prompt("2026-03-01T10:10:00Z", "/tiki:execute 43"),
+            reply("2026-03-01T10:10:05Z", "msg_3", 400, 40),
+        ]);
+
+        let usage = issue_usage(42, &attribute(&home, &project, 42).unwrap());
+        assert_eq!(usage.totals.messages, 2);
+        assert_eq!(usage.totals.tokens.input_tokens, 300);
+        assert_eq!(usage.totals.tokens.output_tokens, 30);
+        assert_eq!(usage.by_step["plan"].tokens.input_tokens, 100);
+        assert_eq!(usage.by_step["execute"].tokens.input_tokens, 200);
+        assert_eq!(usage.sessions.len(), 1);
+        assert!(usage.totals.estimated_cost_usd > 0.0);
+        assert!(usage.unpriced_models.is_empty());
+
+        std::fs::create_dir_all(project.join(".tiki").join("releases")).unwrap();
+        std::fs::write(
+            project.join(".tiki").join("releases").join("v1.json"),
+            r#"{"version":"v1","status":"active","issues":[{"number":42,"title":"A"},{"number":43,"title":"B"}],"createdAt":"2026-03-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        let release = release_usage(&home, &project, "v1").unwrap();
+        assert_eq!(release.by_issue.len(), 2);
+        assert_eq!(release.totals.tokens.input_tokens, 700);
+        assert!(release_usage(&home, &project, "v2").is_err());
+
+        std::fs::remove_dir_all(&home).unwrap();
+        std::fs::remove_dir_all(&project).unwrap();
+    }
+}
@@ -1,16 +1,31 @@
+use crate::claude_sessions::{claude_home, issue_sessions, parse_time};
+use crate::credentials::{self, CLAUDE_SESSION_KEY};
 use crate::fs_utils;
+use crate::state::TikiState;
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
 
-// --- Config (stored in config_dir/tiki-desktop/claude-api.json) ---
+// --- Config (stored in config_dir/tiki-desktop/claude-api.json; the session
+// key itself lives in the credential store) ---
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 struct ApiConfig {
-    #[serde(default)]
+    /// Plaintext key written by older versions. Kept in the file until the
+    /// credential store accepts it, so a save while locked can't lose it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     session_key: Option<String>,
     #[serde(default)]
     org_id: Option<String>,
+    /// Alert thresholds; `None` means the defaults.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    thresholds: Option<Vec<UsageThreshold>>,
 }
 
 fn config_path() -> Option<PathBuf> {
@@ -18,24 +33,69 @@ fn config_path() -> Option<PathBuf> {
 }
 
 fn load_config() -> ApiConfig {
-    config_path()
-        .and_then(|p| std::fs::read_to_string(p).ok())
+    match config_path() {
+        Some(path) => load_config_at(&path, |key| credentials::set_secret(CLAUDE_SESSION_KEY, key)),
+        None => ApiConfig::default(),
+    }
+}
+
+/// Read the config at `path`, handing a plaintext session key to `store`.
+fn load_config_at(path: &Path, store: impl FnOnce(&str) -> Result<(), String>) -> ApiConfig {
+    let mut config: ApiConfig = std::fs::read_to_string(path)
+        .ok()
         .and_then(|c| serde_json::from_str(&c).ok())
-        .unwrap_or_default()
+        .unwrap_or_default();
+    if config.session_key.is_some() {
+        migrate_session_key(path, &mut config, store);
+    }
+    config
+}
+
+/// Move a plaintext session key into the credential store and scrub the old
+/// file. Left in place while the store is locked, so usage keeps working.
+fn migrate_session_key(
+    path: &Path,
+    config: &mut ApiConfig,
+    store: impl FnOnce(&str) -> Result<(), String>,
+) {
+    let Some(key) = config.session_key.as_deref().filter(|k| !k.is_empty()) else {
+        config.session_key = None;
+        return;
+    };
+    if let Err(e) = store(key) {
+        log::warn!("Claude session key not migrated yet: {}", e);
+        return;
+    }
+    config.session_key = None;
+    let scrubbed = credentials::scrub_file(path).and_then(|_| save_config_at(path, config));
+    if let Err(e) = scrubbed {
+        log::error!("Failed to scrub plaintext Claude session key: {}", e);
+    }
+}
+
+/// The stored session key, falling back to a not-yet-migrated plaintext one.
+fn session_key(config: &ApiConfig) -> Result<Option<String>, String> {
+    if let Some(ref key) = config.session_key {
+        return Ok(Some(key.clone()));
+    }
+    credentials::get_secret(CLAUDE_SESSION_KEY)
 }
 
 fn save_config(config: &ApiConfig) -> Result<(), String> {
-    let path = config_path().ok_or("No config directory")?;
+    save_config_at(&config_path().ok_or("No config directory")?, config)
+}
+
+fn save_config_at(path: &Path, config: &ApiConfig) -> Result<(), String> {
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
     let json = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
-    fs_utils::atomic_write(&path, &json)
+    fs_utils::atomic_write(path, &json)
 }
 
 // --- API response types (serialized as camelCase to frontend) ---
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ClaudeApiUsage {
     five_hour: Option<UsageLimit>,
@@ -44,7 +104,18 @@ pub struct ClaudeApiUsage {
     seven_day_sonnet: Option<UsageLimit>,
 }
 
-#[derive(Debug, Serialize)]
+impl ClaudeApiUsage {
+    fn limit(&self, kind: UsageLimitKind) -> Option<&UsageLimit> {
+        match kind {
+            UsageLimitKind::FiveHour => self.five_hour.as_ref(),
+            UsageLimitKind::SevenDay => self.seven_day.as_ref(),
+            UsageLimitKind::SevenDayOpus => self.seven_day_opus.as_ref(),
+            UsageLimitKind::SevenDaySonnet => self.seven_day_sonnet.as_ref(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UsageLimit {
     utilization: f64,
@@ -111,13 +182,10 @@ fn parse_limit(v: &serde_json::Value) -> Option<UsageLimit> {
     })
 }
 
-// --- Tauri commands ---
-
-#[tauri::command]
-pub async fn get_claude_usage() -> Result<Option<ClaudeApiUsage>, String> {
+async fn fetch_usage() -> Result<Option<ClaudeApiUsage>, String> {
     let mut config = load_config();
-    let session_key = match &config.session_key {
-        Some(k) if !k.is_empty() => k.clone(),
+    let session_key = match session_key(&config)? {
+        Some(k) if !k.is_empty() => k,
         _ => return Ok(None),
     };
 
@@ -159,25 +227,601 @@ pub async fn get_claude_usage() -> Result<Option<ClaudeApiUsage>, String> {
     }))
 }
 
+// --- Usage history (config_dir/tiki-desktop/claude-usage-history.jsonl) ---
+
+const HISTORY_RETENTION_DAYS: i64 = 30;
+
+/// Samples closer together than this are dropped, so the sidebar poll and
+/// the background monitor don't both write one.
+const MIN_SAMPLE_INTERVAL_SECS: i64 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageSample {
+    pub at: String,
+    #[serde(flatten)]
+    pub usage: ClaudeApiUsage,
+}
+
+fn history_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("tiki-desktop").join("claude-usage-history.jsonl"))
+}
+
+/// Samples in file order; unreadable lines are skipped.
+fn read_history(path: &Path) -> Vec<UsageSample> {
+    std::fs::read_to_string(path)
+        .map(|c| c.lines().filter_map(|l| serde_json::from_str(l).ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Drop samples older than the retention window.
+fn prune_history(path: &Path, now: DateTime<Utc>) -> Result<(), String> {
+    let cutoff = now - Duration::days(HISTORY_RETENTION_DAYS);
+    let samples = read_history(path);
+    let kept: Vec<String> = samples
+        .iter()
+        .filter(|s| parse_time(&s.at).is_some_and(|t| t >= cutoff))
+        .filter_map(|s| serde_json::to_string(s).ok())
+        .collect();
+    if kept.len() == samples.len() {
+        return Ok(());
+    }
+    let mut content = kept.join("\n");
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    fs_utils::atomic_write(path, &content)
+}
+
+fn append_sample(path: &Path, sample: &UsageSample) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let line = serde_json::to_string(sample).map_err(|e| e.to_string())?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn last_sample_at() -> &'static Mutex<Option<DateTime<Utc>>> {
+    static LAST: OnceLock<Mutex<Option<DateTime<Utc>>>> = OnceLock::new();
+    LAST.get_or_init(|| Mutex::new(None))
+}
+
+/// Append a sample to the history and raise any thresholds it crosses.
+fn record_usage(usage: &ClaudeApiUsage) {
+    let now = Utc::now();
+    let Ok(mut last) = last_sample_at().lock() else {
+        return;
+    };
+    if last.is_some_and(|t| now - t < Duration::seconds(MIN_SAMPLE_INTERVAL_SECS)) {
+        return;
+    }
+    if let Some(path) = history_path() {
+        // Prune once per launch, before the first write.
+        if last.is_none() {
+            if let Err(e) = prune_history(&path, now) {
+                log::warn!("Failed to prune usage history: {}", e);
+            }
+        }
+        let sample = UsageSample {
+            at: now.to_rfc3339(),
+            usage: usage.clone(),
+        };
+        if let Err(e) = append_sample(&path, &sample) {
+            log::warn!("Failed to record usage sample: {}", e);
+        }
+    }
+    *last = Some(now);
+    drop(last);
+
+    let thresholds = load_config().thresholds.unwrap_or_else(default_thresholds);
+    if let Ok(mut alerted) = alerted_thresholds().lock() {
+        for event in crossed_thresholds(&thresholds, usage, &mut alerted) {
+            notify_threshold(&event);
+        }
+    }
+}
+
+// --- Thresholds ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UsageLimitKind {
+    FiveHour,
+    SevenDay,
+    SevenDayOpus,
+    SevenDaySonnet,
+}
+
+impl UsageLimitKind {
+    fn label(self) -> &'static str {
+        match self {
+            UsageLimitKind::FiveHour => "5-hour",
+            UsageLimitKind::SevenDay => "7-day",
+            UsageLimitKind::SevenDayOpus => "7-day Opus",
+            UsageLimitKind::SevenDaySonnet => "7-day Sonnet",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageThreshold {
+    pub limit: UsageLimitKind,
+    /// Utilization percentage (0-100) to alert at.
+    pub percent: f64,
+}
+
+fn default_thresholds() -> Vec<UsageThreshold> {
+    vec![
+        UsageThreshold {
+            limit: UsageLimitKind::FiveHour,
+            percent: 80.0,
+        },
+        UsageThreshold {
+            limit: UsageLimitKind::SevenDay,
+            percent: 90.0,
+        },
+    ]
+}
+
+/// Emitted as `claude-usage-threshold`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageThresholdEvent {
+    pub limit: UsageLimitKind,
+    pub threshold: f64,
+    pub utilization: f64,
+    pub resets_at: Option<String>,
+}
+
+/// Thresholds currently exceeded and already alerted, as (limit, tenths of
+/// a percent). An entry clears once utilization drops back below, e.g. when
+/// the window resets, so each crossing alerts once.
+type AlertedThresholds = HashSet<(UsageLimitKind, u32)>;
+
+fn alerted_thresholds() -> &'static Mutex<AlertedThresholds> {
+    static ALERTED: OnceLock<Mutex<AlertedThresholds>> = OnceLock::new();
+    ALERTED.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+fn crossed_thresholds(
+    thresholds: &[UsageThreshold],
+    usage: &ClaudeApiUsage,
+    alerted: &mut AlertedThresholds,
+) -> Vec<UsageThresholdEvent> {
+    let mut events = Vec::new();
+    for t in thresholds {
+        let Some(limit) = usage.limit(t.limit) else {
+            continue;
+        };
+        let key = (t.limit, (t.percent * 10.0).round() as u32);
+        if limit.utilization < t.percent {
+            alerted.remove(&key);
+        } else if alerted.insert(key) {
+            events.push(UsageThresholdEvent {
+                limit: t.limit,
+                threshold: t.percent,
+                utilization: limit.utilization,
+                resets_at: limit.resets_at.clone(),
+            });
+        }
+    }
+    events
+}
+
+fn app_handle() -> &'static OnceLock<AppHandle> {
+    static APP: OnceLock<AppHandle> = OnceLock::new();
+    &APP
+}
+
+fn notify_threshold(event: &UsageThresholdEvent) {
+    log::warn!(
+        "Claude {} usage at {:.0}% (threshold {:.0}%)",
+        event.limit.label(),
+        event.utilization,
+        event.threshold
+    );
+    let Some(app) = app_handle().get() else {
+        return;
+    };
+    if let Err(e) = app.emit("claude-usage-threshold", event) {
+        log::error!("Failed to emit claude-usage-threshold: {}", e);
+    }
+    let body = format!(
+        "{} limit at {:.0}% (alert set at {:.0}%)",
+        event.limit.label(),
+        event.utilization,
+        event.threshold
+    );
+    if let Err(e) = app.notification().builder().title("Claude usage").body(body).show() {
+        log::warn!("Failed to show usage notification: {}", e);
+    }
+}
+
+// --- Background monitor ---
+
+/// How often usage is sampled while the app runs, sidebar open or not.
+const MONITOR_INTERVAL: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Sample usage in the background so history and alerts don't depend on
+/// the sidebar polling.
+pub fn start_usage_monitor(app: AppHandle) {
+    let _ = app_handle().set(app);
+    std::thread::spawn(|| loop {
+        std::thread::sleep(MONITOR_INTERVAL);
+        if !has_claude_session_key() {
+            continue;
+        }
+        match tauri::async_runtime::block_on(fetch_usage()) {
+            Ok(Some(usage)) => record_usage(&usage),
+            Ok(None) => {}
+            Err(e) => log::debug!("Usage monitor skipped a sample: {}", e),
+        }
+    });
+}
+
+// --- YOLO estimate ---
+
+/// Recently completed issues the estimate averages over.
+const YOLO_SAMPLE_ISSUES: usize = 5;
+
+/// Percentage points of the 5-hour limit kept in reserve.
+const YOLO_SAFETY_MARGIN: f64 = 10.0;
+
+/// With no measured issues, only start below this utilization.
+const YOLO_NO_HISTORY_MAX: f64 = 50.0;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YoloEstimate {
+    pub safe: bool,
+    pub current_percent: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resets_at: Option<String>,
+    /// Average 5-hour percentage points a recent issue used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_percent: Option<f64>,
+    /// Average time Claude spent on a recent issue.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_minutes: Option<f64>,
+    /// Issues the expectation is based on.
+    pub sampled_issues: Vec<u32>,
+    pub reason: String,
+}
+
+/// 5-hour percentage points used between `from` and `to`: the sum of rises
+/// between consecutive samples, so a window reset in between doesn't count
+/// as negative use. `None` without two samples to compare.
+fn five_hour_used(samples: &[UsageSample], from: DateTime<Utc>, to: DateTime<Utc>) -> Option<f64> {
+    // Samples are minutes apart; allow one interval either side of the span.
+    let slack = Duration::seconds(MONITOR_INTERVAL.as_secs() as i64);
+    let points: Vec<f64> = samples
+        .iter()
+        .filter(|s| parse_time(&s.at).is_some_and(|t| t >= from - slack && t <= to + slack))
+        .filter_map(|s| s.usage.five_hour.as_ref().map(|l| l.utilization))
+        .collect();
+    (points.len() >= 2).then(|| points.windows(2).map(|w| (w[1] - w[0]).max(0.0)).sum())
+}
+
+/// Whether a run expected to use `expected_percent` over `expected_minutes`
+/// fits in what's left of the current window. Only the share of the run
+/// before the reset counts against it.
+fn yolo_verdict(
+    current: f64,
+    minutes_to_reset: Option<f64>,
+    expected_percent: f64,
+    expected_minutes: f64,
+) -> (bool, String) {
+    let before_reset = match minutes_to_reset {
+        Some(m) if expected_minutes > 0.0 => (m.max(0.0) / expected_minutes).min(1.0),
+        _ => 1.0,
+    };
+    let needed = expected_percent * before_reset;
+    let available = 100.0 - YOLO_SAFETY_MARGIN - current;
+    if needed <= available {
+        (
+            true,
+            format!(
+                "A run needs about {:.0}% of the 5-hour limit before it resets; {:.0}% is free",
+                needed,
+                100.0 - current
+            ),
+        )
+    } else {
+        (
+            false,
+            format!(
+                "A run needs about {:.0}% of the 5-hour limit before it resets, but only {:.0}% is free",
+                needed,
+                100.0 - current
+            ),
+        )
+    }
+}
+
+/// Per-issue 5-hour usage and duration for recently completed issues,
+/// measured over their Claude sessions.
+fn recent_issue_usage(
+    claude_home: &Path,
+    project: &Path,
+    samples: &[UsageSample],
+) -> Vec<(u32, f64, f64)> {
+    let state = fs_utils::read_json_resilient::<TikiState>(&project.join(".tiki").join("state.json"))
+        .unwrap_or(None);
+    let mut recent = state
+        .and_then(|s| s.history)
+        .and_then(|h| h.recent_issues)
+        .unwrap_or_default();
+    recent.sort_by(|a, b| b.completed_at.cmp(&a.completed_at));
+
+    let mut measured = Vec::new();
+    for issue in recent.iter().take(YOLO_SAMPLE_ISSUES) {
+        let Ok(sessions) = issue_sessions(claude_home, project, issue.number) else {
+            continue;
+        };
+        let (mut used, mut minutes, mut any) = (0.0, 0.0, false);
+        for session in sessions {
+            let start = session.started_at.as_deref().and_then(parse_time);
+            let end = session.ended_at.as_deref().and_then(parse_time);
+            let (Some(start), Some(end)) = (start, end) else {
+                continue;
+            };
+            if let Some(u) = five_hour_used(samples, start, end) {
+                used += u;
+                minutes += (end - start).num_seconds() as f64 / 60.0;
+                any = true;
+            }
+        }
+        if any {
+            measured.push((issue.number, used, minutes));
+        }
+    }
+    measured
+}
+
+// --- Tauri commands ---
+
+#[tauri::command]
+pub async fn get_claude_usage() -> Result<Option<ClaudeApiUsage>, String> {
+    let usage = fetch_usage().await?;
+    if let Some(ref usage) = usage {
+        record_usage(usage);
+    }
+    Ok(usage)
+}
+
+/// Recorded usage samples, oldest first. `range` is "5h", "24h", "7d" or "30d".
+#[tauri::command]
+pub fn get_usage_history(range: String) -> Result<Vec<UsageSample>, String> {
+    let span = match range.as_str() {
+        "5h" => Duration::hours(5),
+        "24h" => Duration::hours(24),
+        "7d" => Duration::days(7),
+        "30d" => Duration::days(30),
+        other => return Err(format!("Unknown range '{}'", other)),
+    };
+    let Some(path) = history_path() else {
+        return Ok(Vec::new());
+    };
+    let since = Utc::now() - span;
+    Ok(read_history(&path)
+        .into_iter()
+        .filter(|s| parse_time(&s.at).is_some_and(|t| t >= since))
+        .collect())
+}
+
+#[tauri::command]
+pub fn get_usage_thresholds() -> Vec<UsageThreshold> {
+    load_config().thresholds.unwrap_or_else(default_thresholds)
+}
+
+#[tauri::command]
+pub fn set_usage_thresholds(thresholds: Vec<UsageThreshold>) -> Result<(), String> {
+    if let Some(t) = thresholds.iter().find(|t| !(t.percent > 0.0 && t.percent <= 100.0)) {
+        return Err(format!("Threshold {}% is out of range (1-100)", t.percent));
+    }
+    let mut config = load_config();
+    config.thresholds = Some(thresholds);
+    save_config(&config)?;
+    // Let the new thresholds alert afresh.
+    if let Ok(mut alerted) = alerted_thresholds().lock() {
+        alerted.clear();
+    }
+    Ok(())
+}
+
+/// Whether a YOLO run fits in the current 5-hour window, judged by how much
+/// of it recent issues used.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub async fn estimate_yolo_run(project_path: Option<String>) -> Result<YoloEstimate, String> {
+    let usage = fetch_usage()
+        .await?
+        .ok_or("Add a Claude session key to estimate plan usage")?;
+    record_usage(&usage);
+    let five_hour = usage
+        .five_hour
+        .clone()
+        .ok_or("Claude didn't report a 5-hour limit for this plan")?;
+    let current = five_hour.utilization;
+    let minutes_to_reset = five_hour
+        .resets_at
+        .as_deref()
+        .and_then(parse_time)
+        .map(|t| (t - Utc::now()).num_seconds() as f64 / 60.0);
+
+    let project = fs_utils::project_root(project_path.as_deref())?;
+    let measured = tauri::async_runtime::spawn_blocking(move || {
+        let samples = history_path().map(|p| read_history(&p)).unwrap_or_default();
+        match claude_home() {
+            Some(home) => recent_issue_usage(&home, &project, &samples),
+            None => Vec::new(),
+        }
+    })
+    .await
+    .map_err(|e| format!("Estimate task failed: {}", e))?;
+
+    if measured.is_empty() {
+        let safe = current <= YOLO_NO_HISTORY_MAX;
+        return Ok(YoloEstimate {
+            safe,
+            current_percent: current,
+            resets_at: five_hour.resets_at,
+            expected_percent: None,
+            expected_minutes: None,
+            sampled_issues: Vec::new(),
+            reason: format!(
+                "No recorded usage for recent issues yet; {:.0}% of the 5-hour limit is in use",
+                current
+            ),
+        });
+    }
+    let n = measured.len() as f64;
+    let expected_percent = measured.iter().map(|m| m.1).sum::<f64>() / n;
+    let expected_minutes = measured.iter().map(|m| m.2).sum::<f64>() / n;
+    let (safe, reason) = yolo_verdict(current, minutes_to_reset, expected_percent, expected_minutes);
+    Ok(YoloEstimate {
+        safe,
+        current_percent: current,
+        resets_at: five_hour.resets_at,
+        expected_percent: Some(expected_percent),
+        expected_minutes: Some(expected_minutes),
+        sampled_issues: measured.iter().map(|m| m.0).collect(),
+        reason,
+    })
+}
+
 #[tauri::command]
 pub fn save_claude_session_key(key: String) -> Result<(), String> {
+    // Load first so a leftover plaintext key migrates before it's replaced.
     let mut config = load_config();
-    config.session_key = Some(key);
+    credentials::set_secret(CLAUDE_SESSION_KEY, &key)?;
+    config.session_key = None;
     config.org_id = None; // Clear cached org when key changes
     save_config(&config)
 }
 
 #[tauri::command]
 pub fn has_claude_session_key() -> bool {
-    load_config()
-        .session_key
-        .map_or(false, |k| !k.is_empty())
+    let config = load_config();
+    config.session_key.is_some() || credentials::has_secret(CLAUDE_SESSION_KEY)
 }
 
 #[tauri::command]
 pub fn clear_claude_session_key() -> Result<(), String> {
     let mut config = load_config();
+    credentials::delete_secret(CLAUDE_SESSION_KEY)?;
+    if config.session_key.is_some() {
+        if let Some(path) = config_path() {
+            credentials::scrub_file(&path)?;
+        }
+    }
     config.session_key = None;
     config.org_id = None;
     save_config(&config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(five_hour: f64, seven_day: f64) -> ClaudeApiUsage {
+        let limit = |utilization| {
+            Some(UsageLimit {
+                utilization,
+                resets_at: None,
+            })
+        };
+        ClaudeApiUsage {
+            five_hour: limit(five_hour),
+            seven_day: limit(seven_day),
+            seven_day_opus: None,
+            seven_day_sonnet: None,
+        }
+    }
+
+    #[test]
+    fn alerts_once_per_threshold_crossing() {
+        let thresholds = default_thresholds();
+        let mut alerted = HashSet::new();
+
+        assert!(crossed_thresholds(&thresholds, &usage(50.0, 10.0), &mut alerted).is_empty());
+        let events = crossed_thresholds(&thresholds, &usage(82.0, 10.0), &mut alerted);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].limit, UsageLimitKind::FiveHour);
+        // Still over: no repeat.
+        assert!(crossed_thresholds(&thresholds, &usage(90.0, 10.0), &mut alerted).is_empty());
+        // Window reset, then crossed again.
+        assert!(crossed_thresholds(&thresholds, &usage(5.0, 10.0), &mut alerted).is_empty());
+        assert_eq!(crossed_thresholds(&thresholds, &usage(85.0, 95.0), &mut alerted).len(), 2);
+    }
+
+    #[test]
+    fn unmigrated_session_key_survives_saves_while_locked() {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("tiki-claude-api-{}", nanos));
+        let path = dir.join("claude-api.json");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&path, r#"{"sessionKey":"sk-ant-secret","orgId":null}"#).unwrap();
+
+        let locked = |_: &str| Err::<(), String>("Credential store is locked.".to_string());
+        let mut config = load_config_at(&path, locked);
+        assert_eq!(config.session_key.as_deref(), Some("sk-ant-secret"));
+        // The org_id cache fill and set_usage_thresholds both save like this.
+        config.org_id = Some("org-1".to_string());
+        save_config_at(&path, &config).unwrap();
+        let config = load_config_at(&path, locked);
+        assert_eq!(config.session_key.as_deref(), Some("sk-ant-secret"));
+        assert_eq!(config.org_id.as_deref(), Some("org-1"));
+
+        // Once the store takes the key, it leaves the file.
+        let mut stored = None;
+        let config = load_config_at(&path, |key| {
+            stored = Some(key.to_string());
+            Ok(())
+        });
+        assert_eq!(stored.as_deref(), Some("sk-ant-secret"));
+        assert_eq!(config.session_key, None);
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(!raw.contains("sk-ant-secret"));
+        assert!(raw.contains("org-1"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn estimates_yolo_headroom_from_history() {
+        let sample = |at: &str, five_hour: f64| UsageSample {
+            at: at.to_string(),
+            usage: usage(five_hour, 0.0),
+        };
+        let samples = vec![
+            sample("2026-03-01T09:00:00Z", 10.0),
+            sample("2026-03-01T10:00:00Z", 20.0),
+            sample("2026-03-01T11:00:00Z", 60.0),
+            // Window reset mid-run; the drop isn't negative use.
+            sample("2026-03-01T12:00:00Z", 5.0),
+            sample("2026-03-01T13:00:00Z", 25.0),
+            sample("2026-03-02T09:00:00Z", 90.0),
+        ];
+        let at = |ts: &str| parse_time(ts).unwrap();
+        assert_eq!(
+            five_hour_used(&samples, at("2026-03-01T10:00:00Z"), at("2026-03-01T13:00:00Z")),
+            Some(60.0)
+        );
+        assert_eq!(five_hour_used(&samples, at("2026-03-02T09:00:00Z"), at("2026-03-02T09:01:00Z")), None);
+
+        assert!(yolo_verdict(20.0, Some(300.0), 50.0, 60.0).0);
+        assert!(!yolo_verdict(60.0, Some(300.0), 50.0, 60.0).0);
+        // Only a tenth of the run lands before the reset.
+        assert!(yolo_verdict(60.0, Some(6.0), 50.0, 60.0).0);
+    }
+}
@@ -2262,6 +2262,7 @@ mod ipc_serialization {
             audit_passed: if with_optionals { Some(true) } else { None },
             yolo: None,
             commit: None,
+            pull_request: None,
             parent_release: None,
         }
     }
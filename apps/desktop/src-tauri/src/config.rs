@@ -81,6 +81,75 @@ pub struct ChangelogConfig {
     pub extra: HashMap<String, serde_json::Value>,
 }
 
+/// Git branch and worktree conventions used by the desktop's git commands.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GitConfig {
+    /// Branch name template for `create_issue_branch`. Placeholders:
+    /// `{number}` and `{slug}` (the slugified issue title).
+    #[serde(default, rename = "branchTemplate", skip_serializing_if = "Option::is_none")]
+    pub branch_template: Option<String>,
+    /// Directory (relative to the project root) that holds per-issue worktrees.
+    #[serde(default, rename = "worktreeDir", skip_serializing_if = "Option::is_none")]
+    pub worktree_dir: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// How the desktop talks to GitHub for this project.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GitHubConfig {
+    /// Transport for issue/PR/label/release data: "gh" (the gh CLI, default)
+    /// or "api" (direct REST/GraphQL with a personal access token or gh's token).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+    /// GitHub host for this project (e.g. a GitHub Enterprise Server
+    /// hostname). Defaults to whatever gh infers from the git remotes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// "owner/repo" to target instead of the repo gh picks from the remotes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<GitHubProjectConfig>,
+    /// Label taxonomy applied by `sync_github_labels`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<LabelTemplate>>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// One label in the `github.labels` template.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LabelTemplate {
+    pub name: String,
+    /// Hex color, with or without a leading `#`.
+    pub color: String,
+    /// Left untouched on existing labels when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// GitHub Projects (v2) board that mirrors Tiki work status.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GitHubProjectConfig {
+    /// Project number (from the board URL, `.../projects/<number>`).
+    pub number: u32,
+    /// User or organization that owns the board. Defaults to the repo owner.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// Single-select field to set. Defaults to "Status".
+    #[serde(default, rename = "statusField", skip_serializing_if = "Option::is_none")]
+    pub status_field: Option<String>,
+    /// Work status (e.g. "executing") → option name on the board. Unmapped
+    /// statuses use the Todo / In Progress / Done defaults.
+    #[serde(default, rename = "statusMap", skip_serializing_if = "Option::is_none")]
+    pub status_map: Option<HashMap<String, String>>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
 /// Project-level Tiki configuration (`.tiki/config.json`).
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TikiConfig {
@@ -90,6 +159,10 @@ pub struct TikiConfig {
     pub changelog: Option<ChangelogConfig>,
     #[serde(default, rename = "backupRetention", skip_serializing_if = "Option::is_none")]
     pub backup_retention: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github: Option<GitHubConfig>,
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
 }
@@ -137,6 +210,28 @@ fn collect_config_warnings(config: &TikiConfig) -> Vec<String> {
         }
     }
 
+    if let Some(git) = &config.git {
+        for k in git.extra.keys() {
+            warnings.push(format!("git.{}", k));
+        }
+    }
+
+    if let Some(github) = &config.github {
+        for k in github.extra.keys() {
+            warnings.push(format!("github.{}", k));
+        }
+        if let Some(project) = &github.project {
+            for k in project.extra.keys() {
+                warnings.push(format!("github.project.{}", k));
+            }
+        }
+        for (i, label) in github.labels.iter().flatten().enumerate() {
+            for k in label.extra.keys() {
+                warnings.push(format!("github.labels.{}.{}", i, k));
+            }
+        }
+    }
+
     warnings.sort();
     warnings
 }
@@ -156,6 +251,16 @@ pub fn read_tiki_config(tiki_path: Option<String>) -> Result<ConfigReadResult, S
     Ok(ConfigReadResult { config, warnings })
 }
 
+/// Load `.tiki/config.json` for internal consumers (git, github). Missing or
+/// unreadable config falls back to the default so callers can apply their own
+/// per-field defaults without surfacing config errors from unrelated commands.
+pub(crate) fn load_tiki_config(tiki_path: &std::path::Path) -> TikiConfig {
+    fs_utils::read_json_resilient::<TikiConfig>(&tiki_path.join("config.json"))
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+}
+
 /// Validate and save `.tiki/config.json` via atomic write.
 ///
 /// The incoming JSON is round-tripped through the typed `TikiConfig` struct: a
@@ -261,6 +366,38 @@ mod config_tests {
         assert!(warnings.contains(&"topLevelUnknown".to_string()));
     }
 
+    #[test]
+    fn parses_git_section() {
+        let json = serde_json::json!({
+            "git": { "branchTemplate": "feat/{number}-{slug}", "worktreeDir": "../wt" }
+        });
+        let cfg: TikiConfig = serde_json::from_value(json).expect("parse");
+        let git = cfg.git.as_ref().expect("git present");
+        assert_eq!(git.branch_template.as_deref(), Some("feat/{number}-{slug}"));
+        assert_eq!(git.worktree_dir.as_deref(), Some("../wt"));
+        assert!(collect_config_warnings(&cfg).is_empty());
+    }
+
+    #[test]
+    fn parses_github_section() {
+        let json = serde_json::json!({ "github": { "backend": "api", "token": "nope" } });
+        let cfg: TikiConfig = serde_json::from_value(json).expect("parse");
+        assert_eq!(cfg.github.as_ref().and_then(|g| g.backend.as_deref()), Some("api"));
+        assert_eq!(collect_config_warnings(&cfg), vec!["github.token".to_string()]);
+
+        let json = serde_json::json!({
+            "github": { "project": { "number": 3, "statusMap": { "failed": "Blocked" }, "colour": 1 } }
+        });
+        let cfg: TikiConfig = serde_json::from_value(json).expect("parse");
+        let project = cfg.github.as_ref().and_then(|g| g.project.as_ref()).expect("project");
+        assert_eq!(project.number, 3);
+        assert_eq!(
+            project.status_map.as_ref().and_then(|m| m.get("failed")).map(String::as_str),
+            Some("Blocked")
+        );
+        assert_eq!(collect_config_warnings(&cfg), vec!["github.project.colour".to_string()]);
+    }
+
     #[test]
     fn rejects_type_mismatch() {
         let json = serde_json::json!({
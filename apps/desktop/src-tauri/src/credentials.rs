@@ -0,0 +1,431 @@
+//! Storage for secrets kept on the user's behalf: the claude.ai session key
+//! and GitHub personal access tokens.
+//!
+//! Secrets live in the OS keyring (Secret Service on Linux, Keychain on
+//! macOS, Credential Manager on Windows). When no keyring is reachable, e.g.
+//! headless Linux without a Secret Service daemon, they go to an encrypted
+//! file in the config dir instead: AES-256-GCM under a key derived from a
+//! user passphrase with Argon2id. That file starts locked every launch;
+//! `unlock_credential_store` sets the passphrase the first time and checks it
+//! afterwards, and the derived key is only ever held in memory.
+
+use crate::fs_utils;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Keyring service name all entries are stored under.
+const SERVICE: &str = "tiki-desktop";
+
+/// Account for the claude.ai session key.
+pub(crate) const CLAUDE_SESSION_KEY: &str = "claude-session-key";
+
+/// Account for a GitHub personal access token.
+pub(crate) fn github_token_account(host: &str) -> String {
+    format!("github-token:{}", host)
+}
+
+const LOCKED: &str = "Credential store is locked. Unlock it with your passphrase.";
+
+// --- Backend selection ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CredentialBackend {
+    Keyring,
+    EncryptedFile,
+}
+
+/// Probed once per launch. `TIKI_CREDENTIAL_STORE=file` skips the keyring.
+fn backend() -> CredentialBackend {
+    static BACKEND: OnceLock<CredentialBackend> = OnceLock::new();
+    *BACKEND.get_or_init(|| {
+        if std::env::var("TIKI_CREDENTIAL_STORE").is_ok_and(|v| v == "file") {
+            return CredentialBackend::EncryptedFile;
+        }
+        match keyring::Entry::new(SERVICE, "probe").and_then(|e| e.get_password()) {
+            Ok(_) | Err(keyring::Error::NoEntry) => CredentialBackend::Keyring,
+            Err(e) => {
+                log::warn!("OS keyring unavailable ({}); using the encrypted credential file", e);
+                CredentialBackend::EncryptedFile
+            }
+        }
+    })
+}
+
+fn keyring_entry(account: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(SERVICE, account).map_err(|e| format!("Keyring error: {}", e))
+}
+
+// --- Encrypted file ---
+
+/// Argon2id cost, stored with the file so it can be raised later without
+/// breaking existing files.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KdfParams {
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+/// OWASP's baseline for Argon2id.
+const DEFAULT_KDF: KdfParams = KdfParams {
+    memory_kib: 19 * 1024,
+    iterations: 2,
+    parallelism: 1,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Sealed {
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Vault {
+    version: u32,
+    salt: String,
+    kdf: KdfParams,
+    /// A known value sealed under the key, to tell a wrong passphrase apart
+    /// from a damaged entry.
+    check: Sealed,
+    /// Account → sealed secret. Account names aren't secret, so presence can
+    /// be answered while locked.
+    #[serde(default)]
+    entries: BTreeMap<String, Sealed>,
+}
+
+type Key = [u8; 32];
+
+const CHECK_ACCOUNT: &str = "check";
+const CHECK_VALUE: &str = "tiki-desktop";
+
+fn vault_path() -> Result<PathBuf, String> {
+    dirs::config_dir()
+        .map(|d| d.join("tiki-desktop").join("credentials.enc.json"))
+        .ok_or_else(|| "No config directory".to_string())
+}
+
+/// The key derived at unlock, for this launch only.
+fn vault_key() -> &'static Mutex<Option<Key>> {
+    static KEY: OnceLock<Mutex<Option<Key>>> = OnceLock::new();
+    KEY.get_or_init(|| Mutex::new(None))
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], String> {
+    let mut bytes = [0u8; N];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("No secure randomness: {}", e))?;
+    Ok(bytes)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], kdf: &KdfParams) -> Result<Key, String> {
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(32))
+        .map_err(|e| format!("Bad key derivation parameters: {}", e))?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+/// Encrypt `secret`, binding it to `account` so entries can't be swapped.
+fn seal(key: &Key, account: &str, secret: &str) -> Result<Sealed, String> {
+    let nonce = random_bytes::<12>()?;
+    let payload = Payload {
+        msg: secret.as_bytes(),
+        aad: account.as_bytes(),
+    };
+    let ciphertext = Aes256Gcm::new(key.into())
+        .encrypt(Nonce::from_slice(&nonce), payload)
+        .map_err(|_| "Encryption failed".to_string())?;
+    Ok(Sealed {
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+fn open(key: &Key, account: &str, sealed: &Sealed) -> Result<String, String> {
+    let nonce = BASE64.decode(&sealed.nonce).map_err(|e| e.to_string())?;
+    let ciphertext = BASE64.decode(&sealed.ciphertext).map_err(|e| e.to_string())?;
+    if nonce.len() != 12 {
+        return Err(format!("Damaged credential entry '{}'", account));
+    }
+    let payload = Payload {
+        msg: &ciphertext,
+        aad: account.as_bytes(),
+    };
+    let plain = Aes256Gcm::new(key.into())
+        .decrypt(Nonce::from_slice(&nonce), payload)
+        .map_err(|_| format!("Couldn't decrypt credential '{}'", account))?;
+    String::from_utf8(plain).map_err(|e| e.to_string())
+}
+
+fn read_vault(path: &Path) -> Result<Option<Vault>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| format!("Damaged credential file {}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
+    }
+}
+
+fn write_vault(path: &Path, vault: &Vault) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(vault).map_err(|e| e.to_string())?;
+    fs_utils::atomic_write(path, &json)
+}
+
+/// Open the file at `path` with `passphrase`, creating it if missing.
+fn unlock_vault(path: &Path, passphrase: &str, kdf: KdfParams) -> Result<Key, String> {
+    if let Some(vault) = read_vault(path)? {
+        let salt = BASE64.decode(&vault.salt).map_err(|e| e.to_string())?;
+        let key = derive_key(passphrase, &salt, &vault.kdf)?;
+        return match open(&key, CHECK_ACCOUNT, &vault.check) {
+            Ok(v) if v == CHECK_VALUE => Ok(key),
+            _ => Err("Wrong passphrase".to_string()),
+        };
+    }
+    if passphrase.chars().count() < 8 {
+        return Err("Passphrase must be at least 8 characters".to_string());
+    }
+    let salt = random_bytes::<16>()?;
+    let key = derive_key(passphrase, &salt, &kdf)?;
+    let vault = Vault {
+        version: 1,
+        salt: BASE64.encode(salt),
+        kdf,
+        check: seal(&key, CHECK_ACCOUNT, CHECK_VALUE)?,
+        entries: BTreeMap::new(),
+    };
+    write_vault(path, &vault)?;
+    Ok(key)
+}
+
+fn vault_get(path: &Path, key: &Key, account: &str) -> Result<Option<String>, String> {
+    let Some(vault) = read_vault(path)? else {
+        return Ok(None);
+    };
+    vault.entries.get(account).map(|s| open(key, account, s)).transpose()
+}
+
+fn vault_set(path: &Path, key: &Key, account: &str, secret: &str) -> Result<(), String> {
+    let mut vault = read_vault(path)?.ok_or(LOCKED)?;
+    vault.entries.insert(account.to_string(), seal(key, account, secret)?);
+    write_vault(path, &vault)
+}
+
+fn vault_delete(path: &Path, account: &str) -> Result<(), String> {
+    let Some(mut vault) = read_vault(path)? else {
+        return Ok(());
+    };
+    if vault.entries.remove(account).is_some() {
+        write_vault(path, &vault)?;
+    }
+    Ok(())
+}
+
+fn current_key() -> Result<Key, String> {
+    vault_key()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| LOCKED.to_string())
+}
+
+// --- Store API ---
+
+pub(crate) fn get_secret(account: &str) -> Result<Option<String>, String> {
+    match backend() {
+        CredentialBackend::Keyring => match keyring_entry(account)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("Keyring error: {}", e)),
+        },
+        CredentialBackend::EncryptedFile => {
+            let path = vault_path()?;
+            if !has_secret(account) {
+                return Ok(None);
+            }
+            vault_get(&path, &current_key()?, account)
+        }
+    }
+}
+
+pub(crate) fn set_secret(account: &str, secret: &str) -> Result<(), String> {
+    match backend() {
+        CredentialBackend::Keyring => keyring_entry(account)?
+            .set_password(secret)
+            .map_err(|e| format!("Keyring error: {}", e)),
+        CredentialBackend::EncryptedFile => vault_set(&vault_path()?, &current_key()?, account, secret),
+    }
+}
+
+pub(crate) fn delete_secret(account: &str) -> Result<(), String> {
+    match backend() {
+        CredentialBackend::Keyring => match keyring_entry(account)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Keyring error: {}", e)),
+        },
+        CredentialBackend::EncryptedFile => vault_delete(&vault_path()?, account),
+    }
+}
+
+/// Whether a secret is stored for `account`; answers while locked.
+pub(crate) fn has_secret(account: &str) -> bool {
+    match backend() {
+        CredentialBackend::Keyring => {
+            keyring_entry(account).is_ok_and(|e| e.get_password().is_ok())
+        }
+        CredentialBackend::EncryptedFile => vault_path()
+            .and_then(|p| read_vault(&p))
+            .ok()
+            .flatten()
+            .is_some_and(|v| v.entries.contains_key(account)),
+    }
+}
+
+/// Overwrite a file that held plaintext secrets, then remove it. Best effort
+/// against recovery: journaling and copy-on-write filesystems may keep the
+/// old blocks around regardless.
+pub(crate) fn scrub_file(path: &Path) -> Result<(), String> {
+    let len = match std::fs::metadata(path) {
+        Ok(meta) => meta.len() as usize,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(format!("{}: {}", path.display(), e)),
+    };
+    {
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .open(path)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        file.write_all(&vec![0u8; len])
+            .and_then(|_| file.sync_all())
+            .map_err(|e| format!("Failed to scrub {}: {}", path.display(), e))?;
+    }
+    std::fs::remove_file(path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))
+}
+
+// --- Tauri commands ---
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialStoreStatus {
+    pub backend: CredentialBackend,
+    /// Encrypted file only: a passphrase has been set.
+    pub initialized: bool,
+    /// Always true for the keyring.
+    pub unlocked: bool,
+}
+
+#[tauri::command]
+pub fn get_credential_store_status() -> Result<CredentialStoreStatus, String> {
+    let backend = backend();
+    Ok(match backend {
+        CredentialBackend::Keyring => CredentialStoreStatus {
+            backend,
+            initialized: true,
+            unlocked: true,
+        },
+        CredentialBackend::EncryptedFile => CredentialStoreStatus {
+            backend,
+            initialized: vault_path()?.exists(),
+            unlocked: current_key().is_ok(),
+        },
+    })
+}
+
+/// Unlock the encrypted credential file, setting `passphrase` as its
+/// passphrase if the file doesn't exist yet. A no-op with the OS keyring.
+#[tauri::command]
+pub async fn unlock_credential_store(passphrase: String) -> Result<(), String> {
+    if backend() == CredentialBackend::Keyring {
+        return Ok(());
+    }
+    let key = tauri::async_runtime::spawn_blocking(move || {
+        unlock_vault(&vault_path()?, &passphrase, DEFAULT_KDF)
+    })
+    .await
+    .map_err(|e| format!("Unlock task failed: {}", e))??;
+    *vault_key().lock().map_err(|e| e.to_string())? = Some(key);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    /// Cheap parameters; the real cost would slow the suite down.
+    const TEST_KDF: KdfParams = KdfParams {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    fn temp_path(tag: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir()
+            .join(format!("tiki-credentials-{}-{}", tag, nanos))
+            .join("credentials.enc.json")
+    }
+
+    #[test]
+    fn encrypted_file_round_trip() {
+        let path = temp_path("vault");
+        assert!(unlock_vault(&path, "short", TEST_KDF).is_err());
+        let key = unlock_vault(&path, "correct horse", TEST_KDF).unwrap();
+
+        vault_set(&path, &key, CLAUDE_SESSION_KEY, "sk-ant-secret").unwrap();
+        vault_set(&path, &key, &github_token_account("github.com"), "ghp_token").unwrap();
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(!raw.contains("sk-ant-secret"));
+        assert!(!raw.contains("ghp_token"));
+
+        // Reopening derives the same key from the stored salt.
+        let key = unlock_vault(&path, "correct horse", TEST_KDF).unwrap();
+        assert_eq!(
+            vault_get(&path, &key, CLAUDE_SESSION_KEY).unwrap().as_deref(),
+            Some("sk-ant-secret")
+        );
+        assert_eq!(unlock_vault(&path, "wrong horse", TEST_KDF), Err("Wrong passphrase".to_string()));
+
+        // An entry moved under another account fails authentication.
+        let mut vault = read_vault(&path).unwrap().unwrap();
+        let sealed = vault.entries[CLAUDE_SESSION_KEY].clone();
+        vault.entries.insert("other".to_string(), sealed);
+        write_vault(&path, &vault).unwrap();
+        assert!(vault_get(&path, &key, "other").is_err());
+
+        vault_delete(&path, CLAUDE_SESSION_KEY).unwrap();
+        assert_eq!(vault_get(&path, &key, CLAUDE_SESSION_KEY).unwrap(), None);
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn scrubs_plaintext_files() {
+        let path = temp_path("scrub");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, r#"{"sessionKey":"sk-ant-secret"}"#).unwrap();
+        scrub_file(&path).unwrap();
+        assert!(!path.exists());
+        // Already gone is fine.
+        scrub_file(&path).unwrap();
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
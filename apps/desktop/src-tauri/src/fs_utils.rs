@@ -3,6 +3,15 @@ use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+/// The project directory a command works on: `project_path` when given,
+/// otherwise the current working directory.
+pub(crate) fn project_root(project_path: Option<&str>) -> Result<PathBuf, String> {
+    match project_path {
+        Some(p) => Ok(PathBuf::from(p)),
+        None => std::env::current_dir().map_err(|e| e.to_string()),
+    }
+}
+
 /// Read and JSON-parse a file with retry tolerance for atomic-write races.
 ///
 /// Returns `Ok(None)` immediately if the path doesn't exist (the legitimate
@@ -1,6 +1,6 @@
 use std::process::Command;
 
-use super::hidden_command;
+use super::{gh_command, hidden_command, GhTarget};
 
 // ─── CLI Check Commands ───────────────────────────────────────────────────────
 
@@ -28,6 +28,14 @@ impl ClaudeCli {
         cmd.args(&self.prefix_args);
         cmd
     }
+
+    /// Launcher + prefix args as one argv, for spawners other than `Command`
+    /// (e.g. a PTY `CommandBuilder`).
+    pub(crate) fn argv(&self) -> Vec<String> {
+        std::iter::once(self.program.clone())
+            .chain(self.prefix_args.iter().cloned())
+            .collect()
+    }
 }
 
 /// Build a `ClaudeCli` for a concrete file path, picking the right launcher:
@@ -117,6 +125,16 @@ pub(crate) fn resolve_claude_cli() -> Option<ClaudeCli> {
     })
 }
 
+/// `resolve_claude_cli`, or an actionable error for commands that need it.
+pub(crate) fn require_claude_cli() -> Result<ClaudeCli, String> {
+    resolve_claude_cli().ok_or_else(|| {
+        "Claude CLI not found. Install it from https://github.com/anthropics/claude-code, \
+         then make sure it's on your PATH. (Checked PATH and common install locations \
+         such as the npm global directory and ~/.local/bin.)"
+            .to_string()
+    })
+}
+
 /// Check if Claude CLI is installed and accessible.
 /// Returns `Ok(true)` if a working `claude` was found (on PATH or in a known
 /// install location), `Ok(false)` otherwise.
@@ -128,18 +146,24 @@ pub fn check_claude_cli() -> Result<bool, String> {
 /// Check if gh CLI is authenticated
 /// Returns Ok(true) if authenticated, Ok(false) if not authenticated,
 /// Err if gh CLI is not installed
+/// - project_path: Optional project directory. When its `.tiki/config.json`
+///   sets `github.host`, only that host's login is checked.
 #[tauri::command]
-pub fn check_gh_auth() -> Result<bool, String> {
-    let output = hidden_command("gh")
-        .args(["auth", "status"])
-        .output()
-        .map_err(|e| {
-            if e.kind() == std::io::ErrorKind::NotFound {
-                "GitHub CLI (gh) is not installed. Please install it from https://cli.github.com/".to_string()
-            } else {
-                format!("Failed to run gh CLI: {}", e)
-            }
-        })?;
+pub fn check_gh_auth(project_path: Option<String>) -> Result<bool, String> {
+    let target = GhTarget::for_project(project_path.as_deref());
+    let mut cmd = gh_command(project_path.as_deref());
+    cmd.args(["auth", "status"]);
+    if let Some(ref host) = target.host {
+        cmd.args(["--hostname", host]);
+    }
+
+    let output = cmd.output().map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            "GitHub CLI (gh) is not installed. Please install it from https://cli.github.com/".to_string()
+        } else {
+            format!("Failed to run gh CLI: {}", e)
+        }
+    })?;
 
     Ok(output.status.success())
 }
@@ -0,0 +1,511 @@
+//! Pluggable transport for the GitHub data paths.
+//!
+//! `GitHubBackend` covers the operations the issue/PR/label/release views and
+//! the cache layer need. Two implementations exist:
+//! - `GhCliBackend` (default) shells out to `gh`, relying on its auth and
+//!   repo detection;
+//! - `RestBackend` (`rest.rs`) talks to the REST and GraphQL APIs directly
+//!   with reqwest, using a stored personal access token or gh's own token.
+//!
+//! The backend is chosen per project via `github.backend` in
+//! `.tiki/config.json` ("gh" or "api"). Workflow commands that wrap richer gh
+//! behavior (PR create/merge, reviews, checks) still use the CLI directly.
+
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    gh_command, interpret_probe, query_gh_rate_limit, run_gh_scheduled, run_gh_with_priority,
+    run_gh_with_retry, run_gh_with_retry_accepting, GhBucket, GhPriority,
+    GhTarget, GitHubComment, DEFAULT_GH_HOST,
+    GitHubIssue, GitHubPullRequest, GitHubRelease, LabelInfo, Probe, RateLimitStatus, RestBackend,
+};
+use crate::config::load_tiki_config;
+use crate::fs_utils::project_root;
+
+/// A new issue for `GitHubBackend::create_issue`.
+#[derive(Debug, Clone, Default)]
+pub struct NewIssue {
+    pub title: String,
+    pub body: Option<String>,
+    pub labels: Vec<String>,
+    pub assignees: Vec<String>,
+    /// Milestone title.
+    pub milestone: Option<String>,
+}
+
+/// Fields to change in `GitHubBackend::edit_issue`. `None`/empty = unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct IssueEdit {
+    pub title: Option<String>,
+    pub body: Option<String>,
+    pub add_labels: Vec<String>,
+    pub remove_labels: Vec<String>,
+}
+
+/// Fields to change in `GitHubBackend::update_label`. `None` = unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LabelEdit {
+    pub new_name: Option<String>,
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Mutations take the scheduler priority to run at: `User` for direct
+/// actions, `Background` for bulk jobs that must leave quota in reserve.
+pub(crate) trait GitHubBackend: Send + Sync {
+    /// Issues in `state` ("open", "closed", "all"), newest first. Excludes PRs.
+    fn list_issues(&self, state: &str, limit: u32) -> Result<Vec<GitHubIssue>, String>;
+    fn get_issue(&self, number: u32) -> Result<GitHubIssue, String>;
+    fn create_issue(&self, issue: &NewIssue, priority: GhPriority) -> Result<GitHubIssue, String>;
+    fn edit_issue(&self, number: u32, edit: &IssueEdit, priority: GhPriority) -> Result<(), String>;
+    fn close_issue(&self, number: u32, priority: GhPriority) -> Result<(), String>;
+    fn list_issue_comments(&self, number: u32) -> Result<Vec<GitHubComment>, String>;
+    fn post_issue_comment(&self, number: u32, body: &str, priority: GhPriority) -> Result<(), String>;
+
+    /// Every label in the repo.
+    fn list_labels(&self) -> Result<Vec<LabelInfo>, String>;
+    fn create_label(&self, label: &LabelInfo, priority: GhPriority) -> Result<(), String>;
+    fn update_label(&self, name: &str, edit: &LabelEdit, priority: GhPriority) -> Result<(), String>;
+    fn delete_label(&self, name: &str, priority: GhPriority) -> Result<(), String>;
+    fn list_releases(&self, limit: u32) -> Result<Vec<GitHubRelease>, String>;
+    /// PRs in `state` ("open", "closed", "merged", "all") with check rollups.
+    fn list_pull_requests(&self, state: &str, limit: u32)
+        -> Result<Vec<GitHubPullRequest>, String>;
+
+    /// Run a GraphQL query with `$owner`/`$name` bound to the current repo.
+    /// Variables keep their JSON type. Returns the raw JSON response body
+    /// (`{ data, errors }`).
+    fn graphql(
+        &self,
+        query: &str,
+        variables: &[(&str, serde_json::Value)],
+        priority: GhPriority,
+    ) -> Result<Vec<u8>, String>;
+
+    /// Conditional GET of a REST endpoint (`{owner}`/`{repo}` placeholders
+    /// allowed) for the cache layer's ETag revalidation.
+    fn probe(&self, endpoint: &str, etag: Option<&str>) -> Result<Probe, String>;
+
+    /// Current rate-limit snapshot. Must NOT go through the scheduler (it
+    /// seeds the scheduler); the endpoint is free.
+    fn rate_limit(&self) -> Result<RateLimitStatus, String>;
+
+    /// Host whose quotas this backend's requests draw from.
+    fn quota_host(&self) -> String;
+}
+
+/// Backend names accepted in `github.backend`.
+pub const GITHUB_BACKENDS: &[&str] = &["gh", "api"];
+
+/// Resolve the configured backend for a project.
+pub(crate) fn backend_for(project_path: Option<&str>) -> Result<Box<dyn GitHubBackend>, String> {
+    let root = project_root(project_path)?;
+    let backend = load_tiki_config(&root.join(".tiki"))
+        .github
+        .and_then(|g| g.backend)
+        .unwrap_or_else(|| "gh".to_string());
+
+    match backend.as_str() {
+        "gh" => Ok(Box::new(GhCliBackend {
+            project_path: project_path.map(str::to_string),
+        })),
+        "api" => Ok(Box::new(RestBackend::for_project(&root)?)),
+        other => Err(format!(
+            "Unknown github.backend '{}' in .tiki/config.json (expected one of: {})",
+            other,
+            GITHUB_BACKENDS.join(", ")
+        )),
+    }
+}
+
+// ─── gh CLI Backend ───────────────────────────────────────────────────────────
+
+/// The original transport: every call is a `gh` subprocess run in the
+/// project directory, so gh resolves the repo from the git remote unless
+/// `github.host`/`github.repo` pin it (see `target.rs`).
+pub(crate) struct GhCliBackend {
+    pub(crate) project_path: Option<String>,
+}
+
+impl GhCliBackend {
+    fn command(&self) -> Command {
+        gh_command(self.project_path.as_deref())
+    }
+
+    /// Run a mutation through the scheduler at `priority`, with the same
+    /// secondary-rate-limit retries and error mapping as every other call.
+    /// Errors name the action ("Failed to create issue: …").
+    fn mutate(
+        &self,
+        priority: GhPriority,
+        args: &[String],
+        action: &str,
+    ) -> Result<std::process::Output, String> {
+        run_gh_with_priority(priority, || {
+            let mut cmd = self.command();
+            cmd.args(args);
+            cmd
+        })
+        .map_err(|e| format!("Failed to {}: {}", action, e))
+    }
+}
+
+impl GitHubBackend for GhCliBackend {
+    fn list_issues(&self, state: &str, limit: u32) -> Result<Vec<GitHubIssue>, String> {
+        let output = run_gh_with_retry(|| {
+            let mut cmd = self.command();
+            cmd.args([
+                "issue",
+                "list",
+                "--json",
+                "number,title,body,state,labels,url,createdAt,updatedAt",
+                "--state",
+                state,
+                "--limit",
+                &limit.to_string(),
+            ]);
+            cmd
+        })?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        serde_json::from_str(&stdout).map_err(|e| format!("Failed to parse issues: {}", e))
+    }
+
+    fn get_issue(&self, number: u32) -> Result<GitHubIssue, String> {
+        let output = run_gh_with_retry(|| {
+            let mut cmd = self.command();
+            cmd.args([
+                "issue",
+                "view",
+                &number.to_string(),
+                "--json",
+                "number,title,body,state,labels,url,createdAt,updatedAt",
+            ]);
+            cmd
+        })?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        serde_json::from_str(&stdout).map_err(|e| format!("Failed to parse issue: {}", e))
+    }
+
+    fn create_issue(&self, issue: &NewIssue, priority: GhPriority) -> Result<GitHubIssue, String> {
+        let mut args = vec![
+            "issue".to_string(),
+            "create".to_string(),
+            "--title".to_string(),
+            issue.title.clone(),
+        ];
+        if let Some(ref body_text) = issue.body {
+            args.push("--body".to_string());
+            args.push(body_text.clone());
+        }
+        for label in &issue.labels {
+            args.push("--label".to_string());
+            args.push(label.clone());
+        }
+        for assignee in &issue.assignees {
+            args.push("--assignee".to_string());
+            args.push(assignee.clone());
+        }
+        if let Some(ref milestone) = issue.milestone {
+            args.push("--milestone".to_string());
+            args.push(milestone.clone());
+        }
+
+        let output = self.mutate(priority, &args, "create issue")?;
+
+        // gh issue create outputs the issue URL on success
+        // Parse the issue number from the URL and fetch full issue data
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let issue_url = stdout.trim();
+
+        // Extract issue number from URL (e.g., "https://github.com/owner/repo/issues/123")
+        let issue_number: u32 = issue_url
+            .rsplit('/')
+            .next()
+            .and_then(|n| n.parse().ok())
+            .ok_or_else(|| format!("Failed to parse issue number from URL: {}", issue_url))?;
+
+        self.get_issue(issue_number)
+    }
+
+    fn edit_issue(&self, number: u32, edit: &IssueEdit, priority: GhPriority) -> Result<(), String> {
+        let mut args = vec!["issue".to_string(), "edit".to_string(), number.to_string()];
+        if let Some(ref t) = edit.title {
+            args.push("--title".to_string());
+            args.push(t.clone());
+        }
+        if let Some(ref b) = edit.body {
+            args.push("--body".to_string());
+            args.push(b.clone());
+        }
+        for label in &edit.add_labels {
+            args.push("--add-label".to_string());
+            args.push(label.clone());
+        }
+        for label in &edit.remove_labels {
+            args.push("--remove-label".to_string());
+            args.push(label.clone());
+        }
+        self.mutate(priority, &args, "edit issue").map(|_| ())
+    }
+
+    fn close_issue(&self, number: u32, priority: GhPriority) -> Result<(), String> {
+        let args = ["issue".to_string(), "close".to_string(), number.to_string()];
+        self.mutate(priority, &args, "close issue").map(|_| ())
+    }
+
+    fn list_issue_comments(&self, number: u32) -> Result<Vec<GitHubComment>, String> {
+        let output = run_gh_with_retry(|| {
+            let mut cmd = self.command();
+            cmd.args([
+                "issue",
+                "view",
+                &number.to_string(),
+                "--json",
+                "comments",
+                "--jq",
+                ".comments",
+            ]);
+            cmd
+        })?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let trimmed = stdout.trim();
+        if trimmed.is_empty() || trimmed == "null" {
+            return Ok(vec![]);
+        }
+
+        serde_json::from_str(trimmed).map_err(|e| format!("Failed to parse comments: {}", e))
+    }
+
+    fn post_issue_comment(&self, number: u32, body: &str, priority: GhPriority) -> Result<(), String> {
+        let args = [
+            "issue".to_string(),
+            "comment".to_string(),
+            number.to_string(),
+            "--body".to_string(),
+            body.to_string(),
+        ];
+        self.mutate(priority, &args, "post comment").map(|_| ())
+    }
+
+    fn list_labels(&self) -> Result<Vec<LabelInfo>, String> {
+        let output = run_gh_with_retry(|| {
+            let mut cmd = self.command();
+            cmd.args(["label", "list", "--json", "name,color,description", "--limit", "1000"]);
+            cmd
+        })?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        serde_json::from_str(&stdout).map_err(|e| format!("Failed to parse labels: {}", e))
+    }
+
+    fn create_label(&self, label: &LabelInfo, priority: GhPriority) -> Result<(), String> {
+        let mut args = vec![
+            "label".to_string(),
+            "create".to_string(),
+            label.name.clone(),
+            "--color".to_string(),
+            label.color.clone(),
+        ];
+        if let Some(ref d) = label.description {
+            args.push("--description".to_string());
+            args.push(d.clone());
+        }
+        self.mutate(priority, &args, "create label").map(|_| ())
+    }
+
+    fn update_label(&self, name: &str, edit: &LabelEdit, priority: GhPriority) -> Result<(), String> {
+        let mut args = vec!["label".to_string(), "edit".to_string(), name.to_string()];
+        if let Some(ref n) = edit.new_name {
+            args.push("--name".to_string());
+            args.push(n.clone());
+        }
+        if let Some(ref c) = edit.color {
+            args.push("--color".to_string());
+            args.push(c.clone());
+        }
+        if let Some(ref d) = edit.description {
+            args.push("--description".to_string());
+            args.push(d.clone());
+        }
+        self.mutate(priority, &args, "update label").map(|_| ())
+    }
+
+    fn delete_label(&self, name: &str, priority: GhPriority) -> Result<(), String> {
+        let args = ["label", "delete", name, "--yes"].map(str::to_string);
+        self.mutate(priority, &args, "delete label").map(|_| ())
+    }
+
+    fn list_releases(&self, limit: u32) -> Result<Vec<GitHubRelease>, String> {
+        let output = run_gh_with_retry(|| {
+            let mut cmd = self.command();
+            cmd.args([
+                "release",
+                "list",
+                "--json",
+                "tagName,name,isDraft,isPrerelease,publishedAt",
+                "--limit",
+                &limit.to_string(),
+            ]);
+            cmd
+        })?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        serde_json::from_str(&stdout).map_err(|e| format!("Failed to parse releases: {}", e))
+    }
+
+    fn list_pull_requests(
+        &self,
+        state: &str,
+        limit: u32,
+    ) -> Result<Vec<GitHubPullRequest>, String> {
+        let output = run_gh_with_retry(|| {
+            let mut cmd = self.command();
+            cmd.args([
+                "pr",
+                "list",
+                "--json",
+                "number,title,state,headRefName,baseRefName,url,isDraft,reviewDecision,statusCheckRollup,labels,body,author",
+                "--state",
+                state,
+                "--limit",
+                &limit.to_string(),
+            ]);
+            cmd
+        })?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let trimmed = stdout.trim();
+
+        if trimmed.is_empty() || trimmed == "[]" {
+            return Ok(vec![]);
+        }
+
+        serde_json::from_str(trimmed).map_err(|e| format!("Failed to parse PR list: {}", e))
+    }
+
+    fn graphql(
+        &self,
+        query: &str,
+        variables: &[(&str, serde_json::Value)],
+        priority: GhPriority,
+    ) -> Result<Vec<u8>, String> {
+        let output = run_gh_scheduled(
+            GhBucket::Graphql,
+            priority,
+            || {
+                let mut cmd = self.command();
+                cmd.args([
+                    "api",
+                    "graphql",
+                    "-F",
+                    "owner={owner}",
+                    "-F",
+                    "name={repo}",
+                    "-f",
+                    &format!("query={}", query),
+                ]);
+                // `-F` guesses types from the text, so a cursor that looks
+                // like a number would be sent as one; strings go raw.
+                for (key, value) in variables {
+                    match value {
+                        serde_json::Value::String(s) => cmd.args(["-f", &format!("{}={}", key, s)]),
+                        other => cmd.args(["-F", &format!("{}={}", key, other)]),
+                    };
+                }
+                cmd
+            },
+            |output| output.status.success(),
+        )?;
+        Ok(output.stdout)
+    }
+
+    fn probe(&self, endpoint: &str, etag: Option<&str>) -> Result<Probe, String> {
+        let output = run_gh_with_retry_accepting(
+            || {
+                let mut cmd = self.command();
+                cmd.args(["api", "-i", endpoint]);
+                if let Some(tag) = etag {
+                    cmd.args(["-H", &format!("If-None-Match: {}", tag)]);
+                }
+                cmd
+            },
+            // gh exits non-zero on 304 but still prints the status line with -i.
+            |output| {
+                output.status.success()
+                    || interpret_probe(&String::from_utf8_lossy(&output.stdout))
+                        == Some(Probe::NotModified)
+            },
+        )?;
+
+        interpret_probe(&String::from_utf8_lossy(&output.stdout))
+            .ok_or_else(|| "Unexpected response from GitHub API probe".to_string())
+    }
+
+    fn rate_limit(&self) -> Result<RateLimitStatus, String> {
+        query_gh_rate_limit(self.project_path.as_deref())
+    }
+
+    fn quota_host(&self) -> String {
+        GhTarget::for_project(self.project_path.as_deref())
+            .host
+            .unwrap_or_else(|| DEFAULT_GH_HOST.to_string())
+    }
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/// Which backend a project uses and whether it can authenticate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHubBackendStatus {
+    pub backend: String,
+    /// For the API backend: where the token came from ("stored", "env",
+    /// "ghConfig", "ghCli"). Always `None` for the gh backend.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_source: Option<String>,
+    /// Set when the configured backend can't be constructed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Report the configured GitHub backend for a project.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn get_github_backend_status(project_path: Option<String>) -> GitHubBackendStatus {
+    let root = match project_root(project_path.as_deref()) {
+        Ok(r) => r,
+        Err(e) => {
+            return GitHubBackendStatus {
+                backend: "gh".to_string(),
+                token_source: None,
+                error: Some(e),
+            }
+        }
+    };
+    let backend = load_tiki_config(&root.join(".tiki"))
+        .github
+        .and_then(|g| g.backend)
+        .unwrap_or_else(|| "gh".to_string());
+
+    let (token_source, error) = if backend == "api" {
+        match RestBackend::for_project(&root) {
+            Ok(b) => (Some(b.token_source().to_string()), None),
+            Err(e) => (None, Some(e)),
+        }
+    } else if GITHUB_BACKENDS.contains(&backend.as_str()) {
+        (None, None)
+    } else {
+        (None, Some(format!("Unknown github.backend '{}'", backend)))
+    };
+
+    GitHubBackendStatus {
+        backend,
+        token_source,
+        error,
+    }
+}
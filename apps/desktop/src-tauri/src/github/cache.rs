@@ -0,0 +1,497 @@
+//! Persistent cache for GitHub list data with ETag revalidation and offline
+//! fallback.
+//!
+//! Each cached list lives in `<project>/.tiki/cache/github/<key>.json` along
+//! with the ETag of a cheap REST "probe" endpoint whose response changes
+//! whenever the list would. Before re-running the full `gh` fetch we send the
+//! probe with `If-None-Match`; a `304 Not Modified` does not count against the
+//! rate limit, so unchanged data is free. When GitHub is unreachable (or the
+//! user has switched offline mode on) the last cached copy is served and
+//! flagged stale until the next successful revalidation.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::{backend_for, GH_NETWORK_ERROR};
+use crate::fs_utils::{atomic_write, project_root, read_json_resilient};
+
+/// Probe for issue and PR lists: the single most recently updated item.
+/// Any edit, label change, comment or state change bumps `updated_at` and
+/// moves that item to the top, so the probe's ETag changes with it.
+pub(crate) const ISSUES_PROBE: &str =
+    "repos/{owner}/{repo}/issues?state=all&sort=updated&direction=desc&per_page=1";
+
+/// Check runs don't bump a PR's `updated_at`, so PR lists are refetched at
+/// least this often even when the probe reports no change.
+pub(crate) const PR_CACHE_MAX_AGE_SECS: i64 = 120;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheEntry<T> {
+    #[serde(default)]
+    etag: Option<String>,
+    fetched_at: String,
+    data: T,
+}
+
+/// Result of a conditional probe request.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Probe {
+    NotModified,
+    Modified { etag: Option<String> },
+}
+
+#[derive(Debug, Default)]
+struct CacheRuntime {
+    /// User-enabled offline mode: never touch the network, serve cache only.
+    forced_offline: bool,
+    /// Per-project state, keyed by cache directory.
+    projects: HashMap<PathBuf, ProjectCacheState>,
+}
+
+#[derive(Debug, Default)]
+struct ProjectCacheState {
+    /// Last network error; cleared on the next successful request.
+    network_error: Option<String>,
+    /// Keys whose last response came from cache without revalidation.
+    stale_keys: BTreeSet<String>,
+}
+
+fn runtime() -> &'static Mutex<CacheRuntime> {
+    static RUNTIME: OnceLock<Mutex<CacheRuntime>> = OnceLock::new();
+    RUNTIME.get_or_init(|| Mutex::new(CacheRuntime::default()))
+}
+
+fn with_runtime<R>(f: impl FnOnce(&mut CacheRuntime) -> R) -> R {
+    let mut guard = runtime().lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut guard)
+}
+
+fn with_project<R>(dir: &Path, f: impl FnOnce(&mut ProjectCacheState) -> R) -> R {
+    with_runtime(|rt| f(rt.projects.entry(dir.to_path_buf()).or_default()))
+}
+
+fn mark_fresh(dir: &Path, key: &str) {
+    with_project(dir, |state| {
+        state.network_error = None;
+        state.stale_keys.remove(key);
+    });
+}
+
+fn mark_stale(dir: &Path, key: &str, network_error: Option<String>) {
+    with_project(dir, |state| {
+        if network_error.is_some() {
+            state.network_error = network_error;
+        }
+        state.stale_keys.insert(key.to_string());
+    });
+}
+
+fn cache_dir(project_path: Option<&str>) -> Result<PathBuf, String> {
+    Ok(project_root(project_path)?.join(".tiki").join("cache").join("github"))
+}
+
+fn is_network_error(err: &str) -> bool {
+    err.starts_with(GH_NETWORK_ERROR)
+}
+
+/// Parse the status code and (lower-cased) headers from `gh api -i` output.
+fn parse_http_head(raw: &str) -> Option<(u16, Vec<(String, String)>)> {
+    let head = raw
+        .split("\r\n\r\n")
+        .next()
+        .and_then(|h| h.split("\n\n").next())
+        .unwrap_or(raw);
+    let mut lines = head.lines();
+    let status = lines
+        .next()?
+        .split_whitespace()
+        .nth(1)?
+        .parse::<u16>()
+        .ok()?;
+    let headers = lines
+        .filter_map(|l| {
+            let (name, value) = l.split_once(':')?;
+            Some((name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        })
+        .collect();
+    Some((status, headers))
+}
+
+/// Interpret `gh api -i` output as a probe result.
+pub(crate) fn interpret_probe(raw: &str) -> Option<Probe> {
+    let (status, headers) = parse_http_head(raw)?;
+    match status {
+        304 => Some(Probe::NotModified),
+        200..=299 => Some(Probe::Modified {
+            etag: headers
+                .into_iter()
+                .find(|(name, _)| name == "etag")
+                .map(|(_, value)| value),
+        }),
+        _ => None,
+    }
+}
+
+fn is_expired(fetched_at: &str, max_age_secs: Option<i64>) -> bool {
+    let Some(max_age) = max_age_secs else {
+        return false;
+    };
+    match chrono::DateTime::parse_from_rfc3339(fetched_at) {
+        Ok(t) => (chrono::Utc::now() - t.with_timezone(&chrono::Utc)).num_seconds() > max_age,
+        Err(_) => true,
+    }
+}
+
+/// Drop a `.gitignore` into `.tiki/cache/` so cached API payloads never end
+/// up in the project's commits.
+fn ignore_cache_dir(github_dir: &Path) {
+    let Some(cache_root) = github_dir.parent() else {
+        return;
+    };
+    let ignore = cache_root.join(".gitignore");
+    if !ignore.exists() {
+        if let Err(e) = std::fs::write(&ignore, "*\n") {
+            log::debug!("Could not write {}: {}", ignore.display(), e);
+        }
+    }
+}
+
+fn store<T: Serialize>(path: &Path, etag: Option<String>, data: &T) {
+    let entry = CacheEntry {
+        etag,
+        fetched_at: chrono::Utc::now().to_rfc3339(),
+        data,
+    };
+    let result = serde_json::to_string(&entry)
+        .map_err(|e| e.to_string())
+        .and_then(|json| {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                ignore_cache_dir(parent);
+            }
+            atomic_write(path, &json)
+        });
+    if let Err(e) = result {
+        log::warn!("Failed to write GitHub cache {}: {}", path.display(), e);
+    }
+}
+
+/// Keys double as file names (and as the keys `get_github_cache_status` reports).
+fn file_key(key: &str) -> String {
+    key.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+/// Number of items in `key`'s cached list, if there is one.
+pub(crate) fn cached_len(project_path: Option<&str>, key: &str) -> Option<usize> {
+    let path = cache_dir(project_path).ok()?.join(format!("{}.json", file_key(key)));
+    read_json_resilient::<CacheEntry<Vec<serde_json::Value>>>(&path)
+        .ok()
+        .flatten()
+        .map(|entry| entry.data.len())
+}
+
+/// Serve `key` from the cache when the `probe_endpoint` ETag is unchanged,
+/// otherwise run `fetch` and cache its result. Falls back to the cached copy
+/// (marked stale) when GitHub is unreachable or offline mode is on.
+pub(crate) fn cached_fetch<T, F>(
+    project_path: Option<&str>,
+    key: &str,
+    probe_endpoint: &str,
+    max_age_secs: Option<i64>,
+    fetch: F,
+) -> Result<T, String>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Result<T, String>,
+{
+    let offline = with_runtime(|rt| rt.forced_offline);
+    fetch_through_cache(offline, project_path, key, probe_endpoint, max_age_secs, fetch)
+}
+
+/// `cached_fetch` with the offline-mode flag passed in.
+fn fetch_through_cache<T, F>(
+    offline: bool,
+    project_path: Option<&str>,
+    key: &str,
+    probe_endpoint: &str,
+    max_age_secs: Option<i64>,
+    fetch: F,
+) -> Result<T, String>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Result<T, String>,
+{
+    let key = file_key(key);
+    let key = key.as_str();
+    let dir = cache_dir(project_path)?;
+    let path = dir.join(format!("{}.json", key));
+    let cached: Option<CacheEntry<T>> = read_json_resilient(&path).unwrap_or_else(|e| {
+        log::warn!("Ignoring unreadable GitHub cache {}: {}", path.display(), e);
+        None
+    });
+
+    if offline {
+        return match cached {
+            Some(entry) => {
+                mark_stale(&dir, key, None);
+                Ok(entry.data)
+            }
+            None => Err(format!(
+                "Offline mode is on and no cached copy of '{}' exists yet.",
+                key
+            )),
+        };
+    }
+
+    let etag = cached
+        .as_ref()
+        .filter(|e| !is_expired(&e.fetched_at, max_age_secs))
+        .and_then(|e| e.etag.clone());
+
+    let probed = backend_for(project_path).and_then(|b| b.probe(probe_endpoint, etag.as_deref()));
+    let new_etag = match probed {
+        Ok(Probe::NotModified) => {
+            if let Some(entry) = cached {
+                mark_fresh(&dir, key);
+                return Ok(entry.data);
+            }
+            None
+        }
+        Ok(Probe::Modified { etag }) => etag,
+        Err(e) if is_network_error(&e) => {
+            return match cached {
+                Some(entry) => {
+                    log::info!("GitHub unreachable; serving cached '{}' from {}", key, entry.fetched_at);
+                    mark_stale(&dir, key, Some(e));
+                    Ok(entry.data)
+                }
+                None => Err(e),
+            };
+        }
+        // A failed probe only costs us the conditional request; fetch as usual.
+        Err(e) => {
+            log::debug!("GitHub cache probe for '{}' failed: {}", key, e);
+            None
+        }
+    };
+
+    match fetch() {
+        Ok(data) => {
+            store(&path, new_etag, &data);
+            mark_fresh(&dir, key);
+            Ok(data)
+        }
+        Err(e) if is_network_error(&e) => match cached {
+            Some(entry) => {
+                mark_stale(&dir, key, Some(e));
+                Ok(entry.data)
+            }
+            None => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/// One cached list and when it was last fetched from GitHub.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHubCacheEntryInfo {
+    pub key: String,
+    pub fetched_at: String,
+    /// True when the last response for this key was served from cache
+    /// without revalidation (offline or GitHub unreachable).
+    pub stale: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHubCacheStatus {
+    pub offline_mode: bool,
+    /// Set while GitHub is unreachable; cleared on the next successful request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_error: Option<String>,
+    pub entries: Vec<GitHubCacheEntryInfo>,
+}
+
+/// Minimal view of a cache file, skipping the (possibly large) payload.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheEntryHeader {
+    fetched_at: String,
+}
+
+/// Report offline state and the age of every cached GitHub list, so the UI can
+/// show "offline — data as of …" next to stale lists.
+#[tauri::command]
+pub fn get_github_cache_status(project_path: Option<String>) -> Result<GitHubCacheStatus, String> {
+    let dir = cache_dir(project_path.as_deref())?;
+    let offline_mode = with_runtime(|rt| rt.forced_offline);
+    let (network_error, stale_keys) =
+        with_project(&dir, |state| (state.network_error.clone(), state.stale_keys.clone()));
+
+    let mut entries = Vec::new();
+    if let Ok(read_dir) = std::fs::read_dir(&dir) {
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(key) = path.file_stem().and_then(|s| s.to_str()).map(str::to_string) else {
+                continue;
+            };
+            if let Ok(Some(header)) = read_json_resilient::<CacheEntryHeader>(&path) {
+                entries.push(GitHubCacheEntryInfo {
+                    stale: stale_keys.contains(&key),
+                    key,
+                    fetched_at: header.fetched_at,
+                });
+            }
+        }
+    }
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+
+    Ok(GitHubCacheStatus {
+        offline_mode,
+        network_error,
+        entries,
+    })
+}
+
+/// Turn offline mode on or off. While on, GitHub list commands serve cached
+/// data only and never spawn `gh`.
+#[tauri::command]
+pub fn set_github_offline_mode(enabled: bool) {
+    with_runtime(|rt| rt.forced_offline = enabled);
+}
+
+/// Delete every cached GitHub list for the project.
+#[tauri::command]
+pub fn clear_github_cache(project_path: Option<String>) -> Result<(), String> {
+    let dir = cache_dir(project_path.as_deref())?;
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).map_err(|e| format!("Failed to clear GitHub cache: {}", e))?;
+    }
+    with_project(&dir, |state| state.stale_keys.clear());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interprets_probe_responses() {
+        let ok = "HTTP/2.0 200 OK\r\nContent-Type: application/json\r\nEtag: W/\"abc123\"\r\n\r\n[{\"number\":1}]";
+        assert_eq!(
+            interpret_probe(ok),
+            Some(Probe::Modified {
+                etag: Some("W/\"abc123\"".to_string())
+            })
+        );
+
+        let not_modified = "HTTP/2.0 304 Not Modified\nEtag: W/\"abc123\"\n\n";
+        assert_eq!(interpret_probe(not_modified), Some(Probe::NotModified));
+
+        assert_eq!(interpret_probe("HTTP/2.0 404 Not Found\n\n{}"), None);
+        assert_eq!(interpret_probe("garbage"), None);
+    }
+
+    #[test]
+    fn expiry_respects_max_age() {
+        let now = chrono::Utc::now().to_rfc3339();
+        let old = (chrono::Utc::now() - chrono::Duration::seconds(600)).to_rfc3339();
+        assert!(!is_expired(&old, None));
+        assert!(!is_expired(&now, Some(120)));
+        assert!(is_expired(&old, Some(120)));
+        assert!(is_expired("not a timestamp", Some(120)));
+    }
+
+    #[test]
+    fn network_errors_are_recognized() {
+        let msg = super::super::gh_error_message(
+            "error connecting to api.github.com\ncheck your internet connection or https://githubstatus.com",
+        );
+        assert!(is_network_error(&msg));
+        assert!(!is_network_error(&super::super::gh_error_message("HTTP 404: Not Found")));
+    }
+
+    #[test]
+    fn offline_mode_serves_cache_without_gh() {
+        let dir = std::env::temp_dir().join(format!("tiki-gh-cache-{}", std::process::id()));
+        let project = dir.to_string_lossy().to_string();
+        let path = cache_dir(Some(&project)).unwrap().join("labels.json");
+        store(&path, Some("W/\"x\"".to_string()), &vec!["bug".to_string()]);
+
+        // The global flag stays untouched so concurrent tests still hit gh.
+        let served: Vec<String> =
+            fetch_through_cache(true, Some(&project), "labels", "unused", None, || {
+                panic!("fetch must not run in offline mode")
+            })
+            .unwrap();
+        let missing: Result<Vec<String>, String> =
+            fetch_through_cache(true, Some(&project), "releases-20", "unused", None, || {
+                panic!("fetch must not run in offline mode")
+            });
+        let status = get_github_cache_status(Some(project.clone())).unwrap();
+
+        assert_eq!(served, vec!["bug".to_string()]);
+        assert!(missing.unwrap_err().contains("Offline mode"));
+        assert_eq!(status.entries.len(), 1);
+        assert!(status.entries[0].stale);
+
+        clear_github_cache(Some(project)).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn runtime_state_is_per_project() {
+        let base =
+            std::env::temp_dir().join(format!("tiki-gh-cache-projects-{}", std::process::id()));
+        let (a, b) = (base.join("a"), base.join("b"));
+        for dir in [&a, &b] {
+            let project = dir.to_string_lossy().to_string();
+            let path = cache_dir(Some(&project)).unwrap().join("labels.json");
+            store(&path, None, &vec!["bug".to_string()]);
+            let served: Vec<String> =
+                fetch_through_cache(true, Some(&project), "labels", "unused", None, || {
+                    panic!("fetch must not run in offline mode")
+                })
+                .unwrap();
+            assert_eq!(served, vec!["bug".to_string()]);
+        }
+        let dir_a = cache_dir(Some(&a.to_string_lossy())).unwrap();
+        mark_stale(&dir_a, "labels", Some("down".to_string()));
+
+        let (a, b) = (a.to_string_lossy().to_string(), b.to_string_lossy().to_string());
+        clear_github_cache(Some(a.clone())).unwrap();
+        let status_a = get_github_cache_status(Some(a)).unwrap();
+        let status_b = get_github_cache_status(Some(b)).unwrap();
+
+        assert!(status_a.entries.is_empty());
+        assert_eq!(status_a.network_error.as_deref(), Some("down"));
+        assert_eq!(status_b.network_error, None);
+        assert!(status_b.entries[0].stale);
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn counts_cached_items() {
+        let dir = std::env::temp_dir().join(format!("tiki-gh-cache-len-{}", std::process::id()));
+        let project = dir.to_string_lossy().to_string();
+        let path = cache_dir(Some(&project)).unwrap().join("labels.json");
+        store(&path, None, &vec!["bug".to_string(), "docs".to_string()]);
+
+        assert_eq!(cached_len(Some(&project), "labels"), Some(2));
+        assert_eq!(cached_len(Some(&project), "releases-20"), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,532 @@
+//! AI enhancement of issue descriptions via the Claude CLI.
+//!
+//! Each enhancement type is a prompt. Four ship built in; a project adds its
+//! own (or overrides a built-in) by dropping `<id>.md` into `.tiki/prompts/`.
+//! A prompt file is Markdown with optional YAML front matter (`name`,
+//! `description`, `timeoutSecs`). `{{description}}` in the body is replaced
+//! by the text being enhanced; without it the text is appended.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use super::require_claude_cli;
+use crate::fs_utils::project_root;
+
+const PROMPTS_DIR: &str = "prompts";
+const DESCRIPTION_PLACEHOLDER: &str = "{{description}}";
+
+/// How long the CLI may run before it's killed, unless the prompt says otherwise.
+const DEFAULT_TIMEOUT_SECS: u64 = 180;
+/// How often a running enhancement checks for cancellation and the deadline.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+struct BuiltinPrompt {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    template: &'static str,
+}
+
+const BUILTIN_PROMPTS: &[BuiltinPrompt] = &[
+    BuiltinPrompt {
+        id: "clarity",
+        name: "Improve clarity",
+        description: "Make the description clearer and easier to understand",
+        template: "Improve the clarity of this GitHub issue description. Make it clearer and easier to understand while preserving the original intent. Return ONLY the improved description text, no explanations:\n\n{{description}}",
+    },
+    BuiltinPrompt {
+        id: "technical",
+        name: "Add technical details",
+        description: "Add implementation hints and technical context",
+        template: "Add technical details and implementation hints to this GitHub issue description. Include relevant technical context that would help a developer understand what needs to be done. Return ONLY the enhanced description text, no explanations:\n\n{{description}}",
+    },
+    BuiltinPrompt {
+        id: "simplify",
+        name: "Simplify language",
+        description: "Use simpler terms and reduce complexity",
+        template: "Simplify this GitHub issue description. Use simpler terms and reduce complexity while preserving all important information. Return ONLY the simplified description text, no explanations:\n\n{{description}}",
+    },
+    BuiltinPrompt {
+        id: "acceptance",
+        name: "Add acceptance criteria",
+        description: "Generate testable acceptance criteria",
+        template: "Add acceptance criteria to this GitHub issue description. Generate testable criteria that define when this issue is complete. Format with bullet points under an '## Acceptance Criteria' heading. Return the original description followed by the acceptance criteria, no other explanations:\n\n{{description}}",
+    },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptSource {
+    Builtin,
+    Project,
+}
+
+/// An enhancement type, as listed by `list_enhancement_prompts`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnhancementPrompt {
+    /// Pass this as `enhancement_type`. For project prompts, the file stem.
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub source: PromptSource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    #[serde(skip)]
+    template: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PromptHeader {
+    name: Option<String>,
+    description: Option<String>,
+    timeout_secs: Option<u64>,
+}
+
+fn builtin_prompts() -> Vec<EnhancementPrompt> {
+    BUILTIN_PROMPTS
+        .iter()
+        .map(|p| EnhancementPrompt {
+            id: p.id.to_string(),
+            name: p.name.to_string(),
+            description: Some(p.description.to_string()),
+            source: PromptSource::Builtin,
+            timeout_secs: None,
+            template: p.template.to_string(),
+        })
+        .collect()
+}
+
+/// Parse a `.tiki/prompts/<id>.md` file. Front matter is optional; without a
+/// `name` the id is used.
+fn parse_prompt(id: &str, contents: &str) -> Result<EnhancementPrompt, String> {
+    let contents = contents.trim_start_matches('\u{feff}');
+    let (header, body) = match contents.strip_prefix("---") {
+        Some(rest) => {
+            let end = rest
+                .find("\n---")
+                .ok_or_else(|| format!("{}.md: unterminated front matter", id))?;
+            let header: PromptHeader = serde_yaml::from_str::<Option<PromptHeader>>(&rest[..end])
+                .map_err(|e| format!("{}.md: invalid front matter: {}", id, e))?
+                .unwrap_or_default();
+            (header, &rest[end + 4..])
+        }
+        None => (PromptHeader::default(), contents),
+    };
+    let template = body.trim().to_string();
+    if template.is_empty() {
+        return Err(format!("{}.md: prompt is empty", id));
+    }
+
+    Ok(EnhancementPrompt {
+        id: id.to_string(),
+        name: header.name.filter(|n| !n.is_empty()).unwrap_or_else(|| id.to_string()),
+        description: header.description.filter(|d| !d.is_empty()),
+        source: PromptSource::Project,
+        timeout_secs: header.timeout_secs,
+        template,
+    })
+}
+
+/// Built-in prompts followed by the project's, sorted by file name. A project
+/// prompt whose id matches a built-in replaces it in place.
+fn load_prompts(tiki_path: &Path) -> Vec<EnhancementPrompt> {
+    let mut prompts = builtin_prompts();
+
+    let Ok(entries) = std::fs::read_dir(tiki_path.join(PROMPTS_DIR)) else {
+        return prompts;
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("md"))
+        .collect();
+    files.sort();
+
+    for path in files {
+        let Some(id) = path.file_stem().and_then(|s| s.to_str()).map(str::to_string) else {
+            continue;
+        };
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(|e| format!("{}.md: {}", id, e))
+            .and_then(|c| parse_prompt(&id, &c));
+        match parsed {
+            Ok(prompt) => match prompts.iter_mut().find(|p| p.id == prompt.id) {
+                Some(existing) => *existing = prompt,
+                None => prompts.push(prompt),
+            },
+            Err(e) => log::warn!("Skipping enhancement prompt {}", e),
+        }
+    }
+    prompts
+}
+
+fn render_prompt(template: &str, description: &str) -> String {
+    if template.contains(DESCRIPTION_PLACEHOLDER) {
+        template.replace(DESCRIPTION_PLACEHOLDER, description)
+    } else {
+        format!("{}\n\n{}", template, description)
+    }
+}
+
+fn tiki_dir(project_path: Option<&str>) -> Result<PathBuf, String> {
+    Ok(project_root(project_path)?.join(".tiki"))
+}
+
+/// List the enhancement types available for a project: the built-ins plus
+/// any `.tiki/prompts/*.md`.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn list_enhancement_prompts(
+    project_path: Option<String>,
+) -> Result<Vec<EnhancementPrompt>, String> {
+    Ok(load_prompts(&tiki_dir(project_path.as_deref())?))
+}
+
+// ─── Running ──────────────────────────────────────────────────────────────────
+
+/// Emitted as `enhancement-output` for every chunk of CLI stdout.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnhancementOutputEvent {
+    pub request_id: String,
+    pub chunk: String,
+}
+
+/// Cancellation flags of running enhancements, by request id.
+fn running() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    static RUNNING: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+    RUNNING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Registers a request's cancellation flag for as long as it's alive.
+struct RunningGuard(Option<String>);
+
+impl RunningGuard {
+    fn register(request_id: Option<String>) -> (Self, Arc<AtomicBool>) {
+        let flag = Arc::new(AtomicBool::new(false));
+        if let Some(ref id) = request_id {
+            if let Ok(mut map) = running().lock() {
+                map.insert(id.clone(), flag.clone());
+            }
+        }
+        (RunningGuard(request_id), flag)
+    }
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        if let Some(ref id) = self.0 {
+            if let Ok(mut map) = running().lock() {
+                map.remove(id);
+            }
+        }
+    }
+}
+
+/// Split off the longest valid UTF-8 prefix of `buf`, leaving a trailing
+/// partial character (if any) for the next read.
+fn take_utf8(buf: &mut Vec<u8>) -> String {
+    let valid = match std::str::from_utf8(buf) {
+        Ok(_) => buf.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        // Genuinely invalid bytes: don't hold them back forever.
+        Err(_) => buf.len(),
+    };
+    let rest = buf.split_off(valid);
+    let text = String::from_utf8_lossy(buf).into_owned();
+    *buf = rest;
+    text
+}
+
+/// Spawn `cmd` with all three standard streams piped.
+fn spawn_piped(cmd: &mut Command) -> std::io::Result<Child> {
+    cmd.stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+}
+
+/// Spawn `claude -p` (prompt on stdin) in `project_path`, ready for
+/// `run_streaming`.
+pub(crate) fn spawn_claude_print(project_path: Option<&str>) -> Result<Child, String> {
+    let cli = require_claude_cli()?;
+    let mut cmd = cli.command();
+    cmd.arg("-p");
+    if let Some(p) = project_path {
+        cmd.current_dir(p);
+    }
+    spawn_piped(&mut cmd).map_err(|e| {
+        format!(
+            "Failed to run Claude CLI ({}): {}. Make sure Claude CLI is properly installed and configured.",
+            cli.program, e
+        )
+    })
+}
+
+/// Feed `input` to a child from `spawn_piped`, passing its stdout to
+/// `on_chunk` as it arrives. The child is killed if `cancel` is set or
+/// `timeout` elapses. Returns the full stdout on a zero exit.
+pub(crate) fn run_streaming(
+    mut child: Child,
+    input: String,
+    timeout: Duration,
+    cancel: &AtomicBool,
+    mut on_chunk: impl FnMut(&str),
+) -> Result<String, String> {
+    // Write from a thread so a CLI that never reads stdin can't block us
+    // past the deadline. Dropping the handle closes the pipe.
+    if let Some(mut stdin) = child.stdin.take() {
+        std::thread::spawn(move || {
+            if let Err(e) = stdin.write_all(input.as_bytes()) {
+                log::warn!("Failed to write to Claude CLI stdin: {}", e);
+            }
+        });
+    }
+
+    let (tx, rx) = mpsc::channel::<Vec<u8>>();
+    if let Some(mut stdout) = child.stdout.take() {
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 4096];
+            while let Ok(n) = stdout.read(&mut buf) {
+                if n == 0 || tx.send(buf[..n].to_vec()).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    let stderr_reader = child.stderr.take().map(|mut stderr| {
+        std::thread::spawn(move || {
+            let mut text = String::new();
+            let _ = stderr.read_to_string(&mut text);
+            text
+        })
+    });
+
+    let deadline = Instant::now() + timeout;
+    let mut output = String::new();
+    let mut pending = Vec::new();
+    let mut stdout_open = true;
+    let status = loop {
+        let stop = if cancel.load(Ordering::SeqCst) {
+            Some("Claude CLI run cancelled".to_string())
+        } else if Instant::now() >= deadline {
+            Some(format!("Claude CLI timed out after {}s", timeout.as_secs()))
+        } else {
+            None
+        };
+        if let Some(reason) = stop {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(reason);
+        }
+
+        if stdout_open {
+            match rx.recv_timeout(POLL_INTERVAL) {
+                Ok(bytes) => {
+                    pending.extend_from_slice(&bytes);
+                    let text = take_utf8(&mut pending);
+                    if !text.is_empty() {
+                        on_chunk(&text);
+                        output.push_str(&text);
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => stdout_open = false,
+            }
+        } else {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) => std::thread::sleep(POLL_INTERVAL),
+                Err(e) => return Err(format!("Failed to wait for Claude CLI: {}", e)),
+            }
+        }
+    };
+
+    if !pending.is_empty() {
+        let text = String::from_utf8_lossy(&pending).into_owned();
+        on_chunk(&text);
+        output.push_str(&text);
+    }
+
+    if !status.success() {
+        let stderr = stderr_reader
+            .and_then(|h| h.join().ok())
+            .unwrap_or_default();
+        return Err(format!("Claude CLI failed: {}", stderr.trim()));
+    }
+    Ok(output)
+}
+
+fn enhance_blocking(
+    app: AppHandle,
+    description: String,
+    enhancement_type: String,
+    request_id: Option<String>,
+    project_path: Option<String>,
+) -> Result<String, String> {
+    let prompt = load_prompts(&tiki_dir(project_path.as_deref())?)
+        .into_iter()
+        .find(|p| p.id == enhancement_type)
+        .ok_or_else(|| format!("Unknown enhancement type: {}", enhancement_type))?;
+
+    // Resolve the CLI the same way `check_claude_cli` does — PATH plus known
+    // install locations — so the button being enabled always matches what
+    // runs here. The prompt goes via stdin to avoid shell metacharacter issues.
+    let child = spawn_claude_print(project_path.as_deref())?;
+
+    let (_guard, cancel) = RunningGuard::register(request_id.clone());
+    let timeout = Duration::from_secs(prompt.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+    let output = run_streaming(
+        child,
+        render_prompt(&prompt.template, &description),
+        timeout,
+        &cancel,
+        |chunk| {
+            let Some(ref request_id) = request_id else {
+                return;
+            };
+            let event = EnhancementOutputEvent {
+                request_id: request_id.clone(),
+                chunk: chunk.to_string(),
+            };
+            if let Err(e) = app.emit("enhancement-output", event) {
+                log::error!("Failed to emit enhancement-output event: {}", e);
+            }
+        },
+    )?;
+
+    Ok(output.trim().to_string())
+}
+
+/// Enhance an issue description using Claude AI.
+/// Streams stdout as `enhancement-output` events (`{ requestId, chunk }`)
+/// while running, and returns the full trimmed result.
+/// - description: The current description text to enhance
+/// - enhancement_type: A prompt id from `list_enhancement_prompts`
+/// - request_id: Caller-chosen id for output events and `cancel_issue_enhancement`. Without one, nothing is streamed.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub async fn enhance_issue_description(
+    app: AppHandle,
+    description: String,
+    enhancement_type: String,
+    request_id: Option<String>,
+    project_path: Option<String>,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        enhance_blocking(app, description, enhancement_type, request_id, project_path)
+    })
+    .await
+    .map_err(|e| format!("Enhancement task failed: {}", e))?
+}
+
+/// Cancel a running enhancement. Returns false if no enhancement with this
+/// id is running (it may already have finished).
+#[tauri::command]
+pub fn cancel_issue_enhancement(request_id: String) -> Result<bool, String> {
+    let map = running().lock().map_err(|e| e.to_string())?;
+    match map.get(&request_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::SeqCst);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::hidden_command;
+
+    #[test]
+    fn parses_project_prompts() {
+        let md = "---\nname: Security review\ntimeoutSecs: 300\n---\n\nList security risks in:\n\n{{description}}\n";
+        let p = parse_prompt("security", md).unwrap();
+        assert_eq!(p.name, "Security review");
+        assert_eq!(p.description, None);
+        assert_eq!(p.timeout_secs, Some(300));
+        assert_eq!(render_prompt(&p.template, "Add login"), "List security risks in:\n\nAdd login");
+
+        let bare = parse_prompt("split", "Split this into sub-issues.").unwrap();
+        assert_eq!(bare.name, "split");
+        assert_eq!(render_prompt(&bare.template, "Big task"), "Split this into sub-issues.\n\nBig task");
+
+        assert!(parse_prompt("empty", "---\nname: Empty\n---\n").is_err());
+    }
+
+    #[test]
+    fn project_prompts_extend_and_override_builtins() {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let tiki = std::env::temp_dir().join(format!("tiki-prompts-{}-{}", std::process::id(), nanos));
+        let dir = tiki.join(PROMPTS_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("clarity.md"), "---\nname: Team clarity\n---\nRewrite: {{description}}").unwrap();
+        std::fs::write(dir.join("security.md"), "Review: {{description}}").unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let prompts = load_prompts(&tiki);
+        let ids: Vec<&str> = prompts.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["clarity", "technical", "simplify", "acceptance", "security"]);
+        assert_eq!(prompts[0].name, "Team clarity");
+        assert_eq!(prompts[0].source, PromptSource::Project);
+        assert_eq!(prompts[1].source, PromptSource::Builtin);
+
+        std::fs::remove_dir_all(&tiki).unwrap();
+    }
+
+    #[test]
+    fn keeps_split_utf8_characters_for_the_next_chunk() {
+        let mut buf = "héllo".as_bytes()[..2].to_vec();
+        assert_eq!(take_utf8(&mut buf), "h");
+        buf.extend_from_slice(&"héllo".as_bytes()[2..]);
+        assert_eq!(take_utf8(&mut buf), "éllo");
+        assert!(buf.is_empty());
+    }
+
+    fn spawn(program: &str, args: &[&str]) -> Child {
+        spawn_piped(hidden_command(program).args(args)).unwrap()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn streams_output_and_enforces_timeout_and_cancel() {
+        let never = AtomicBool::new(false);
+        let mut chunks = String::new();
+        let out = run_streaming(
+            spawn("cat", &[]),
+            "streamed".to_string(),
+            Duration::from_secs(10),
+            &never,
+            |c| chunks.push_str(c),
+        )
+        .unwrap();
+        assert_eq!(out, "streamed");
+        assert_eq!(chunks, "streamed");
+
+        let started = Instant::now();
+        let err = run_streaming(spawn("sleep", &["10"]), String::new(), Duration::from_millis(300), &never, |_| {})
+            .unwrap_err();
+        assert!(err.contains("timed out"), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let cancelled = AtomicBool::new(true);
+        let err = run_streaming(spawn("sleep", &["10"]), String::new(), Duration::from_secs(10), &cancelled, |_| {})
+            .unwrap_err();
+        assert_eq!(err, "Claude CLI run cancelled");
+    }
+}
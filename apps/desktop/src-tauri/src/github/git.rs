@@ -1,11 +1,23 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
 use super::hidden_command;
+use crate::config::load_tiki_config;
+use crate::fs_utils::{self, project_root};
 
-/// Get the current git branch name
-/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
-#[tauri::command]
-pub fn get_current_branch(project_path: Option<String>) -> Result<String, String> {
+/// Default branch template for `create_issue_branch` when `.tiki/config.json`
+/// has no `git.branchTemplate`.
+const DEFAULT_BRANCH_TEMPLATE: &str = "issue-{number}-{slug}";
+
+/// Slugs are truncated so generated branch names stay readable in `git branch`.
+const MAX_SLUG_LEN: usize = 40;
+
+/// Run `git` with `args` in `project_path` (or the cwd) and return stdout.
+/// `action` names the operation in the error message (e.g. "list branches").
+pub(super) fn run_git(args: &[&str], project_path: Option<&Path>, action: &str) -> Result<String, String> {
     let mut cmd = hidden_command("git");
-    cmd.args(["rev-parse", "--abbrev-ref", "HEAD"]);
+    cmd.args(args);
 
     if let Some(path) = project_path {
         cmd.current_dir(path);
@@ -24,10 +36,21 @@ pub fn get_current_branch(project_path: Option<String>) -> Result<String, String
         if stderr.contains("not a git repository") {
             return Err("Not in a git repository.".to_string());
         }
-        return Err(format!("Failed to get current branch: {}", stderr));
+        return Err(format!("Failed to {}: {}", action, stderr.trim()));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Get the current git branch name
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn get_current_branch(project_path: Option<String>) -> Result<String, String> {
+    let stdout = run_git(
+        &["rev-parse", "--abbrev-ref", "HEAD"],
+        project_path.as_deref().map(Path::new),
+        "get current branch",
+    )?;
     Ok(stdout.trim().to_string())
 }
 
@@ -35,35 +58,626 @@ pub fn get_current_branch(project_path: Option<String>) -> Result<String, String
 /// - project_path: Optional path to the project directory. If not provided, uses current working directory.
 #[tauri::command]
 pub fn list_git_branches(project_path: Option<String>) -> Result<Vec<String>, String> {
-    let mut cmd = hidden_command("git");
-    cmd.args(["branch", "--format=%(refname:short)"]);
+    let stdout = run_git(
+        &["branch", "--format=%(refname:short)"],
+        project_path.as_deref().map(Path::new),
+        "list branches",
+    )?;
 
-    if let Some(path) = project_path {
-        cmd.current_dir(path);
+    let branches: Vec<String> = stdout
+        .lines()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    Ok(branches)
+}
+
+// ─── Working Tree Status ──────────────────────────────────────────────────────
+
+/// One changed path from `git status --porcelain=v2`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFileStatus {
+    pub path: String,
+    /// Source path for renames/copies.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub orig_path: Option<String>,
+    /// Staged state code (`M`, `A`, `D`, `R`, ...; `.` = unchanged, `?` = untracked).
+    pub index: String,
+    /// Unstaged state code, same alphabet as `index`.
+    pub worktree: String,
+    /// True for unmerged (conflicted) entries.
+    #[serde(default)]
+    pub conflicted: bool,
+}
+
+/// Working tree summary: branch, upstream divergence and dirty files.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitStatus {
+    /// Current branch, or `None` when HEAD is detached.
+    pub branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream: Option<String>,
+    pub ahead: u32,
+    pub behind: u32,
+    pub files: Vec<GitFileStatus>,
+    pub is_dirty: bool,
+}
+
+/// Parse `git status --porcelain=v2 --branch -z` output. Records are
+/// NUL-terminated and paths are verbatim (no quoting), with a rename's
+/// original path in the record after it.
+fn parse_porcelain_v2(stdout: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+
+    let mut records = stdout.split('\0').filter(|r| !r.is_empty());
+    while let Some(line) = records.next() {
+        if let Some(header) = line.strip_prefix("# ") {
+            if let Some(head) = header.strip_prefix("branch.head ") {
+                if head != "(detached)" {
+                    status.branch = Some(head.to_string());
+                }
+            } else if let Some(upstream) = header.strip_prefix("branch.upstream ") {
+                status.upstream = Some(upstream.to_string());
+            } else if let Some(ab) = header.strip_prefix("branch.ab ") {
+                for part in ab.split_whitespace() {
+                    if let Some(n) = part.strip_prefix('+') {
+                        status.ahead = n.parse().unwrap_or(0);
+                    } else if let Some(n) = part.strip_prefix('-') {
+                        status.behind = n.parse().unwrap_or(0);
+                    }
+                }
+            }
+            continue;
+        }
+
+        let mut fields = line.splitn(2, ' ');
+        let kind = fields.next().unwrap_or("");
+        let rest = fields.next().unwrap_or("");
+        match kind {
+            // 1 XY sub mH mI mW hH hI path
+            "1" => {
+                let parts: Vec<&str> = rest.splitn(8, ' ').collect();
+                if let (Some(xy), Some(path)) = (parts.first(), parts.get(7)) {
+                    status.files.push(file_status(xy, path, None, false));
+                }
+            }
+            // 2 XY sub mH mI mW hH hI Xscore path<NUL>origPath
+            "2" => {
+                let parts: Vec<&str> = rest.splitn(9, ' ').collect();
+                let orig = records.next().map(str::to_string);
+                if let (Some(xy), Some(path)) = (parts.first(), parts.get(8)) {
+                    status.files.push(file_status(xy, path, orig, false));
+                }
+            }
+            // u XY sub m1 m2 m3 mW h1 h2 h3 path
+            "u" => {
+                let parts: Vec<&str> = rest.splitn(10, ' ').collect();
+                if let (Some(xy), Some(path)) = (parts.first(), parts.get(9)) {
+                    status.files.push(file_status(xy, path, None, true));
+                }
+            }
+            "?" => status.files.push(GitFileStatus {
+                path: rest.to_string(),
+                orig_path: None,
+                index: "?".to_string(),
+                worktree: "?".to_string(),
+                conflicted: false,
+            }),
+            _ => {}
+        }
     }
 
-    let output = cmd.output().map_err(|e| {
-        if e.kind() == std::io::ErrorKind::NotFound {
-            "Git is not installed. Please install Git.".to_string()
-        } else {
-            format!("Failed to run git: {}", e)
+    status.is_dirty = !status.files.is_empty();
+    status
+}
+
+fn file_status(xy: &str, path: &str, orig_path: Option<String>, conflicted: bool) -> GitFileStatus {
+    let mut chars = xy.chars();
+    GitFileStatus {
+        path: path.to_string(),
+        orig_path,
+        index: chars.next().unwrap_or('.').to_string(),
+        worktree: chars.next().unwrap_or('.').to_string(),
+        conflicted,
+    }
+}
+
+/// Get the working tree status: current branch, ahead/behind its upstream, and
+/// every modified, staged, untracked or conflicted file.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn git_status(project_path: Option<String>) -> Result<GitStatus, String> {
+    let stdout = run_git(
+        &["status", "--porcelain=v2", "--branch", "-z"],
+        project_path.as_deref().map(Path::new),
+        "get git status",
+    )?;
+    Ok(parse_porcelain_v2(&stdout))
+}
+
+// ─── Issue Branches ───────────────────────────────────────────────────────────
+
+/// Lowercase, ASCII-alphanumeric slug with single dashes, truncated to
+/// `MAX_SLUG_LEN` without leaving a trailing dash.
+fn slugify(title: &str) -> String {
+    let mut slug = String::with_capacity(title.len());
+    for c in title.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
         }
-    })?;
+    }
+    slug.truncate(MAX_SLUG_LEN);
+    slug.trim_end_matches('-').to_string()
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if stderr.contains("not a git repository") {
-            return Err("Not in a git repository.".to_string());
+/// Render a branch name from `template`. An empty slug collapses the separator
+/// in front of `{slug}` so `issue-{number}-{slug}` becomes `issue-42`, not `issue-42-`.
+fn render_branch_name(template: &str, number: u32, title: Option<&str>) -> String {
+    let slug = title.map(slugify).unwrap_or_default();
+    let mut name = template.replace("{number}", &number.to_string());
+    if slug.is_empty() {
+        for sep in ["-{slug}", "_{slug}", "/{slug}", "{slug}"] {
+            name = name.replace(sep, "");
         }
-        return Err(format!("Failed to list branches: {}", stderr));
+    } else {
+        name = name.replace("{slug}", &slug);
     }
+    name
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let branches: Vec<String> = stdout
-        .lines()
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect();
+/// Branch name for an issue, from `git.branchTemplate` or the default template.
+fn issue_branch_name(root: &Path, number: u32, title: Option<&str>) -> String {
+    let template = load_tiki_config(&root.join(".tiki"))
+        .git
+        .and_then(|g| g.branch_template)
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_BRANCH_TEMPLATE.to_string());
+    render_branch_name(&template, number, title)
+}
 
-    Ok(branches)
+fn local_branch_exists(root: &Path, branch: &str) -> bool {
+    run_git(
+        &["rev-parse", "--verify", "--quiet", &format!("refs/heads/{}", branch)],
+        Some(root),
+        "verify branch",
+    )
+    .is_ok()
+}
+
+/// Reject a ref argument git would parse as an option.
+fn reject_option_like(what: &str, value: &str) -> Result<(), String> {
+    if value.starts_with('-') {
+        return Err(format!("Invalid {}: {}", what, value));
+    }
+    Ok(())
+}
+
+/// Create (and optionally check out) the branch for an issue, named from the
+/// `git.branchTemplate` in `.tiki/config.json` (default `issue-{number}-{slug}`).
+/// Returns the branch name. An existing branch with that name is reused.
+/// - base: Optional start point (branch, tag or SHA). Defaults to HEAD.
+/// - checkout: Switch to the branch after creating it. Defaults to true.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn create_issue_branch(
+    issue_number: u32,
+    title: Option<String>,
+    base: Option<String>,
+    checkout: Option<bool>,
+    project_path: Option<String>,
+) -> Result<String, String> {
+    if let Some(ref start) = base {
+        reject_option_like("start point", start)?;
+    }
+    let root = project_root(project_path.as_deref())?;
+    let branch = issue_branch_name(&root, issue_number, title.as_deref());
+
+    if !local_branch_exists(&root, &branch) {
+        let mut args = vec!["branch", branch.as_str()];
+        if let Some(ref start) = base {
+            args.push(start);
+        }
+        run_git(&args, Some(&root), "create branch")?;
+    }
+
+    if checkout.unwrap_or(true) {
+        run_git(&["checkout", &branch, "--"], Some(&root), "check out branch")?;
+    }
+
+    Ok(branch)
+}
+
+/// Check out an existing branch, or create it first when `create` is true.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn checkout_branch(
+    branch: String,
+    create: Option<bool>,
+    project_path: Option<String>,
+) -> Result<(), String> {
+    reject_option_like("branch name", &branch)?;
+    let mut args = vec!["checkout"];
+    if create.unwrap_or(false) {
+        args.push("-b");
+    }
+    // `--` stops a branch that matches a file name being taken as a path.
+    args.extend([branch.as_str(), "--"]);
+    run_git(&args, project_path.as_deref().map(Path::new), "check out branch")?;
+    Ok(())
+}
+
+// ─── Worktrees ────────────────────────────────────────────────────────────────
+
+/// A git worktree, annotated with the issue it was created for (if any).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitWorktree {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+    /// Short branch name, or `None` for a detached or bare worktree.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// True for the repository's main working tree (always listed first by git).
+    pub is_main: bool,
+    #[serde(default)]
+    pub locked: bool,
+    /// True when git reports the worktree directory is gone.
+    #[serde(default)]
+    pub prunable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue_number: Option<u32>,
+}
+
+/// One issue → worktree mapping persisted in `.tiki/worktrees.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorktreeEntry {
+    path: String,
+    branch: String,
+    created_at: String,
+}
+
+/// `.tiki/worktrees.json`: the app-owned mapping from issue number to worktree.
+/// git itself has no notion of issues, so this is the only place the link lives.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct WorktreeRegistry {
+    #[serde(default)]
+    worktrees: BTreeMap<u32, WorktreeEntry>,
+}
+
+fn registry_path(root: &Path) -> PathBuf {
+    root.join(".tiki").join("worktrees.json")
+}
+
+fn load_registry(root: &Path) -> Result<WorktreeRegistry, String> {
+    Ok(fs_utils::read_json_resilient::<WorktreeRegistry>(&registry_path(root))?.unwrap_or_default())
+}
+
+fn save_registry(root: &Path, registry: &WorktreeRegistry) -> Result<(), String> {
+    let path = registry_path(root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create .tiki directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(registry).map_err(|e| e.to_string())?;
+    fs_utils::atomic_write(&path, &content)
+}
+
+/// Compare paths as git and the registry spell them. Canonicalizes when both
+/// exist (symlinks, `..`), otherwise falls back to a separator-normalized compare.
+fn same_path(a: &str, b: &str) -> bool {
+    if let (Ok(ca), Ok(cb)) = (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        return ca == cb;
+    }
+    let norm = |s: &str| s.replace('\\', "/").trim_end_matches('/').to_string();
+    norm(a) == norm(b)
+}
+
+/// Parse `git worktree list --porcelain` output.
+fn parse_worktree_list(stdout: &str) -> Vec<GitWorktree> {
+    let mut worktrees: Vec<GitWorktree> = Vec::new();
+
+    for line in stdout.lines() {
+        if let Some(path) = line.strip_prefix("worktree ") {
+            worktrees.push(GitWorktree {
+                path: path.to_string(),
+                head: None,
+                branch: None,
+                is_main: worktrees.is_empty(),
+                locked: false,
+                prunable: false,
+                issue_number: None,
+            });
+            continue;
+        }
+        let Some(current) = worktrees.last_mut() else {
+            continue;
+        };
+        if let Some(head) = line.strip_prefix("HEAD ") {
+            current.head = Some(head.to_string());
+        } else if let Some(branch) = line.strip_prefix("branch ") {
+            current.branch = Some(branch.trim_start_matches("refs/heads/").to_string());
+        } else if line == "locked" || line.starts_with("locked ") {
+            current.locked = true;
+        } else if line == "prunable" || line.starts_with("prunable ") {
+            current.prunable = true;
+        }
+    }
+
+    worktrees
+}
+
+/// Directory that holds per-issue worktrees: `git.worktreeDir` (relative to the
+/// project root) or `../<repo>-worktrees` next to the project.
+fn worktree_base_dir(root: &Path) -> PathBuf {
+    if let Some(dir) = load_tiki_config(&root.join(".tiki"))
+        .git
+        .and_then(|g| g.worktree_dir)
+        .filter(|d| !d.trim().is_empty())
+    {
+        return root.join(dir);
+    }
+    let name = root
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "project".to_string());
+    root.join("..").join(format!("{}-worktrees", name))
+}
+
+/// Create a worktree (and branch, named per `git.branchTemplate`) dedicated to
+/// one issue, so several issues can be worked in parallel on the same repo.
+/// The issue → worktree mapping is recorded in `.tiki/worktrees.json`.
+/// - base: Optional start point for a new branch. Defaults to HEAD.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn create_worktree_for_issue(
+    issue_number: u32,
+    title: Option<String>,
+    base: Option<String>,
+    project_path: Option<String>,
+) -> Result<GitWorktree, String> {
+    if let Some(ref start) = base {
+        reject_option_like("start point", start)?;
+    }
+    let root = project_root(project_path.as_deref())?;
+    let mut registry = load_registry(&root)?;
+
+    if let Some(existing) = registry.worktrees.get(&issue_number) {
+        if Path::new(&existing.path).exists() {
+            return Err(format!(
+                "Issue #{} already has a worktree at {}",
+                issue_number, existing.path
+            ));
+        }
+        // The directory is gone but git still has the worktree registered,
+        // with its branch marked as checked out there.
+        run_git(&["worktree", "prune"], Some(&root), "prune worktrees")?;
+        registry.worktrees.remove(&issue_number);
+        save_registry(&root, &registry)?;
+    }
+
+    let branch = issue_branch_name(&root, issue_number, title.as_deref());
+    let base_dir = worktree_base_dir(&root);
+    std::fs::create_dir_all(&base_dir)
+        .map_err(|e| format!("Failed to create worktree directory: {}", e))?;
+    let worktree_path = base_dir.join(format!("issue-{}", issue_number));
+    let path_str = worktree_path.to_string_lossy().into_owned();
+
+    if local_branch_exists(&root, &branch) {
+        run_git(&["worktree", "add", &path_str, &branch], Some(&root), "create worktree")?;
+    } else {
+        let mut args = vec!["worktree", "add", "-b", branch.as_str(), path_str.as_str()];
+        if let Some(ref start) = base {
+            args.push(start);
+        }
+        run_git(&args, Some(&root), "create worktree")?;
+    }
+
+    registry.worktrees.insert(
+        issue_number,
+        WorktreeEntry {
+            path: path_str.clone(),
+            branch: branch.clone(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+        },
+    );
+    save_registry(&root, &registry)?;
+
+    list_worktrees(project_path)?
+        .into_iter()
+        .find(|w| same_path(&w.path, &path_str))
+        .ok_or_else(|| format!("Worktree created at {} but not listed by git", path_str))
+}
+
+/// List the repository's worktrees, tagging each with the issue it belongs to
+/// according to `.tiki/worktrees.json`.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn list_worktrees(project_path: Option<String>) -> Result<Vec<GitWorktree>, String> {
+    let root = project_root(project_path.as_deref())?;
+    let stdout = run_git(&["worktree", "list", "--porcelain"], Some(&root), "list worktrees")?;
+    let registry = load_registry(&root)?;
+
+    let mut worktrees = parse_worktree_list(&stdout);
+    for worktree in &mut worktrees {
+        worktree.issue_number = registry
+            .worktrees
+            .iter()
+            .find(|(_, entry)| same_path(&entry.path, &worktree.path))
+            .map(|(number, _)| *number);
+    }
+    Ok(worktrees)
+}
+
+/// Remove a worktree by issue number (looked up in `.tiki/worktrees.json`) or
+/// by path, and drop its mapping. A worktree whose directory is already gone
+/// is pruned instead. The issue branch itself is kept.
+/// - force: Remove even with uncommitted changes. Defaults to false.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn remove_worktree(
+    issue_number: Option<u32>,
+    path: Option<String>,
+    force: Option<bool>,
+    project_path: Option<String>,
+) -> Result<(), String> {
+    let root = project_root(project_path.as_deref())?;
+    let mut registry = load_registry(&root)?;
+
+    let target = match (issue_number, path) {
+        (_, Some(p)) => p,
+        (Some(n), None) => registry
+            .worktrees
+            .get(&n)
+            .map(|e| e.path.clone())
+            .ok_or_else(|| format!("No worktree recorded for issue #{}", n))?,
+        (None, None) => return Err("Either an issue number or a worktree path is required".to_string()),
+    };
+
+    if Path::new(&target).exists() {
+        let mut args = vec!["worktree", "remove"];
+        if force.unwrap_or(false) {
+            args.push("--force");
+        }
+        args.push(&target);
+        run_git(&args, Some(&root), "remove worktree")?;
+    } else {
+        run_git(&["worktree", "prune"], Some(&root), "prune worktrees")?;
+    }
+
+    let before = registry.worktrees.len();
+    registry.worktrees.retain(|_, entry| !same_path(&entry.path, &target));
+    if registry.worktrees.len() != before {
+        save_registry(&root, &registry)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_porcelain_v2_status() {
+        let stdout = [
+            "# branch.oid 1234567890abcdef",
+            "# branch.head feature/x",
+            "# branch.upstream origin/feature/x",
+            "# branch.ab +2 -1",
+            "1 .M N... 100644 100644 100644 abc abc src/lib.rs",
+            "1 A. N... 000000 100644 100644 000 def src/new file.rs",
+            "2 R. N... 100644 100644 100644 abc abc R100 src/renamed.rs",
+            "src/old.rs",
+            "u UU N... 100644 100644 100644 100644 a b c conflict.rs",
+            "? untracked\t\"name\".txt",
+            "",
+        ]
+        .join("\0");
+        let status = parse_porcelain_v2(&stdout);
+        assert_eq!(status.branch.as_deref(), Some("feature/x"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/feature/x"));
+        assert_eq!(status.ahead, 2);
+        assert_eq!(status.behind, 1);
+        assert!(status.is_dirty);
+        assert_eq!(status.files.len(), 5);
+        assert_eq!(status.files[0].path, "src/lib.rs");
+        assert_eq!(status.files[0].index, ".");
+        assert_eq!(status.files[0].worktree, "M");
+        // Paths with spaces survive the fixed-field split.
+        assert_eq!(status.files[1].path, "src/new file.rs");
+        assert_eq!(status.files[2].path, "src/renamed.rs");
+        assert_eq!(status.files[2].orig_path.as_deref(), Some("src/old.rs"));
+        assert!(status.files[3].conflicted);
+        assert_eq!(status.files[3].path, "conflict.rs");
+        assert_eq!(status.files[4].index, "?");
+        // -z leaves special characters unquoted.
+        assert_eq!(status.files[4].path, "untracked\t\"name\".txt");
+    }
+
+    #[test]
+    fn clean_detached_status() {
+        let status = parse_porcelain_v2("# branch.oid abc\0# branch.head (detached)\0");
+        assert!(status.branch.is_none());
+        assert!(status.upstream.is_none());
+        assert!(!status.is_dirty);
+    }
+
+    #[test]
+    fn renders_branch_names_from_template() {
+        assert_eq!(
+            render_branch_name(DEFAULT_BRANCH_TEMPLATE, 42, Some("Fix: the *Login* flow!")),
+            "issue-42-fix-the-login-flow"
+        );
+        assert_eq!(render_branch_name(DEFAULT_BRANCH_TEMPLATE, 42, None), "issue-42");
+        assert_eq!(render_branch_name("feat/{slug}", 7, Some("")), "feat");
+        assert_eq!(render_branch_name("tiki/{number}", 7, Some("ignored")), "tiki/7");
+        let long = render_branch_name("{slug}", 1, Some(&"word ".repeat(20)));
+        assert!(long.len() <= MAX_SLUG_LEN);
+        assert!(!long.ends_with('-'));
+    }
+
+    #[test]
+    fn rejects_option_like_refs() {
+        assert!(reject_option_like("start point", "main").is_ok());
+        assert!(reject_option_like("start point", "origin/feature-x").is_ok());
+        assert_eq!(
+            reject_option_like("start point", "--orphan"),
+            Err("Invalid start point: --orphan".to_string())
+        );
+        // Each command checks before touching the repo.
+        assert!(checkout_branch("-f".to_string(), None, None).is_err());
+        assert!(create_issue_branch(1, None, Some("-d".to_string()), None, None).is_err());
+        assert!(create_worktree_for_issue(1, None, Some("--force".to_string()), None).is_err());
+    }
+
+    #[test]
+    fn parses_worktree_list() {
+        let stdout = "\
+worktree /repo
+HEAD aaa
+branch refs/heads/main
+
+worktree /repo-worktrees/issue-42
+HEAD bbb
+branch refs/heads/issue-42-login
+locked reason
+
+worktree /tmp/gone
+HEAD ccc
+detached
+prunable gitdir file points to non-existent location
+";
+        let worktrees = parse_worktree_list(stdout);
+        assert_eq!(worktrees.len(), 3);
+        assert!(worktrees[0].is_main);
+        assert_eq!(worktrees[0].branch.as_deref(), Some("main"));
+        assert!(!worktrees[1].is_main);
+        assert_eq!(worktrees[1].branch.as_deref(), Some("issue-42-login"));
+        assert!(worktrees[1].locked);
+        assert!(worktrees[2].branch.is_none());
+        assert!(worktrees[2].prunable);
+    }
+
+    #[test]
+    fn registry_round_trips_with_numeric_keys() {
+        let mut registry = WorktreeRegistry::default();
+        registry.worktrees.insert(
+            42,
+            WorktreeEntry {
+                path: "/repo-worktrees/issue-42".to_string(),
+                branch: "issue-42".to_string(),
+                created_at: "2026-01-01T00:00:00Z".to_string(),
+            },
+        );
+        let value = serde_json::to_value(&registry).unwrap();
+        assert_eq!(value["worktrees"]["42"]["createdAt"], "2026-01-01T00:00:00Z");
+        let back: WorktreeRegistry = serde_json::from_value(value).unwrap();
+        assert_eq!(back.worktrees[&42].branch, "issue-42");
+    }
 }
@@ -0,0 +1,351 @@
+//! GraphQL query layer over `gh api graphql`.
+//!
+//! The REST-backed `gh issue list` needs one process (and one request) per
+//! resource kind and caps out at `--limit`. A single GraphQL query returns
+//! issues together with their labels, comments and linked PRs, and the
+//! connection cursors let us walk repos with thousands of issues page by page.
+//! These requests draw from the `graphql` rate-limit bucket, not `core`.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::{backend_for, GhPriority, GitHubComment, GitHubCommentAuthor, GitHubIssue, GitHubLabel};
+
+/// GitHub's maximum page size for connections.
+const GRAPHQL_PAGE_SIZE: usize = 100;
+
+/// Comments fetched per issue in bulk mode. The full thread is still
+/// available through `fetch_issue_comments`; `commentCount` reports the total.
+const GRAPHQL_COMMENTS_PER_ISSUE: usize = 20;
+
+/// Run a GraphQL query against the current repository through the project's
+/// backend. `$owner`/`$name` are bound to the repo; `variables` are extra
+/// fields, sent with their JSON type.
+pub(crate) fn run_graphql<T: DeserializeOwned>(
+    query: &str,
+    variables: &[(&str, serde_json::Value)],
+    priority: GhPriority,
+    project_path: Option<&str>,
+) -> Result<T, String> {
+    let stdout = backend_for(project_path)?.graphql(query, variables, priority)?;
+
+    let response: GraphQlResponse<T> = serde_json::from_slice(&stdout)
+        .map_err(|e| format!("Failed to parse GraphQL response: {}", e))?;
+    response.into_result()
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlResponse<T> {
+    data: Option<T>,
+    #[serde(default)]
+    errors: Vec<GraphQlError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlError {
+    message: String,
+}
+
+impl<T> GraphQlResponse<T> {
+    fn into_result(self) -> Result<T, String> {
+        if !self.errors.is_empty() {
+            let messages: Vec<&str> = self.errors.iter().map(|e| e.message.as_str()).collect();
+            return Err(format!("GitHub GraphQL error: {}", messages.join("; ")));
+        }
+        self.data
+            .ok_or_else(|| "GitHub GraphQL response had no data".to_string())
+    }
+}
+
+// ─── Bulk Issue Types ─────────────────────────────────────────────────────────
+
+/// A pull request linked to an issue (one that will close it when merged).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkedPullRequest {
+    pub number: u32,
+    pub title: String,
+    pub state: String,
+    pub url: String,
+}
+
+/// An issue with its labels, recent comments and linked PRs, fetched in one
+/// GraphQL round trip. Serializes as a `GitHubIssue` plus the extra fields.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHubIssueDetail {
+    #[serde(flatten)]
+    pub issue: GitHubIssue,
+    pub comments: Vec<GitHubComment>,
+    pub comment_count: u32,
+    pub linked_pull_requests: Vec<LinkedPullRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RepoData {
+    repository: Option<RepoIssues>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RepoIssues {
+    issues: Connection<IssueNode>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Connection<T> {
+    #[serde(default)]
+    total_count: u32,
+    #[serde(default)]
+    page_info: Option<PageInfo>,
+    #[serde(default = "Vec::new")]
+    nodes: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfo {
+    has_next_page: bool,
+    end_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IssueNode {
+    number: u32,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    state: String,
+    url: String,
+    created_at: String,
+    updated_at: String,
+    labels: Option<Connection<GitHubLabel>>,
+    comments: Option<Connection<CommentNode>>,
+    closed_by_pull_requests_references: Option<Connection<LinkedPullRequest>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CommentNode {
+    id: String,
+    /// `null` for deleted ("ghost") accounts.
+    author: Option<GitHubCommentAuthor>,
+    body: String,
+    created_at: String,
+    url: String,
+}
+
+impl From<IssueNode> for GitHubIssueDetail {
+    fn from(node: IssueNode) -> Self {
+        let (comment_count, comments) = match node.comments {
+            Some(c) => (
+                c.total_count,
+                c.nodes
+                    .into_iter()
+                    .map(|n| GitHubComment {
+                        id: n.id,
+                        author: n.author.unwrap_or_else(|| GitHubCommentAuthor {
+                            login: "ghost".to_string(),
+                        }),
+                        body: n.body,
+                        created_at: n.created_at,
+                        url: n.url,
+                    })
+                    .collect(),
+            ),
+            None => (0, Vec::new()),
+        };
+        GitHubIssueDetail {
+            issue: GitHubIssue {
+                number: node.number,
+                title: node.title,
+                body: node.body,
+                state: node.state,
+                labels: node.labels.map(|l| l.nodes).unwrap_or_default(),
+                url: node.url,
+                created_at: node.created_at,
+                updated_at: node.updated_at,
+            },
+            comments,
+            comment_count,
+            linked_pull_requests: node
+                .closed_by_pull_requests_references
+                .map(|c| c.nodes)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Map the `gh issue list --state` vocabulary onto GraphQL `IssueState` values.
+fn issue_states(state: &str) -> Result<&'static str, String> {
+    match state {
+        "open" => Ok("[OPEN]"),
+        "closed" => Ok("[CLOSED]"),
+        "all" => Ok("[OPEN, CLOSED]"),
+        other => Err(format!("Unknown issue state filter: {}", other)),
+    }
+}
+
+fn issues_query(states: &str, include_comments: bool) -> String {
+    let comments = if include_comments {
+        format!(
+            "comments(last: {}) {{ totalCount nodes {{ id author {{ login }} body createdAt url }} }}",
+            GRAPHQL_COMMENTS_PER_ISSUE
+        )
+    } else {
+        "comments { totalCount }".to_string()
+    };
+    format!(
+        "query($owner: String!, $name: String!, $first: Int!, $cursor: String) {{
+  repository(owner: $owner, name: $name) {{
+    issues(first: $first, after: $cursor, states: {states}, orderBy: {{field: CREATED_AT, direction: DESC}}) {{
+      totalCount
+      pageInfo {{ hasNextPage endCursor }}
+      nodes {{
+        number title body state url createdAt updatedAt
+        labels(first: 50) {{ nodes {{ id name color description }} }}
+        {comments}
+        closedByPullRequestsReferences(first: 10, includeClosedPrs: true) {{ nodes {{ number title state url }} }}
+      }}
+    }}
+  }}
+}}"
+    )
+}
+
+/// Walk the issues connection page by page until `max` issues (or the end).
+pub(crate) fn paginate_issues(
+    state: &str,
+    max: Option<usize>,
+    include_comments: bool,
+    project_path: Option<&str>,
+) -> Result<Vec<GitHubIssueDetail>, String> {
+    let query = issues_query(issue_states(state)?, include_comments);
+    let mut out: Vec<GitHubIssueDetail> = Vec::new();
+    let mut cursor: Option<String> = None;
+
+    loop {
+        let remaining = max.map(|m| m.saturating_sub(out.len())).unwrap_or(GRAPHQL_PAGE_SIZE);
+        if remaining == 0 {
+            break;
+        }
+        let mut variables = vec![("first", remaining.min(GRAPHQL_PAGE_SIZE).into())];
+        if let Some(ref c) = cursor {
+            variables.push(("cursor", c.clone().into()));
+        }
+
+        let data: RepoData = run_graphql(&query, &variables, GhPriority::Normal, project_path)?;
+        let issues = data
+            .repository
+            .ok_or_else(|| "Repository not found or not accessible.".to_string())?
+            .issues;
+        out.extend(issues.nodes.into_iter().map(GitHubIssueDetail::from));
+
+        match issues.page_info {
+            Some(PageInfo { has_next_page: true, end_cursor: Some(next) }) => cursor = Some(next),
+            _ => break,
+        }
+    }
+
+    Ok(out)
+}
+
+/// Fetch issues with labels, recent comments and linked PRs in one GraphQL
+/// round trip per 100 issues, following cursors to the end of the list.
+/// - state: Filter by issue state ("open", "closed", "all"). Defaults to "open"
+/// - limit: Maximum number of issues. Defaults to no limit (every matching issue).
+/// - include_comments: Fetch the latest comments per issue. Defaults to true.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn fetch_github_issues_bulk(
+    state: Option<String>,
+    limit: Option<u32>,
+    include_comments: Option<bool>,
+    project_path: Option<String>,
+) -> Result<Vec<GitHubIssueDetail>, String> {
+    paginate_issues(
+        state.as_deref().unwrap_or("open"),
+        limit.map(|l| l as usize),
+        include_comments.unwrap_or(true),
+        project_path.as_deref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_issue_page_into_details() {
+        let payload = serde_json::json!({
+            "data": {
+                "repository": {
+                    "issues": {
+                        "totalCount": 2,
+                        "pageInfo": { "hasNextPage": true, "endCursor": "Y3Vyc29y" },
+                        "nodes": [{
+                            "number": 12,
+                            "title": "Crash on save",
+                            "body": "Steps...",
+                            "state": "OPEN",
+                            "url": "https://github.com/o/r/issues/12",
+                            "createdAt": "2026-01-01T00:00:00Z",
+                            "updatedAt": "2026-01-02T00:00:00Z",
+                            "labels": { "nodes": [{ "id": "L_1", "name": "bug", "color": "d73a4a", "description": null }] },
+                            "comments": {
+                                "totalCount": 3,
+                                "nodes": [
+                                    { "id": "IC_1", "author": { "login": "octocat" }, "body": "+1", "createdAt": "2026-01-01T01:00:00Z", "url": "u1" },
+                                    { "id": "IC_2", "author": null, "body": "old", "createdAt": "2026-01-01T02:00:00Z", "url": "u2" }
+                                ]
+                            },
+                            "closedByPullRequestsReferences": {
+                                "nodes": [{ "number": 40, "title": "Fix crash", "state": "OPEN", "url": "https://github.com/o/r/pull/40" }]
+                            }
+                        }]
+                    }
+                }
+            }
+        });
+
+        let response: GraphQlResponse<RepoData> = serde_json::from_value(payload).unwrap();
+        let issues = response.into_result().unwrap().repository.unwrap().issues;
+        let page = issues.page_info.as_ref().unwrap();
+        assert!(page.has_next_page);
+        assert_eq!(page.end_cursor.as_deref(), Some("Y3Vyc29y"));
+
+        let detail = GitHubIssueDetail::from(issues.nodes.into_iter().next().unwrap());
+        assert_eq!(detail.issue.number, 12);
+        assert_eq!(detail.issue.labels[0].name, "bug");
+        assert_eq!(detail.comment_count, 3);
+        assert_eq!(detail.comments[1].author.login, "ghost");
+        assert_eq!(detail.linked_pull_requests[0].number, 40);
+
+        // Flattened: the wire shape is a GitHubIssue plus the extra fields.
+        let value = serde_json::to_value(&detail).unwrap();
+        assert_eq!(value["number"], 12);
+        assert_eq!(value["createdAt"], "2026-01-01T00:00:00Z");
+        assert_eq!(value["commentCount"], 3);
+        assert!(value["linkedPullRequests"].is_array());
+    }
+
+    #[test]
+    fn graphql_errors_become_err() {
+        let payload = serde_json::json!({
+            "data": null,
+            "errors": [{ "message": "Field 'x' doesn't exist" }, { "message": "second" }]
+        });
+        let response: GraphQlResponse<RepoData> = serde_json::from_value(payload).unwrap();
+        let err = response.into_result().unwrap_err();
+        assert!(err.contains("Field 'x' doesn't exist; second"));
+    }
+
+    #[test]
+    fn builds_query_for_state_filters() {
+        assert!(issues_query(issue_states("all").unwrap(), false).contains("states: [OPEN, CLOSED]"));
+        assert!(issues_query("[OPEN]", false).contains("comments { totalCount }"));
+        assert!(issues_query("[OPEN]", true).contains("comments(last: 20)"));
+        assert!(issue_states("merged").is_err());
+    }
+}
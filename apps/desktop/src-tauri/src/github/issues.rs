@@ -1,8 +1,12 @@
 use serde::{Deserialize, Serialize};
-use std::io::Write;
-use std::process::Stdio;
+use std::collections::HashMap;
 
-use super::{hidden_command, resolve_claude_cli, run_gh_with_retry};
+use super::{
+    add_issue_to_github_project, apply_title_prefix, backend_for, cached_fetch,
+    find_issue_template, paginate_issues, render_issue_form, GhPriority, IssueEdit,
+    IssueTemplateKind, NewIssue, ISSUES_PROBE,
+};
+use crate::fs_utils::project_root;
 
 /// A GitHub label attached to an issue
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,111 +37,107 @@ pub struct GitHubIssue {
 /// Fetch GitHub issues from a repository
 /// - state: Filter by issue state ("open", "closed", "all"). Defaults to "open"
 /// - limit: Maximum number of issues to fetch. Defaults to 30
+/// - paginate: Follow GraphQL cursors past the first page. With no `limit`
+///   this returns every matching issue instead of truncating.
 /// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+///
+/// Results are cached per project and revalidated with a conditional request;
+/// when GitHub is unreachable the last cached list is returned.
 #[tauri::command]
 pub fn fetch_github_issues(
     state: Option<String>,
     limit: Option<u32>,
+    paginate: Option<bool>,
     project_path: Option<String>,
 ) -> Result<Vec<GitHubIssue>, String> {
     let state_filter = state.unwrap_or_else(|| "open".to_string());
-    let limit_val = limit.unwrap_or(30);
+    let paginate = paginate.unwrap_or(false);
+    let key = match (paginate, limit) {
+        (true, None) => format!("issues-{}-all", state_filter),
+        (_, l) => format!("issues-{}-{}", state_filter, l.unwrap_or(30)),
+    };
 
-    let output = run_gh_with_retry(move || {
-        let mut cmd = hidden_command("gh");
-        cmd.args([
-            "issue",
-            "list",
-            "--json",
-            "number,title,body,state,labels,url,createdAt,updatedAt",
-            "--state",
-            &state_filter,
-            "--limit",
-            &limit_val.to_string(),
-        ]);
-        if let Some(ref path) = project_path {
-            cmd.current_dir(path);
+    cached_fetch(project_path.as_deref(), &key, ISSUES_PROBE, None, || {
+        if paginate {
+            let details = paginate_issues(
+                &state_filter,
+                limit.map(|l| l as usize),
+                false,
+                project_path.as_deref(),
+            )?;
+            return Ok(details.into_iter().map(|d| d.issue).collect());
         }
-        cmd
-    })?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    serde_json::from_str(&stdout)
-        .map_err(|e| format!("Failed to parse issues: {}", e))
+        backend_for(project_path.as_deref())?.list_issues(&state_filter, limit.unwrap_or(30))
+    })
 }
 
 /// Create a new GitHub issue
+/// - template: Optional issue template (file name or name from
+///   `list_issue_templates`). Its title prefix, labels and assignees are
+///   merged in. Issue forms render `fields` into the body and fail on missing
+///   required fields; Markdown templates supply the body when `body` is empty.
+/// - fields: Form field values keyed by field id (or label when the form has no ids)
+/// - assignees: Logins to assign
+/// - milestone: Milestone title
+/// - add_to_project: Also add the issue to the `github.project` board
 /// - project_path: Optional path to the project directory. If not provided, uses current working directory.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn create_github_issue(
     title: String,
     body: Option<String>,
     labels: Vec<String>,
+    template: Option<String>,
+    fields: Option<HashMap<String, serde_json::Value>>,
+    assignees: Option<Vec<String>>,
+    milestone: Option<String>,
+    add_to_project: Option<bool>,
     project_path: Option<String>,
 ) -> Result<GitHubIssue, String> {
-    let mut args = vec![
-        "issue".to_string(),
-        "create".to_string(),
-        "--title".to_string(),
+    let mut issue = NewIssue {
         title,
-    ];
-
-    if let Some(body_text) = body {
-        args.push("--body".to_string());
-        args.push(body_text);
-    }
-
-    for label in labels {
-        args.push("--label".to_string());
-        args.push(label);
-    }
-
-    let mut cmd = hidden_command("gh");
-    cmd.args(&args);
-
-    if let Some(ref path) = project_path {
-        cmd.current_dir(path);
-    }
+        body: body.filter(|b| !b.trim().is_empty()),
+        labels,
+        assignees: assignees.unwrap_or_default(),
+        milestone: milestone.filter(|m| !m.trim().is_empty()),
+    };
 
-    let output = cmd.output().map_err(|e| {
-        if e.kind() == std::io::ErrorKind::NotFound {
-            "GitHub CLI (gh) is not installed. Please install it from https://cli.github.com/"
-                .to_string()
-        } else {
-            format!("Failed to run gh CLI: {}", e)
+    if let Some(ref name) = template {
+        let root = project_root(project_path.as_deref())?;
+        let template = find_issue_template(&root, name)?;
+        issue.title = apply_title_prefix(&template, &issue.title);
+        match template.kind {
+            IssueTemplateKind::Form => {
+                issue.body = Some(render_issue_form(&template, &fields.unwrap_or_default())?);
+            }
+            IssueTemplateKind::Markdown => {
+                if issue.body.is_none() {
+                    issue.body = template.body.clone();
+                }
+            }
         }
-    })?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if stderr.contains("not logged in") || stderr.contains("authentication") {
-            return Err(
-                "Not authenticated with GitHub. Run 'gh auth login' to authenticate.".to_string(),
-            );
+        for label in template.labels {
+            if !issue.labels.contains(&label) {
+                issue.labels.push(label);
+            }
         }
-        if stderr.contains("not a git repository") || stderr.contains("no git remotes") {
-            return Err(
-                "Not in a GitHub repository. Please open a project with a GitHub remote."
-                    .to_string(),
-            );
+        for assignee in template.assignees {
+            if !issue.assignees.contains(&assignee) {
+                issue.assignees.push(assignee);
+            }
         }
-        return Err(format!("Failed to create issue: {}", stderr));
     }
 
-    // gh issue create outputs the issue URL on success
-    // Parse the issue number from the URL and fetch full issue data
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let issue_url = stdout.trim();
-
-    // Extract issue number from URL (e.g., "https://github.com/owner/repo/issues/123")
-    let issue_number: u32 = issue_url
-        .rsplit('/')
-        .next()
-        .and_then(|n| n.parse().ok())
-        .ok_or_else(|| format!("Failed to parse issue number from URL: {}", issue_url))?;
+    let created = backend_for(project_path.as_deref())?.create_issue(&issue, GhPriority::User)?;
 
-    // Fetch the created issue using gh issue view
-    fetch_github_issue_by_number(issue_number, project_path)
+    if add_to_project.unwrap_or(false) {
+        // The issue exists either way; a board failure shouldn't hide that.
+        if let Err(e) = add_issue_to_github_project(created.number, None, project_path.clone()) {
+            log::warn!("Created issue #{} but could not add it to the project: {}", created.number, e);
+        }
+    }
+    Ok(created)
 }
 
 /// Fetch a single GitHub issue by number
@@ -146,23 +146,7 @@ pub fn fetch_github_issue_by_number(
     number: u32,
     project_path: Option<String>,
 ) -> Result<GitHubIssue, String> {
-    let output = run_gh_with_retry(move || {
-        let mut cmd = hidden_command("gh");
-        cmd.args([
-            "issue",
-            "view",
-            &number.to_string(),
-            "--json",
-            "number,title,body,state,labels,url,createdAt,updatedAt",
-        ]);
-        if let Some(ref path) = project_path {
-            cmd.current_dir(path);
-        }
-        cmd
-    })?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    serde_json::from_str(&stdout).map_err(|e| format!("Failed to parse issue: {}", e))
+    backend_for(project_path.as_deref())?.get_issue(number)
 }
 
 /// A GitHub user (comment author)
@@ -184,37 +168,12 @@ pub struct GitHubComment {
 }
 
 /// Fetch comments for a GitHub issue
-/// Uses `gh issue view` with --comments and --json to fetch comments
 #[tauri::command]
 pub fn fetch_issue_comments(
     number: u32,
     project_path: Option<String>,
 ) -> Result<Vec<GitHubComment>, String> {
-    let output = run_gh_with_retry(move || {
-        let mut cmd = hidden_command("gh");
-        cmd.args([
-            "issue",
-            "view",
-            &number.to_string(),
-            "--json",
-            "comments",
-            "--jq",
-            ".comments",
-        ]);
-        if let Some(ref path) = project_path {
-            cmd.current_dir(path);
-        }
-        cmd
-    })?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let trimmed = stdout.trim();
-    if trimmed.is_empty() || trimmed == "null" {
-        return Ok(vec![]);
-    }
-
-    serde_json::from_str(trimmed)
-        .map_err(|e| format!("Failed to parse comments: {}", e))
+    backend_for(project_path.as_deref())?.list_issue_comments(number)
 }
 
 /// Post a comment on a GitHub issue
@@ -224,108 +183,7 @@ pub fn post_issue_comment(
     body: String,
     project_path: Option<String>,
 ) -> Result<(), String> {
-    let mut cmd = hidden_command("gh");
-    cmd.args([
-        "issue",
-        "comment",
-        &number.to_string(),
-        "--body",
-        &body,
-    ]);
-
-    if let Some(path) = project_path {
-        cmd.current_dir(path);
-    }
-
-    let output = cmd.output().map_err(|e| {
-        if e.kind() == std::io::ErrorKind::NotFound {
-            "GitHub CLI (gh) is not installed. Please install it from https://cli.github.com/"
-                .to_string()
-        } else {
-            format!("Failed to run gh CLI: {}", e)
-        }
-    })?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if stderr.contains("not logged in") || stderr.contains("authentication") {
-            return Err(
-                "Not authenticated with GitHub. Run 'gh auth login' to authenticate.".to_string(),
-            );
-        }
-        return Err(format!("Failed to post comment: {}", stderr));
-    }
-
-    Ok(())
-}
-
-/// Enhance an issue description using Claude AI
-/// - description: The current description text to enhance
-/// - enhancement_type: One of "clarity", "technical", "simplify", "acceptance"
-#[tauri::command]
-pub fn enhance_issue_description(
-    description: String,
-    enhancement_type: String,
-) -> Result<String, String> {
-    let prompt = match enhancement_type.as_str() {
-        "clarity" => format!(
-            "Improve the clarity of this GitHub issue description. Make it clearer and easier to understand while preserving the original intent. Return ONLY the improved description text, no explanations:\n\n{}",
-            description
-        ),
-        "technical" => format!(
-            "Add technical details and implementation hints to this GitHub issue description. Include relevant technical context that would help a developer understand what needs to be done. Return ONLY the enhanced description text, no explanations:\n\n{}",
-            description
-        ),
-        "simplify" => format!(
-            "Simplify this GitHub issue description. Use simpler terms and reduce complexity while preserving all important information. Return ONLY the simplified description text, no explanations:\n\n{}",
-            description
-        ),
-        "acceptance" => format!(
-            "Add acceptance criteria to this GitHub issue description. Generate testable criteria that define when this issue is complete. Format with bullet points under an '## Acceptance Criteria' heading. Return the original description followed by the acceptance criteria, no other explanations:\n\n{}",
-            description
-        ),
-        _ => return Err(format!("Unknown enhancement type: {}", enhancement_type)),
-    };
-
-    // Use the claude CLI to enhance the description.
-    // Resolve it the same way `check_claude_cli` does — PATH plus known install
-    // locations — so the button being enabled always matches what runs here.
-    // Pipe the prompt via stdin to avoid shell metacharacter issues.
-    let cli = resolve_claude_cli().ok_or_else(|| {
-        "Claude CLI not found. Install it from https://github.com/anthropics/claude-code, \
-         then make sure it's on your PATH. (Checked PATH and common install locations \
-         such as the npm global directory and ~/.local/bin.)"
-            .to_string()
-    })?;
-
-    let mut child = cli
-        .command()
-        .arg("-p")
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| {
-            format!(
-                "Failed to run Claude CLI ({}): {}. Make sure Claude CLI is properly installed and configured.",
-                cli.program, e
-            )
-        })?;
-
-    // Write prompt to stdin and close it
-    if let Some(mut stdin) = child.stdin.take() {
-        stdin.write_all(prompt.as_bytes()).map_err(|e| format!("Failed to write to Claude CLI stdin: {}", e))?;
-    }
-
-    let output = child.wait_with_output().map_err(|e| format!("Failed to wait for Claude CLI: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Claude enhancement failed: {}", stderr));
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    Ok(stdout.trim().to_string())
+    backend_for(project_path.as_deref())?.post_issue_comment(number, &body, GhPriority::User)
 }
 
 /// Close a GitHub issue
@@ -333,39 +191,7 @@ pub fn enhance_issue_description(
 /// - project_path: Optional path to the project directory. If not provided, uses current working directory.
 #[tauri::command]
 pub fn close_github_issue(number: u32, project_path: Option<String>) -> Result<(), String> {
-    let mut cmd = hidden_command("gh");
-    cmd.args(["issue", "close", &number.to_string()]);
-
-    if let Some(path) = project_path {
-        cmd.current_dir(path);
-    }
-
-    let output = cmd.output().map_err(|e| {
-        if e.kind() == std::io::ErrorKind::NotFound {
-            "GitHub CLI (gh) is not installed. Please install it from https://cli.github.com/"
-                .to_string()
-        } else {
-            format!("Failed to run gh CLI: {}", e)
-        }
-    })?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if stderr.contains("not logged in") || stderr.contains("authentication") {
-            return Err(
-                "Not authenticated with GitHub. Run 'gh auth login' to authenticate.".to_string(),
-            );
-        }
-        if stderr.contains("not a git repository") || stderr.contains("no git remotes") {
-            return Err(
-                "Not in a GitHub repository. Please open a project with a GitHub remote."
-                    .to_string(),
-            );
-        }
-        return Err(format!("Failed to close issue: {}", stderr));
-    }
-
-    Ok(())
+    backend_for(project_path.as_deref())?.close_issue(number, GhPriority::User)
 }
 
 /// Edit an existing GitHub issue
@@ -379,65 +205,13 @@ pub fn edit_github_issue(
     remove_labels: Vec<String>,
     project_path: Option<String>,
 ) -> Result<(), String> {
-    let mut args = vec![
-        "issue".to_string(),
-        "edit".to_string(),
-        number.to_string(),
-    ];
-
-    if let Some(t) = title {
-        args.push("--title".to_string());
-        args.push(t);
-    }
-
-    if let Some(b) = body {
-        args.push("--body".to_string());
-        args.push(b);
-    }
-
-    for label in add_labels {
-        args.push("--add-label".to_string());
-        args.push(label);
-    }
-
-    for label in remove_labels {
-        args.push("--remove-label".to_string());
-        args.push(label);
-    }
-
-    let mut cmd = hidden_command("gh");
-    cmd.args(&args);
-
-    if let Some(path) = project_path {
-        cmd.current_dir(path);
-    }
-
-    let output = cmd.output().map_err(|e| {
-        if e.kind() == std::io::ErrorKind::NotFound {
-            "GitHub CLI (gh) is not installed. Please install it from https://cli.github.com/"
-                .to_string()
-        } else {
-            format!("Failed to run gh CLI: {}", e)
-        }
-    })?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if stderr.contains("not logged in") || stderr.contains("authentication") {
-            return Err(
-                "Not authenticated with GitHub. Run 'gh auth login' to authenticate.".to_string(),
-            );
-        }
-        if stderr.contains("not a git repository") || stderr.contains("no git remotes") {
-            return Err(
-                "Not in a GitHub repository. Please open a project with a GitHub remote."
-                    .to_string(),
-            );
-        }
-        return Err(format!("Failed to edit issue: {}", stderr));
-    }
-
-    Ok(())
+    let edit = IssueEdit {
+        title,
+        body,
+        add_labels,
+        remove_labels,
+    };
+    backend_for(project_path.as_deref())?.edit_issue(number, &edit, GhPriority::User)
 }
 
 #[cfg(test)]
@@ -1,29 +1,293 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
-use super::{hidden_command, run_gh_with_retry};
+use super::{backend_for, cached_fetch, cached_len, GhPriority, IssueEdit, LabelEdit};
+use crate::config::{load_tiki_config, LabelTemplate};
+use crate::fs_utils::project_root;
+
+/// The first page of labels serves as its own ETag probe; it covers the
+/// whole list for any repo with fewer than `LABELS_PROBE_PAGE` labels.
+const LABELS_PROBE: &str = "repos/{owner}/{repo}/labels?per_page=100";
+const LABELS_PROBE_PAGE: usize = 100;
+
+/// Edits past the probed page don't move its ETag, so longer lists are also
+/// refetched once their cached copy is this old.
+const LABELS_MAX_AGE_SECS: i64 = 600;
 
 /// A simple label structure for fetching available labels
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LabelInfo {
     pub name: String,
     pub color: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
 }
 
 /// Fetch available labels from a repository
 /// - project_path: Optional path to the project directory. If not provided, uses current working directory.
 #[tauri::command]
 pub fn fetch_github_labels(project_path: Option<String>) -> Result<Vec<LabelInfo>, String> {
-    let output = run_gh_with_retry(move || {
-        let mut cmd = hidden_command("gh");
-        cmd.args(["label", "list", "--json", "name,color", "--limit", "100"]);
-        if let Some(ref path) = project_path {
-            cmd.current_dir(path);
+    let max_age = cached_len(project_path.as_deref(), "labels")
+        .filter(|&len| len >= LABELS_PROBE_PAGE)
+        .map(|_| LABELS_MAX_AGE_SECS);
+    cached_fetch(project_path.as_deref(), "labels", LABELS_PROBE, max_age, || {
+        backend_for(project_path.as_deref())?.list_labels()
+    })
+}
+
+/// Normalize a label color to GitHub's form: six hex digits, no `#`.
+fn normalize_color(color: &str) -> Result<String, String> {
+    let hex = color.trim().trim_start_matches('#').to_lowercase();
+    if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(hex)
+    } else {
+        Err(format!("Invalid label color '{}': expected six hex digits", color))
+    }
+}
+
+/// Create a label
+/// - color: Hex color, with or without a leading `#`
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn create_github_label(
+    name: String,
+    color: String,
+    description: Option<String>,
+    project_path: Option<String>,
+) -> Result<LabelInfo, String> {
+    let label = LabelInfo {
+        name,
+        color: normalize_color(&color)?,
+        description: description.filter(|d| !d.is_empty()),
+    };
+    backend_for(project_path.as_deref())?.create_label(&label, GhPriority::User)?;
+    Ok(label)
+}
+
+/// Rename, recolor or re-describe a label. `None` fields are left unchanged.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn update_github_label(
+    name: String,
+    new_name: Option<String>,
+    color: Option<String>,
+    description: Option<String>,
+    project_path: Option<String>,
+) -> Result<(), String> {
+    let edit = LabelEdit {
+        new_name: new_name.filter(|n| n != &name),
+        color: color.as_deref().map(normalize_color).transpose()?,
+        description,
+    };
+    if edit == LabelEdit::default() {
+        return Ok(());
+    }
+    backend_for(project_path.as_deref())?.update_label(&name, &edit, GhPriority::User)
+}
+
+/// Delete a label. GitHub removes it from every issue and PR that has it.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn delete_github_label(name: String, project_path: Option<String>) -> Result<(), String> {
+    backend_for(project_path.as_deref())?.delete_label(&name, GhPriority::User)
+}
+
+// ─── Bulk Apply ───────────────────────────────────────────────────────────────
+
+/// An issue a bulk label edit could not update.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkLabelFailure {
+    pub number: u32,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkLabelResult {
+    pub updated: Vec<u32>,
+    pub failed: Vec<BulkLabelFailure>,
+}
+
+/// Add and/or remove labels across a set of issues. A failure on one issue
+/// doesn't stop the rest; failures are reported per issue.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn bulk_edit_issue_labels(
+    numbers: Vec<u32>,
+    add_labels: Vec<String>,
+    remove_labels: Vec<String>,
+    project_path: Option<String>,
+) -> Result<BulkLabelResult, String> {
+    let mut result = BulkLabelResult::default();
+    if add_labels.is_empty() && remove_labels.is_empty() {
+        return Ok(result);
+    }
+
+    let backend = backend_for(project_path.as_deref())?;
+    let edit = IssueEdit {
+        add_labels,
+        remove_labels,
+        ..Default::default()
+    };
+    // Background priority: a bulk edit must leave the core quota's reserve
+    // for the user's own actions.
+    for number in numbers {
+        match backend.edit_issue(number, &edit, GhPriority::Background) {
+            Ok(()) => result.updated.push(number),
+            Err(error) => {
+                log::warn!("bulk label edit: issue #{} failed: {}", number, error);
+                result.failed.push(BulkLabelFailure { number, error });
+            }
         }
-        cmd
-    })?;
+    }
+    Ok(result)
+}
+
+// ─── Template Sync ────────────────────────────────────────────────────────────
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    serde_json::from_str(&stdout).map_err(|e| format!("Failed to parse labels: {}", e))
+/// One change needed to bring a repo's labels in line with the template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LabelChange {
+    Create(LabelInfo),
+    Update { name: String, edit: LabelEdit },
+    Delete(String),
+}
+
+/// Diff existing labels against the template. Names match case-insensitively
+/// (as on GitHub); a template description of `None` leaves the existing one
+/// alone. Labels outside the template are only deleted when `prune` is set.
+fn plan_label_sync(
+    existing: &[LabelInfo],
+    template: &[LabelTemplate],
+    prune: bool,
+) -> Result<(Vec<LabelChange>, usize), String> {
+    let by_name: HashMap<String, &LabelInfo> = existing
+        .iter()
+        .map(|l| (l.name.to_lowercase(), l))
+        .collect();
+
+    let mut changes = Vec::new();
+    let mut unchanged = 0;
+    for wanted in template {
+        let color = normalize_color(&wanted.color)
+            .map_err(|e| format!("github.labels '{}': {}", wanted.name, e))?;
+        match by_name.get(&wanted.name.to_lowercase()) {
+            None => changes.push(LabelChange::Create(LabelInfo {
+                name: wanted.name.clone(),
+                color,
+                description: wanted.description.clone(),
+            })),
+            Some(current) => {
+                let edit = LabelEdit {
+                    new_name: (current.name != wanted.name).then(|| wanted.name.clone()),
+                    color: (current.color.to_lowercase() != color).then_some(color),
+                    description: wanted
+                        .description
+                        .clone()
+                        .filter(|d| current.description.as_deref().unwrap_or("") != d),
+                };
+                if edit == LabelEdit::default() {
+                    unchanged += 1;
+                } else {
+                    changes.push(LabelChange::Update {
+                        name: current.name.clone(),
+                        edit,
+                    });
+                }
+            }
+        }
+    }
+
+    if prune {
+        let keep: Vec<String> = template.iter().map(|t| t.name.to_lowercase()).collect();
+        for label in existing {
+            if !keep.contains(&label.name.to_lowercase()) {
+                changes.push(LabelChange::Delete(label.name.clone()));
+            }
+        }
+    }
+
+    Ok((changes, unchanged))
+}
+
+/// A label change `sync_github_labels` could not apply.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelSyncFailure {
+    pub name: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelSyncResult {
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub deleted: Vec<String>,
+    pub failed: Vec<LabelSyncFailure>,
+    pub unchanged: usize,
+    /// True when nothing was written (`dry_run`).
+    pub dry_run: bool,
+}
+
+/// Make the repo's labels match the `github.labels` template in
+/// `.tiki/config.json` (name, color, description). Runs at background
+/// priority; a failed change doesn't stop the rest and is reported in
+/// `failed`.
+/// - prune: Also delete labels that aren't in the template. Defaults to false
+/// - dry_run: Report the changes without applying them. Defaults to false
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn sync_github_labels(
+    prune: Option<bool>,
+    dry_run: Option<bool>,
+    project_path: Option<String>,
+) -> Result<LabelSyncResult, String> {
+    let root = project_root(project_path.as_deref())?;
+    let template = load_tiki_config(&root.join(".tiki"))
+        .github
+        .and_then(|g| g.labels)
+        .filter(|l| !l.is_empty())
+        .ok_or_else(|| "No label template. Add github.labels to .tiki/config.json.".to_string())?;
+
+    let backend = backend_for(project_path.as_deref())?;
+    let existing = backend.list_labels()?;
+    let (changes, unchanged) = plan_label_sync(&existing, &template, prune.unwrap_or(false))?;
+
+    let dry_run = dry_run.unwrap_or(false);
+    let mut result = LabelSyncResult {
+        unchanged,
+        dry_run,
+        ..Default::default()
+    };
+    let priority = GhPriority::Background;
+    let apply = |f: &dyn Fn() -> Result<(), String>| if dry_run { Ok(()) } else { f() };
+    for change in changes {
+        let (done, name, applied) = match change {
+            LabelChange::Create(label) => {
+                let applied = apply(&|| backend.create_label(&label, priority));
+                (&mut result.created, label.name, applied)
+            }
+            LabelChange::Update { name, edit } => {
+                let applied = apply(&|| backend.update_label(&name, &edit, priority));
+                (&mut result.updated, edit.new_name.unwrap_or(name), applied)
+            }
+            LabelChange::Delete(name) => {
+                let applied = apply(&|| backend.delete_label(&name, priority));
+                (&mut result.deleted, name, applied)
+            }
+        };
+        match applied {
+            Ok(()) => done.push(name),
+            Err(error) => {
+                log::warn!("label sync: '{}' failed: {}", name, error);
+                result.failed.push(LabelSyncFailure { name, error });
+            }
+        }
+    }
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -45,4 +309,65 @@ mod tests {
         assert_eq!(labels[1].name, "enhancement");
         assert_eq!(labels[1].color, "a2eeef");
     }
+
+    fn label(name: &str, color: &str, description: Option<&str>) -> LabelInfo {
+        LabelInfo {
+            name: name.to_string(),
+            color: color.to_string(),
+            description: description.map(str::to_string),
+        }
+    }
+
+    fn template(name: &str, color: &str, description: Option<&str>) -> LabelTemplate {
+        LabelTemplate {
+            name: name.to_string(),
+            color: color.to_string(),
+            description: description.map(str::to_string),
+            extra: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn plans_label_sync() {
+        let existing = vec![
+            label("Bug", "d73a4a", Some("Something broke")),
+            label("docs", "0075ca", None),
+            label("wontfix", "ffffff", None),
+        ];
+        let wanted = vec![
+            template("bug", "#D73A4A", None),
+            template("docs", "0075ca", Some("Documentation")),
+            template("good first issue", "7057ff", None),
+        ];
+
+        let (changes, unchanged) = plan_label_sync(&existing, &wanted, false).unwrap();
+        assert_eq!(unchanged, 0);
+        assert_eq!(
+            changes,
+            vec![
+                LabelChange::Update {
+                    name: "Bug".to_string(),
+                    edit: LabelEdit { new_name: Some("bug".to_string()), ..Default::default() },
+                },
+                LabelChange::Update {
+                    name: "docs".to_string(),
+                    edit: LabelEdit {
+                        description: Some("Documentation".to_string()),
+                        ..Default::default()
+                    },
+                },
+                LabelChange::Create(label("good first issue", "7057ff", None)),
+            ]
+        );
+
+        let (changes, _) = plan_label_sync(&existing, &wanted, true).unwrap();
+        assert_eq!(changes.last(), Some(&LabelChange::Delete("wontfix".to_string())));
+    }
+
+    #[test]
+    fn rejects_bad_template_colors() {
+        assert_eq!(normalize_color("#A2EEEF").unwrap(), "a2eeef");
+        let err = plan_label_sync(&[], &[template("x", "red", None)], false).unwrap_err();
+        assert!(err.contains("github.labels 'x'"), "{}", err);
+    }
 }
@@ -0,0 +1,398 @@
+//! GitHub milestones, and syncing a `TikiRelease`'s issue list with one.
+//!
+//! A release maps to the milestone titled after its version, unless the
+//! release's `activeWork` entry names one in `ReleaseRef::milestone`.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::{gh_command, run_gh_with_priority, run_gh_with_retry, GhPriority};
+use crate::commands::{load_tiki_releases, resolve_tiki_path, save_tiki_release};
+use crate::fs_utils;
+use crate::state::{TikiReleaseIssue, TikiState, WorkContext};
+
+/// A GitHub milestone (REST shape).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHubMilestone {
+    pub number: u32,
+    pub title: String,
+    /// "open" or "closed"
+    pub state: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, alias = "due_on", skip_serializing_if = "Option::is_none")]
+    pub due_on: Option<String>,
+    #[serde(default, alias = "open_issues")]
+    pub open_issues: u32,
+    #[serde(default, alias = "closed_issues")]
+    pub closed_issues: u32,
+    /// The milestone's web URL. REST payloads also carry an API `url`,
+    /// which is ignored.
+    #[serde(rename(deserialize = "html_url"))]
+    pub url: String,
+}
+
+/// Fetch milestones for a repository
+/// - state: "open", "closed" or "all". Defaults to "open"
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn fetch_github_milestones(
+    state: Option<String>,
+    project_path: Option<String>,
+) -> Result<Vec<GitHubMilestone>, String> {
+    let state = state.unwrap_or_else(|| "open".to_string());
+    if !matches!(state.as_str(), "open" | "closed" | "all") {
+        return Err(format!("Unknown milestone state filter: {}", state));
+    }
+
+    let output = run_gh_with_retry(|| {
+        let mut cmd = gh_command(project_path.as_deref());
+        cmd.args([
+            "api",
+            "--paginate",
+            &format!("repos/{{owner}}/{{repo}}/milestones?state={}&per_page=100", state),
+        ]);
+        cmd
+    })?;
+
+    parse_milestone_pages(&output.stdout)
+}
+
+/// `gh api --paginate` concatenates one JSON array per page.
+fn parse_milestone_pages(stdout: &[u8]) -> Result<Vec<GitHubMilestone>, String> {
+    let mut milestones = Vec::new();
+    for page in serde_json::Deserializer::from_slice(stdout).into_iter::<Vec<GitHubMilestone>>() {
+        milestones.extend(page.map_err(|e| format!("Failed to parse milestones: {}", e))?);
+    }
+    Ok(milestones)
+}
+
+/// Create a milestone
+/// - due_on: ISO 8601 timestamp (e.g. "2026-11-01T00:00:00Z")
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn create_github_milestone(
+    title: String,
+    description: Option<String>,
+    due_on: Option<String>,
+    project_path: Option<String>,
+) -> Result<GitHubMilestone, String> {
+    create_milestone(
+        &title,
+        description.as_deref(),
+        due_on.as_deref(),
+        GhPriority::User,
+        project_path.as_deref(),
+    )
+}
+
+fn create_milestone(
+    title: &str,
+    description: Option<&str>,
+    due_on: Option<&str>,
+    priority: GhPriority,
+    project_path: Option<&str>,
+) -> Result<GitHubMilestone, String> {
+    let output = run_gh_with_priority(priority, || {
+        let mut cmd = gh_command(project_path);
+        cmd.args([
+            "api",
+            "--method",
+            "POST",
+            "repos/{owner}/{repo}/milestones",
+            "-f",
+            &format!("title={}", title),
+        ]);
+        if let Some(d) = description {
+            cmd.args(["-f", &format!("description={}", d)]);
+        }
+        if let Some(due) = due_on {
+            cmd.args(["-f", &format!("due_on={}", due)]);
+        }
+        cmd
+    })?;
+
+    serde_json::from_slice(&output.stdout).map_err(|e| format!("Failed to parse milestone: {}", e))
+}
+
+/// Close a milestone
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn close_github_milestone(
+    number: u32,
+    project_path: Option<String>,
+) -> Result<GitHubMilestone, String> {
+    let output = run_gh_with_priority(GhPriority::User, || {
+        let mut cmd = gh_command(project_path.as_deref());
+        cmd.args([
+            "api",
+            "--method",
+            "PATCH",
+            &format!("repos/{{owner}}/{{repo}}/milestones/{}", number),
+            "-f",
+            "state=closed",
+        ]);
+        cmd
+    })?;
+
+    serde_json::from_slice(&output.stdout).map_err(|e| format!("Failed to parse milestone: {}", e))
+}
+
+// ─── Release ↔ Milestone Sync ─────────────────────────────────────────────────
+
+/// Which side wins when syncing a release with its milestone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MilestoneSyncDirection {
+    /// Make the milestone match the release (adds and removes on GitHub).
+    Push,
+    /// Make the release match the milestone (adds and removes locally).
+    Pull,
+    /// Union of both sides; nothing is removed.
+    Both,
+}
+
+/// An issue whose milestone a sync could not update.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MilestoneSyncFailure {
+    pub number: u32,
+    pub error: String,
+}
+
+/// What a sync changed. `milestone` is filled in after planning; issues that
+/// failed to update are listed in `failed` instead of the added/removed lists.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MilestoneSyncResult {
+    pub milestone: Option<GitHubMilestone>,
+    pub added_to_milestone: Vec<u32>,
+    pub removed_from_milestone: Vec<u32>,
+    pub added_to_release: Vec<u32>,
+    pub removed_from_release: Vec<u32>,
+    pub failed: Vec<MilestoneSyncFailure>,
+}
+
+/// Pure diff of the two issue sets for a sync direction.
+fn plan_milestone_sync(
+    release: &BTreeSet<u32>,
+    milestone: &BTreeSet<u32>,
+    direction: MilestoneSyncDirection,
+) -> MilestoneSyncResult {
+    let only_release: Vec<u32> = release.difference(milestone).copied().collect();
+    let only_milestone: Vec<u32> = milestone.difference(release).copied().collect();
+
+    let mut result = MilestoneSyncResult::default();
+    match direction {
+        MilestoneSyncDirection::Push => {
+            result.added_to_milestone = only_release;
+            result.removed_from_milestone = only_milestone;
+        }
+        MilestoneSyncDirection::Pull => {
+            result.added_to_release = only_milestone;
+            result.removed_from_release = only_release;
+        }
+        MilestoneSyncDirection::Both => {
+            result.added_to_milestone = only_release;
+            result.added_to_release = only_milestone;
+        }
+    }
+    result
+}
+
+/// Milestone title for a release: `ReleaseRef::milestone` from its
+/// `activeWork` entry when set, otherwise the version.
+fn release_milestone_title(tiki: &Path, version: &str) -> Result<String, String> {
+    let state = fs_utils::read_json_resilient::<TikiState>(&tiki.join("state.json"))?;
+    let configured = state.and_then(|s| {
+        s.active_work.into_values().find_map(|w| match w {
+            WorkContext::Release(ctx) if ctx.release.version == version => ctx.release.milestone,
+            _ => None,
+        })
+    });
+    Ok(configured
+        .filter(|m| !m.trim().is_empty())
+        .unwrap_or_else(|| version.to_string()))
+}
+
+#[derive(Debug, Deserialize)]
+struct MilestoneIssue {
+    number: u32,
+    title: String,
+}
+
+fn list_milestone_issues(title: &str, project_path: Option<&str>) -> Result<Vec<MilestoneIssue>, String> {
+    let output = run_gh_with_retry(|| {
+        let mut cmd = gh_command(project_path);
+        cmd.args([
+            "issue",
+            "list",
+            "--milestone",
+            title,
+            "--state",
+            "all",
+            "--limit",
+            "1000",
+            "--json",
+            "number,title",
+        ]);
+        cmd
+    })?;
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse milestone issues: {}", e))
+}
+
+/// Runs at background priority: a sync can touch many issues and must not
+/// drain the quota the user's own actions need.
+fn set_issue_milestone(number: u32, title: Option<&str>, project_path: Option<&str>) -> Result<(), String> {
+    run_gh_with_priority(GhPriority::Background, || {
+        let mut cmd = gh_command(project_path);
+        cmd.args(["issue", "edit", &number.to_string()]);
+        match title {
+            Some(t) => cmd.args(["--milestone", t]),
+            None => cmd.arg("--remove-milestone"),
+        };
+        cmd
+    })?;
+    Ok(())
+}
+
+/// Sync a Tiki release's issue list with its GitHub milestone.
+/// - direction: "push" (GitHub follows the release), "pull" (the release
+///   follows GitHub) or "both" (union, no removals). Defaults to "both"
+/// - The milestone is created on push/both when it doesn't exist yet.
+/// - Issues whose milestone can't be updated are reported in `failed`.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn sync_release_milestone(
+    version: String,
+    direction: Option<MilestoneSyncDirection>,
+    project_path: Option<String>,
+    tiki_path: Option<String>,
+) -> Result<MilestoneSyncResult, String> {
+    let direction = direction.unwrap_or(MilestoneSyncDirection::Both);
+    let tiki = match (&tiki_path, &project_path) {
+        (None, Some(project)) => Path::new(project).join(".tiki"),
+        _ => resolve_tiki_path(tiki_path.clone())?,
+    };
+    let tiki_str = tiki.to_string_lossy().into_owned();
+
+    let mut release = load_tiki_releases(Some(tiki_str.clone()), Some(false))?
+        .into_iter()
+        .find(|r| r.version == version)
+        .ok_or_else(|| format!("Release {} not found", version))?;
+    let title = release_milestone_title(&tiki, &version)?;
+
+    let existing = fetch_github_milestones(Some("all".to_string()), project_path.clone())?
+        .into_iter()
+        .find(|m| m.title == title);
+    let milestone = match existing {
+        Some(m) => Some(m),
+        None if direction == MilestoneSyncDirection::Pull => {
+            return Err(format!("No milestone titled '{}' on GitHub", title));
+        }
+        None => Some(create_milestone(
+            &title,
+            release.name.as_deref(),
+            None,
+            GhPriority::Background,
+            project_path.as_deref(),
+        )?),
+    };
+
+    let remote_issues = list_milestone_issues(&title, project_path.as_deref())?;
+    let release_set: BTreeSet<u32> = release.issues.iter().map(|i| i.number).collect();
+    let milestone_set: BTreeSet<u32> = remote_issues.iter().map(|i| i.number).collect();
+
+    let mut result = plan_milestone_sync(&release_set, &milestone_set, direction);
+    result.milestone = milestone;
+
+    // A failure on one issue doesn't stop the rest; it is reported instead.
+    let mut failed = Vec::new();
+    let mut apply = |numbers: &mut Vec<u32>, target: Option<&str>| {
+        numbers.retain(|&number| match set_issue_milestone(number, target, project_path.as_deref()) {
+            Ok(()) => true,
+            Err(error) => {
+                log::warn!("milestone sync: issue #{} failed: {}", number, error);
+                failed.push(MilestoneSyncFailure { number, error });
+                false
+            }
+        });
+    };
+    apply(&mut result.added_to_milestone, Some(&title));
+    apply(&mut result.removed_from_milestone, None);
+    result.failed = failed;
+
+    if !result.added_to_release.is_empty() || !result.removed_from_release.is_empty() {
+        release
+            .issues
+            .retain(|i| !result.removed_from_release.contains(&i.number));
+        release.issues.extend(
+            remote_issues
+                .into_iter()
+                .filter(|i| result.added_to_release.contains(&i.number))
+                .map(|i| TikiReleaseIssue {
+                    number: i.number,
+                    title: i.title,
+                }),
+        );
+        release.updated_at = Some(chrono::Utc::now().to_rfc3339());
+        save_tiki_release(release, Some(tiki_str))?;
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(items: &[u32]) -> BTreeSet<u32> {
+        items.iter().copied().collect()
+    }
+
+    #[test]
+    fn plans_each_sync_direction() {
+        let release = set(&[1, 2, 3]);
+        let milestone = set(&[3, 4]);
+
+        let push = plan_milestone_sync(&release, &milestone, MilestoneSyncDirection::Push);
+        assert_eq!(push.added_to_milestone, vec![1, 2]);
+        assert_eq!(push.removed_from_milestone, vec![4]);
+        assert!(push.added_to_release.is_empty() && push.removed_from_release.is_empty());
+
+        let pull = plan_milestone_sync(&release, &milestone, MilestoneSyncDirection::Pull);
+        assert_eq!(pull.added_to_release, vec![4]);
+        assert_eq!(pull.removed_from_release, vec![1, 2]);
+        assert!(pull.added_to_milestone.is_empty() && pull.removed_from_milestone.is_empty());
+
+        let both = plan_milestone_sync(&release, &milestone, MilestoneSyncDirection::Both);
+        assert_eq!(both.added_to_milestone, vec![1, 2]);
+        assert_eq!(both.added_to_release, vec![4]);
+        assert!(both.removed_from_milestone.is_empty() && both.removed_from_release.is_empty());
+    }
+
+    #[test]
+    fn parses_paginated_milestones() {
+        let page = |n: u32| {
+            serde_json::json!([{
+                "number": n, "title": format!("v0.{}.0", n), "state": "open",
+                "description": null, "due_on": null,
+                "open_issues": 2, "closed_issues": 5,
+                "url": format!("https://api.github.com/repos/o/r/milestones/{}", n),
+                "html_url": format!("https://github.com/o/r/milestone/{}", n)
+            }])
+            .to_string()
+        };
+        let stdout = format!("{}\n{}", page(1), page(2));
+
+        let milestones = parse_milestone_pages(stdout.as_bytes()).unwrap();
+        assert_eq!(milestones.len(), 2);
+        assert_eq!(milestones[1].title, "v0.2.0");
+        assert_eq!(milestones[0].closed_issues, 5);
+        assert_eq!(milestones[0].url, "https://github.com/o/r/milestone/1");
+    }
+}
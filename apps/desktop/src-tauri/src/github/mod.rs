@@ -1,20 +1,44 @@
 use std::process::Command;
 
 mod auth;
+mod backend;
+mod cache;
+mod enhance;
 mod git;
+mod graphql;
 mod issues;
 mod labels;
+mod milestones;
+mod projects;
 mod pulls;
 mod rate_limit;
 mod releases;
+mod rest;
+mod reviews;
+mod scheduler;
+mod target;
+mod templates;
+mod triage;
 
 pub use auth::*;
+pub use backend::*;
+pub use cache::*;
+pub use enhance::*;
 pub use git::*;
+pub use graphql::*;
 pub use issues::*;
 pub use labels::*;
+pub use milestones::*;
+pub use projects::*;
 pub use pulls::*;
 pub use rate_limit::*;
 pub use releases::*;
+pub use rest::*;
+pub use reviews::*;
+pub use scheduler::*;
+pub(crate) use target::*;
+pub use templates::*;
+pub use triage::*;
 
 /// Create a Command that suppresses console window creation on Windows.
 /// On non-Windows platforms, this is identical to `Command::new()`.
@@ -45,6 +69,8 @@ pub(crate) enum GhErrorKind {
     RateLimitExceeded,
     /// Secondary/abuse rate limit — short-lived, retry after backoff.
     SecondaryRateLimit,
+    /// DNS/connect/TLS failure reaching the API — the machine is offline.
+    Network,
     Other,
 }
 
@@ -58,6 +84,16 @@ pub(crate) fn classify_gh_error(stderr: &str) -> GhErrorKind {
     if lower.contains("rate limit") || lower.contains("x-ratelimit-remaining: 0") {
         return GhErrorKind::RateLimitExceeded;
     }
+    if lower.contains("error connecting to")
+        || lower.contains("no such host")
+        || lower.contains("could not resolve host")
+        || lower.contains("network is unreachable")
+        || lower.contains("connection refused")
+        || lower.contains("i/o timeout")
+        || lower.contains("tls handshake timeout")
+    {
+        return GhErrorKind::Network;
+    }
     if lower.contains("not logged in") || lower.contains("authentication") {
         return GhErrorKind::NotAuthenticated;
     }
@@ -83,31 +119,98 @@ pub(crate) fn gh_error_message(stderr: &str) -> String {
         GhErrorKind::NotAGitRepo => {
             "Not in a GitHub repository. Please open a project with a GitHub remote.".to_string()
         }
+        GhErrorKind::Network => format!("{} ({})", GH_NETWORK_ERROR, stderr.trim()),
         GhErrorKind::Other => format!("gh CLI failed: {}", stderr.trim()),
     }
 }
 
-/// Retry budget for transient `gh` failures (secondary rate limit only).
+/// Prefix of the message returned for `GhErrorKind::Network`. The cache layer
+/// matches on it to decide when to fall back to cached data.
+pub(crate) const GH_NETWORK_ERROR: &str = "Unable to reach GitHub — check your network connection";
+
+/// Backoff for secondary rate limits when GitHub sends no `Retry-After`.
 /// Total worst-case wait: 2 + 4 + 8 = 14 seconds across 4 attempts.
 const GH_RETRY_BACKOFF_SECS: &[u64] = &[2, 4, 8];
 
-/// Run a `gh` command with synchronous backoff retry on secondary rate limits.
+/// Run a `gh` command at normal priority against the core bucket, retrying
+/// secondary rate limits. See `run_gh_scheduled`.
+pub(crate) fn run_gh_with_retry<F>(factory: F) -> Result<std::process::Output, String>
+where
+    F: FnMut() -> Command,
+{
+    run_gh_scheduled(GhBucket::Core, GhPriority::Normal, factory, |output| {
+        output.status.success()
+    })
+}
+
+/// Like `run_gh_with_retry`, at an explicit priority.
+pub(crate) fn run_gh_with_priority<F>(
+    priority: GhPriority,
+    factory: F,
+) -> Result<std::process::Output, String>
+where
+    F: FnMut() -> Command,
+{
+    run_gh_scheduled(GhBucket::Core, priority, factory, |output| {
+        output.status.success()
+    })
+}
+
+/// Like `run_gh_with_retry`, but `accept` decides which outputs count as
+/// success. Used where a non-zero exit still carries a meaningful response
+/// (`gh api -i` exits 1 on `304 Not Modified`).
+pub(crate) fn run_gh_with_retry_accepting<F, A>(
+    factory: F,
+    accept: A,
+) -> Result<std::process::Output, String>
+where
+    F: FnMut() -> Command,
+    A: Fn(&std::process::Output) -> bool,
+{
+    run_gh_scheduled(GhBucket::Core, GhPriority::Normal, factory, accept)
+}
+
+/// The host whose quotas a `gh` command draws from: its `GH_HOST` (set by
+/// `gh_command` for pinned targets), else github.com.
+fn gh_quota_host(cmd: &Command) -> String {
+    cmd.get_envs()
+        .find(|(key, _)| *key == "GH_HOST")
+        .and_then(|(_, value)| value)
+        .map(|value| value.to_string_lossy().into_owned())
+        .unwrap_or_else(|| DEFAULT_GH_HOST.to_string())
+}
+
+/// Run a `gh` command through the rate-limit scheduler.
 ///
 /// `factory` returns a fresh `Command` each call (Command isn't Clone, and
 /// rebuilding via the same closure keeps args/env consistent across attempts).
+/// Every attempt waits for a scheduler permit for `bucket` at `priority`.
+/// A secondary rate limit blocks the whole bucket for `Retry-After` (or the
+/// fixed backoff) and retries; a primary limit marks the bucket exhausted so
+/// queued requests wait for the reset instead of failing one by one.
 ///
-/// Sleeps between attempts via `std::thread::sleep` — safe because Tauri
-/// commands run on a worker thread, not the UI thread. Returns the first
-/// successful Output, or the final error mapped through `gh_error_message`.
-pub(crate) fn run_gh_with_retry<F>(mut factory: F) -> Result<std::process::Output, String>
+/// Waiting blocks the calling thread — safe because Tauri commands run on a
+/// worker thread, not the UI thread. Returns the first accepted Output, or
+/// the final error mapped through `gh_error_message`.
+pub(crate) fn run_gh_scheduled<F, A>(
+    bucket: GhBucket,
+    priority: GhPriority,
+    mut factory: F,
+    accept: A,
+) -> Result<std::process::Output, String>
 where
     F: FnMut() -> Command,
+    A: Fn(&std::process::Output) -> bool,
 {
     let mut last_err: String = String::new();
     let attempts = GH_RETRY_BACKOFF_SECS.len() + 1;
 
     for attempt in 0..attempts {
-        let output = factory().output().map_err(|e| {
+        let mut cmd = factory();
+        let host = gh_quota_host(&cmd);
+        let refresh = || query_gh_host_rate_limit(&host);
+        let permit = acquire(&host, bucket, priority, Some(&refresh));
+        let output = cmd.output().map_err(|e| {
             if e.kind() == std::io::ErrorKind::NotFound {
                 "GitHub CLI (gh) is not installed. Please install it from https://cli.github.com/"
                     .to_string()
@@ -115,28 +218,34 @@ where
                 format!("Failed to run gh CLI: {}", e)
             }
         })?;
+        drop(permit);
 
-        if output.status.success() {
+        if accept(&output) {
             return Ok(output);
         }
 
         let stderr = String::from_utf8_lossy(&output.stderr);
-        let kind = classify_gh_error(&stderr);
-
-        if kind != GhErrorKind::SecondaryRateLimit {
-            return Err(gh_error_message(&stderr));
+        match classify_gh_error(&stderr) {
+            GhErrorKind::SecondaryRateLimit => {}
+            GhErrorKind::RateLimitExceeded => {
+                mark_exhausted(&host, bucket);
+                return Err(gh_error_message(&stderr));
+            }
+            _ => return Err(gh_error_message(&stderr)),
         }
 
-        last_err = stderr.into_owned();
-        if let Some(delay) = GH_RETRY_BACKOFF_SECS.get(attempt) {
+        if let Some(&backoff) = GH_RETRY_BACKOFF_SECS.get(attempt) {
+            let delay = parse_retry_after(&stderr).unwrap_or(backoff);
             log::warn!(
-                "gh secondary rate limit (attempt {}/{}); sleeping {}s before retry",
+                "gh secondary rate limit (attempt {}/{}); pausing {:?} requests for {}s",
                 attempt + 1,
                 attempts,
+                bucket,
                 delay
             );
-            std::thread::sleep(std::time::Duration::from_secs(*delay));
+            block_bucket(&host, bucket, delay);
         }
+        last_err = stderr.into_owned();
     }
 
     Err(gh_error_message(&last_err))
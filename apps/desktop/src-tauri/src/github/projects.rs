@@ -0,0 +1,302 @@
+//! GitHub Projects (v2) board integration.
+//!
+//! Configured per project under `github.project` in `.tiki/config.json`:
+//! the board number, optionally its owner, and the single-select field whose
+//! option should track each issue's `WorkStatus`. Projects v2 is GraphQL-only.
+
+use serde::{Deserialize, Serialize};
+
+use super::{run_graphql, GhPriority};
+use crate::config::{load_tiki_config, GitHubProjectConfig};
+use crate::fs_utils::project_root;
+use crate::state::WorkStatus;
+
+const DEFAULT_STATUS_FIELD: &str = "Status";
+
+/// Option names on GitHub's default board template.
+fn default_status_option(status: &WorkStatus) -> &'static str {
+    match status {
+        WorkStatus::Pending | WorkStatus::Reviewing | WorkStatus::Planning => "Todo",
+        WorkStatus::Executing | WorkStatus::Paused | WorkStatus::Failed | WorkStatus::Shipping => {
+            "In Progress"
+        }
+        WorkStatus::Completed => "Done",
+    }
+}
+
+/// Board option for a status: `statusMap` entry (keyed by the lowercase
+/// status name) or the default.
+fn status_option_name(config: &GitHubProjectConfig, status: &WorkStatus) -> String {
+    let key = serde_json::to_value(status)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+    config
+        .status_map
+        .as_ref()
+        .and_then(|m| m.get(&key))
+        .cloned()
+        .unwrap_or_else(|| default_status_option(status).to_string())
+}
+
+// ─── GraphQL Shapes ───────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct ResolveData {
+    repository: Option<ResolveRepository>,
+    #[serde(rename = "repositoryOwner", default)]
+    repository_owner: Option<ProjectOwner>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResolveRepository {
+    issue: Option<NodeId>,
+    #[serde(default)]
+    owner: Option<ProjectOwner>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NodeId {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectOwner {
+    #[serde(rename = "projectV2", default)]
+    project: Option<ProjectNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectNode {
+    id: String,
+    title: String,
+    #[serde(default)]
+    field: Option<SingleSelectField>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SingleSelectField {
+    /// Absent when the named field isn't a single-select field.
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    options: Vec<SelectOption>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SelectOption {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AddItemData {
+    add_project_v2_item_by_id: AddItemPayload,
+}
+
+#[derive(Debug, Deserialize)]
+struct AddItemPayload {
+    item: NodeId,
+}
+
+fn resolve_query(config: &GitHubProjectConfig) -> String {
+    let project = "... on ProjectV2Owner { projectV2(number: $number) { id title field(name: $field) { ... on ProjectV2SingleSelectField { id options { id name } } } } }";
+    match config.owner {
+        Some(_) => format!(
+            "query($owner: String!, $name: String!, $number: Int!, $issue: Int!, $field: String!, $login: String!) {{
+  repository(owner: $owner, name: $name) {{ issue(number: $issue) {{ id }} }}
+  repositoryOwner(login: $login) {{ {} }}
+}}",
+            project
+        ),
+        None => format!(
+            "query($owner: String!, $name: String!, $number: Int!, $issue: Int!, $field: String!) {{
+  repository(owner: $owner, name: $name) {{ issue(number: $issue) {{ id }} owner {{ {} }} }}
+}}",
+            project
+        ),
+    }
+}
+
+const ADD_ITEM_MUTATION: &str = "mutation($projectId: ID!, $contentId: ID!) {
+  addProjectV2ItemById(input: {projectId: $projectId, contentId: $contentId}) { item { id } }
+}";
+
+const SET_STATUS_MUTATION: &str = "mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!, $optionId: String!) {
+  updateProjectV2ItemFieldValue(input: {projectId: $projectId, itemId: $itemId, fieldId: $fieldId, value: {singleSelectOptionId: $optionId}}) { projectV2Item { id } }
+}";
+
+// ─── Commands ─────────────────────────────────────────────────────────────────
+
+/// Where an issue landed on the project board.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHubProjectItem {
+    pub project_title: String,
+    pub item_id: String,
+    /// Option set on the status field, if a status was given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+/// Add an issue to the configured Projects (v2) board and, when `status` is
+/// given, set the board's status field from it. Adding is idempotent, so this
+/// doubles as "sync the status of an issue already on the board".
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn add_issue_to_github_project(
+    number: u32,
+    status: Option<WorkStatus>,
+    project_path: Option<String>,
+) -> Result<GitHubProjectItem, String> {
+    let root = project_root(project_path.as_deref())?;
+    let config = load_tiki_config(&root.join(".tiki"))
+        .github
+        .and_then(|g| g.project)
+        .ok_or_else(|| {
+            "No GitHub project configured. Set github.project.number in .tiki/config.json."
+                .to_string()
+        })?;
+    let field_name = config
+        .status_field
+        .clone()
+        .unwrap_or_else(|| DEFAULT_STATUS_FIELD.to_string());
+
+    let mut variables = vec![
+        ("number", config.number.into()),
+        ("issue", number.into()),
+        ("field", field_name.as_str().into()),
+    ];
+    if let Some(ref owner) = config.owner {
+        variables.push(("login", owner.as_str().into()));
+    }
+    let resolved: ResolveData = run_graphql(
+        &resolve_query(&config),
+        &variables,
+        GhPriority::Normal,
+        project_path.as_deref(),
+    )?;
+    let repository = resolved
+        .repository
+        .ok_or("Repository not found or not accessible.")?;
+    let issue_id = repository
+        .issue
+        .ok_or_else(|| format!("Issue #{} not found", number))?
+        .id;
+    let project = resolved
+        .repository_owner
+        .or(repository.owner)
+        .and_then(|o| o.project)
+        .ok_or_else(|| {
+            format!(
+                "Project #{} not found (check github.project and the token's project scope)",
+                config.number
+            )
+        })?;
+
+    let added: AddItemData = run_graphql(
+        ADD_ITEM_MUTATION,
+        &[
+            ("projectId", project.id.as_str().into()),
+            ("contentId", issue_id.into()),
+        ],
+        GhPriority::User,
+        project_path.as_deref(),
+    )?;
+    let item_id = added.add_project_v2_item_by_id.item.id;
+
+    let status = match status {
+        Some(ref s) => {
+            let wanted = status_option_name(&config, s);
+            let field = project
+                .field
+                .and_then(|f| f.id.map(|id| (id, f.options)))
+                .ok_or_else(|| {
+                    format!("'{}' is not a single-select field on {}", field_name, project.title)
+                })?;
+            let option = field
+                .1
+                .iter()
+                .find(|o| o.name.eq_ignore_ascii_case(&wanted))
+                .ok_or_else(|| {
+                    format!("{} field has no '{}' option", field_name, wanted)
+                })?;
+
+            run_graphql::<serde_json::Value>(
+                SET_STATUS_MUTATION,
+                &[
+                    ("projectId", project.id.as_str().into()),
+                    ("itemId", item_id.as_str().into()),
+                    ("fieldId", field.0.as_str().into()),
+                    ("optionId", option.id.as_str().into()),
+                ],
+                GhPriority::User,
+                project_path.as_deref(),
+            )?;
+            Some(option.name.clone())
+        }
+        None => None,
+    };
+
+    Ok(GitHubProjectItem {
+        project_title: project.title,
+        item_id,
+        status,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn maps_work_status_to_board_options() {
+        let mut config = GitHubProjectConfig {
+            number: 1,
+            ..Default::default()
+        };
+        assert_eq!(status_option_name(&config, &WorkStatus::Planning), "Todo");
+        assert_eq!(status_option_name(&config, &WorkStatus::Executing), "In Progress");
+        assert_eq!(status_option_name(&config, &WorkStatus::Completed), "Done");
+
+        config.status_map = Some(HashMap::from([("failed".to_string(), "Blocked".to_string())]));
+        assert_eq!(status_option_name(&config, &WorkStatus::Failed), "Blocked");
+        assert_eq!(status_option_name(&config, &WorkStatus::Shipping), "In Progress");
+    }
+
+    #[test]
+    fn resolves_project_through_configured_owner() {
+        let mut config = GitHubProjectConfig {
+            number: 4,
+            ..Default::default()
+        };
+        let query = resolve_query(&config);
+        assert!(query.contains("owner { ... on ProjectV2Owner"));
+        assert!(query.contains("field(name: $field)"));
+        assert!(!query.contains("repositoryOwner"));
+        assert!(!query.contains("$login"));
+
+        config.owner = Some("my-org".to_string());
+        let query = resolve_query(&config);
+        assert!(query.contains("$login: String!"));
+        assert!(query.contains("repositoryOwner(login: $login)"));
+    }
+
+    #[test]
+    fn parses_resolve_response() {
+        let payload = serde_json::json!({
+            "repository": { "issue": { "id": "I_1" }, "owner": { "projectV2": {
+                "id": "PVT_1", "title": "Roadmap",
+                "field": { "id": "F_1", "options": [{ "id": "o1", "name": "Todo" }] }
+            } } }
+        });
+        let data: ResolveData = serde_json::from_value(payload).unwrap();
+        let repo = data.repository.unwrap();
+        assert_eq!(repo.issue.unwrap().id, "I_1");
+        let project = repo.owner.unwrap().project.unwrap();
+        assert_eq!(project.title, "Roadmap");
+        assert_eq!(project.field.unwrap().options[0].name, "Todo");
+    }
+}
@@ -1,6 +1,15 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
 
-use super::{hidden_command, run_gh_with_retry, GitHubLabel};
+use super::git::run_git;
+use super::{
+    backend_for, cached_fetch, fetch_github_issue_by_number, gh_command, run_gh_with_priority,
+    run_gh_with_retry, GhPriority, GhTarget, GitHubLabel, ISSUES_PROBE, PR_CACHE_MAX_AGE_SECS,
+};
+use crate::commands::{get_plan, resolve_tiki_path};
+use crate::fs_utils;
+use crate::state::{PhaseStatus, TikiPlan, TikiState, WorkContext};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -124,34 +133,15 @@ pub fn fetch_github_prs(
 ) -> Result<Vec<GitHubPullRequest>, String> {
     let filter = state_filter.unwrap_or_else(|| "open".to_string());
     let pr_limit = limit.unwrap_or(30);
+    let key = format!("prs-{}-{}", filter, pr_limit);
 
-    let output = run_gh_with_retry(move || {
-        let mut cmd = hidden_command("gh");
-        cmd.args([
-            "pr",
-            "list",
-            "--json",
-            "number,title,state,headRefName,baseRefName,url,isDraft,reviewDecision,statusCheckRollup,labels,body,author",
-            "--state",
-            &filter,
-            "--limit",
-            &pr_limit.to_string(),
-        ]);
-        if let Some(ref path) = project_path {
-            cmd.current_dir(path);
-        }
-        cmd
-    })?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let trimmed = stdout.trim();
-
-    if trimmed.is_empty() || trimmed == "[]" {
-        return Ok(vec![]);
-    }
-
-    serde_json::from_str(trimmed)
-        .map_err(|e| format!("Failed to parse PR list: {}", e))
+    cached_fetch(
+        project_path.as_deref(),
+        &key,
+        ISSUES_PROBE,
+        Some(PR_CACHE_MAX_AGE_SECS),
+        || backend_for(project_path.as_deref())?.list_pull_requests(&filter, pr_limit),
+    )
 }
 
 /// Fetch detailed information about a single GitHub pull request
@@ -163,7 +153,7 @@ pub fn fetch_github_pr_detail(
     project_path: Option<String>,
 ) -> Result<GitHubPrDetail, String> {
     let output = run_gh_with_retry(move || {
-        let mut cmd = hidden_command("gh");
+        let mut cmd = gh_command(project_path.as_deref());
         cmd.args([
             "pr",
             "view",
@@ -171,9 +161,6 @@ pub fn fetch_github_pr_detail(
             "--json",
             "number,title,body,state,headRefName,baseRefName,url,isDraft,reviewDecision,statusCheckRollup,labels,author,additions,deletions,commits,files,reviews",
         ]);
-        if let Some(ref path) = project_path {
-            cmd.current_dir(path);
-        }
         cmd
     })?;
 
@@ -183,6 +170,285 @@ pub fn fetch_github_pr_detail(
         .map_err(|e| format!("Failed to parse PR detail: {}", e))
 }
 
+// ─── Pull Request Authoring ───────────────────────────────────────────────────
+
+/// Whether a success criterion is met. An explicit `verified` flag wins;
+/// otherwise it is derived the same way as `deriveCriteriaVerification` in
+/// `packages/shared/src/types/plan.ts`: every phase listed for the criterion in
+/// `coverageMatrix` must be completed (no coverage entry = unverified).
+fn criterion_verified(plan: &TikiPlan, id: &str, explicit: Option<bool>) -> bool {
+    if let Some(v) = explicit {
+        return v;
+    }
+    let covering = match plan.coverage_matrix.as_ref().and_then(|m| m.get(id)) {
+        Some(phases) if !phases.is_empty() => phases,
+        _ => return false,
+    };
+    let status_by_number: HashMap<u32, &PhaseStatus> =
+        plan.phases.iter().map(|p| (p.number, &p.status)).collect();
+    covering
+        .iter()
+        .all(|n| status_by_number.get(n) == Some(&&PhaseStatus::Completed))
+}
+
+/// Render a PR body from a plan: description, phases with their summaries,
+/// success criteria as a verification checklist, and a closing keyword so
+/// merging the PR closes the issue.
+fn build_pr_body(issue_number: u32, plan: Option<&TikiPlan>) -> String {
+    let mut body = String::new();
+
+    if let Some(plan) = plan {
+        if let Some(desc) = plan.description.as_deref().filter(|d| !d.trim().is_empty()) {
+            body.push_str("## Summary\n\n");
+            body.push_str(desc.trim());
+            body.push_str("\n\n");
+        }
+
+        if !plan.phases.is_empty() {
+            body.push_str("## Phases\n\n");
+            for phase in &plan.phases {
+                let mark = match phase.status {
+                    PhaseStatus::Completed => "x",
+                    _ => " ",
+                };
+                body.push_str(&format!("- [{}] **Phase {}: {}**", mark, phase.number, phase.title));
+                match phase.status {
+                    PhaseStatus::Skipped => body.push_str(" _(skipped)_"),
+                    PhaseStatus::Failed => body.push_str(" _(failed)_"),
+                    _ => {}
+                }
+                if let Some(summary) = phase.summary.as_deref().filter(|s| !s.trim().is_empty()) {
+                    body.push_str(" — ");
+                    body.push_str(summary.trim());
+                }
+                body.push('\n');
+            }
+            body.push('\n');
+        }
+
+        if let Some(criteria) = plan.success_criteria.as_ref().filter(|c| !c.is_empty()) {
+            body.push_str("## Success Criteria\n\n");
+            for criterion in criteria {
+                let mark = if criterion_verified(plan, &criterion.id, criterion.verified) {
+                    "x"
+                } else {
+                    " "
+                };
+                body.push_str(&format!("- [{}] {}: {}\n", mark, criterion.id, criterion.description));
+            }
+            body.push('\n');
+        }
+    }
+
+    body.push_str(&format!("Closes #{}\n", issue_number));
+    body
+}
+
+/// Parse the PR number from the URL `gh pr create` prints on success.
+fn parse_pr_number(url: &str) -> Option<u32> {
+    url.trim().rsplit('/').next().and_then(|n| n.parse().ok())
+}
+
+/// Record the PR number on the issue's `activeWork` entry in `.tiki/state.json`.
+/// A missing entry is not an error — the PR may be for an issue Tiki isn't tracking.
+fn record_pull_request(tiki_path: &Path, issue_number: u32, pr_number: u32) -> Result<(), String> {
+    let state_file = tiki_path.join("state.json");
+    let mut state = match fs_utils::read_json_resilient::<TikiState>(&state_file)? {
+        Some(s) => s,
+        None => return Ok(()),
+    };
+
+    match state.active_work.get_mut(&format!("issue:{}", issue_number)) {
+        Some(WorkContext::Issue(ctx)) => {
+            ctx.pull_request = Some(pr_number);
+            ctx.last_activity = Some(chrono::Utc::now().to_rfc3339());
+        }
+        _ => {
+            log::info!(
+                "PR #{} created for issue #{} with no activeWork entry; not recorded",
+                pr_number,
+                issue_number
+            );
+            return Ok(());
+        }
+    }
+
+    let content = serde_json::to_string_pretty(&state).map_err(|e| e.to_string())?;
+    fs_utils::atomic_write(&state_file, &content)
+}
+
+/// Remote to push a PR branch to: the one pointing at the project's pinned
+/// repo, falling back to `origin`.
+fn push_remote(target: &GhTarget, root: Option<&Path>) -> String {
+    if target.repo.is_some() {
+        match run_git(&["remote", "-v"], root, "list remotes") {
+            Ok(remotes) => {
+                if let Some(remote) = target.matching_remote(&remotes) {
+                    return remote;
+                }
+            }
+            Err(e) => log::warn!("Falling back to origin for push: {}", e),
+        }
+    }
+    "origin".to_string()
+}
+
+/// Create a pull request for an issue, with the body built from its Tiki plan
+/// (phases + summaries, success-criteria checklist, `Closes #N`). The current
+/// branch is pushed first unless `head` names an already-pushed branch. The PR
+/// number is recorded on the issue's `activeWork` entry.
+/// - base: Base branch. Defaults to the repository default branch.
+/// - draft: Open as a draft PR. Defaults to false.
+/// - title: PR title. Defaults to the plan's issue title, then the GitHub issue title.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn create_pull_request(
+    issue_number: u32,
+    base: Option<String>,
+    draft: Option<bool>,
+    title: Option<String>,
+    head: Option<String>,
+    project_path: Option<String>,
+    tiki_path: Option<String>,
+) -> Result<GitHubPrDetail, String> {
+    let tiki = match (&tiki_path, &project_path) {
+        (None, Some(project)) => Path::new(project).join(".tiki"),
+        _ => resolve_tiki_path(tiki_path.clone())?,
+    };
+    let plan = get_plan(issue_number, Some(tiki.to_string_lossy().into_owned()))?;
+
+    let pr_title = match title.filter(|t| !t.trim().is_empty()) {
+        Some(t) => t,
+        None => match plan
+            .as_ref()
+            .and_then(|p| p.issue.as_ref().and_then(|i| i.title.clone()).or_else(|| p.title.clone()))
+        {
+            Some(t) => t,
+            None => fetch_github_issue_by_number(issue_number, project_path.clone())?.title,
+        },
+    };
+    let body = build_pr_body(issue_number, plan.as_ref());
+
+    if head.is_none() {
+        let root = project_path.as_deref().map(Path::new);
+        let remote = push_remote(&GhTarget::for_project(project_path.as_deref()), root);
+        run_git(&["push", "--set-upstream", &remote, "HEAD"], root, "push branch")?;
+    }
+
+    let output = run_gh_with_priority(GhPriority::User, || {
+        let mut cmd = gh_command(project_path.as_deref());
+        cmd.args(["pr", "create", "--title", &pr_title, "--body", &body]);
+        if let Some(ref b) = base {
+            cmd.args(["--base", b]);
+        }
+        if let Some(ref h) = head {
+            cmd.args(["--head", h]);
+        }
+        if draft.unwrap_or(false) {
+            cmd.arg("--draft");
+        }
+        cmd
+    })?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let pr_number = parse_pr_number(&stdout)
+        .ok_or_else(|| format!("Failed to parse PR number from output: {}", stdout.trim()))?;
+
+    if let Err(e) = record_pull_request(&tiki, issue_number, pr_number) {
+        log::warn!("Failed to record PR #{} on issue #{}: {}", pr_number, issue_number, e);
+    }
+
+    fetch_github_pr_detail(pr_number, project_path)
+}
+
+/// Edit an existing pull request's title, body, base branch or labels.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn update_pull_request(
+    number: u32,
+    title: Option<String>,
+    body: Option<String>,
+    base: Option<String>,
+    add_labels: Option<Vec<String>>,
+    remove_labels: Option<Vec<String>>,
+    project_path: Option<String>,
+) -> Result<(), String> {
+    let mut args = vec!["pr".to_string(), "edit".to_string(), number.to_string()];
+    if let Some(t) = title {
+        args.push("--title".to_string());
+        args.push(t);
+    }
+    if let Some(b) = body {
+        args.push("--body".to_string());
+        args.push(b);
+    }
+    if let Some(b) = base {
+        args.push("--base".to_string());
+        args.push(b);
+    }
+    for label in add_labels.unwrap_or_default() {
+        args.push("--add-label".to_string());
+        args.push(label);
+    }
+    for label in remove_labels.unwrap_or_default() {
+        args.push("--remove-label".to_string());
+        args.push(label);
+    }
+
+    run_gh_with_priority(GhPriority::User, || {
+        let mut cmd = gh_command(project_path.as_deref());
+        cmd.args(&args);
+        cmd
+    })?;
+    Ok(())
+}
+
+/// Mark a draft pull request as ready for review.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn mark_ready_for_review(number: u32, project_path: Option<String>) -> Result<(), String> {
+    run_gh_with_priority(GhPriority::User, || {
+        let mut cmd = gh_command(project_path.as_deref());
+        cmd.args(["pr", "ready", &number.to_string()]);
+        cmd
+    })?;
+    Ok(())
+}
+
+/// Merge a pull request.
+/// - method: "squash", "rebase" or "merge". Defaults to "squash"
+/// - delete_branch: Delete the head branch after merging. Defaults to false.
+/// - auto: Enable auto-merge (merges once required checks pass) instead of merging now.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn merge_pull_request(
+    number: u32,
+    method: Option<String>,
+    delete_branch: Option<bool>,
+    auto: Option<bool>,
+    project_path: Option<String>,
+) -> Result<(), String> {
+    let method_flag = match method.as_deref().unwrap_or("squash") {
+        "squash" => "--squash",
+        "rebase" => "--rebase",
+        "merge" => "--merge",
+        other => return Err(format!("Unknown merge method: {}", other)),
+    };
+
+    run_gh_with_priority(GhPriority::User, || {
+        let mut cmd = gh_command(project_path.as_deref());
+        cmd.args(["pr", "merge", &number.to_string(), method_flag]);
+        if delete_branch.unwrap_or(false) {
+            cmd.arg("--delete-branch");
+        }
+        if auto.unwrap_or(false) {
+            cmd.arg("--auto");
+        }
+        cmd
+    })?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -291,4 +557,48 @@ mod tests {
         assert_eq!(detail.reviews[0].author.as_ref().unwrap().login, "reviewer");
         assert_eq!(detail.reviews[0].state, "APPROVED");
     }
+
+    fn sample_plan() -> TikiPlan {
+        serde_json::from_value(serde_json::json!({
+            "issue": { "number": 42, "title": "Add login" },
+            "description": "Adds a login flow.",
+            "createdAt": "2026-01-01T00:00:00Z",
+            "successCriteria": [
+                { "id": "SC1", "description": "Users can log in" },
+                { "id": "SC2", "description": "Errors are shown" },
+                { "id": "SC3", "description": "Checked by hand", "verified": true }
+            ],
+            "coverageMatrix": { "SC1": [1], "SC2": [1, 2] },
+            "phases": [
+                { "number": 1, "title": "Backend", "status": "completed", "summary": "Added /login." },
+                { "number": 2, "title": "UI", "status": "pending" }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn pr_body_renders_phases_criteria_and_closing_line() {
+        let body = build_pr_body(42, Some(&sample_plan()));
+        assert!(body.starts_with("## Summary\n\nAdds a login flow."));
+        assert!(body.contains("- [x] **Phase 1: Backend** — Added /login.\n"));
+        assert!(body.contains("- [ ] **Phase 2: UI**\n"));
+        // SC1: all covering phases complete. SC2: phase 2 still pending.
+        assert!(body.contains("- [x] SC1: Users can log in\n"));
+        assert!(body.contains("- [ ] SC2: Errors are shown\n"));
+        // Explicit verified flag wins over (missing) coverage.
+        assert!(body.contains("- [x] SC3: Checked by hand\n"));
+        assert!(body.ends_with("Closes #42\n"));
+    }
+
+    #[test]
+    fn pr_body_without_plan_is_just_the_closing_line() {
+        assert_eq!(build_pr_body(7, None), "Closes #7\n");
+    }
+
+    #[test]
+    fn parses_pr_number_from_create_output() {
+        assert_eq!(parse_pr_number("https://github.com/owner/repo/pull/123\n"), Some(123));
+        assert_eq!(parse_pr_number("garbage"), None);
+    }
 }
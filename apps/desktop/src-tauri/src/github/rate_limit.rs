@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-use super::{gh_error_message, hidden_command};
+use std::process::Command;
+
+use super::{backend_for, gh_command, gh_error_message, hidden_command, record_rate_limit_status};
 
 // ─── Rate Limit Types ─────────────────────────────────────────────────────────
 
@@ -17,8 +19,8 @@ pub struct RateLimitBucket {
 
 /// Subset of `gh api rate_limit` exposed to the UI. The full GitHub response
 /// includes ~14 buckets; only the three the desktop actually exercises are
-/// surfaced (core for issue/PR/label/release fetches, search/graphql reserved
-/// for future use).
+/// surfaced (core for issue/PR/label/release fetches, graphql for bulk issue
+/// fetches, search reserved for future use).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RateLimitStatus {
@@ -46,15 +48,32 @@ struct GhRateLimitResources {
 /// buckets the desktop actually uses plus a capture timestamp.
 ///
 /// This call itself does NOT count against the rate limit (per GitHub docs),
-/// so polling it from the frontend is safe.
+/// so polling it from the frontend is safe. Each snapshot also refreshes the
+/// request scheduler's quota view.
 #[tauri::command]
 pub fn fetch_rate_limit_status(project_path: Option<String>) -> Result<RateLimitStatus, String> {
-    let mut cmd = hidden_command("gh");
+    let backend = backend_for(project_path.as_deref())?;
+    let status = backend.rate_limit()?;
+    record_rate_limit_status(&backend.quota_host(), &status);
+    Ok(status)
+}
+
+/// `GhCliBackend::rate_limit`: spawn `gh api rate_limit` directly.
+pub(crate) fn query_gh_rate_limit(project_path: Option<&str>) -> Result<RateLimitStatus, String> {
+    let mut cmd = gh_command(project_path);
     cmd.args(["api", "rate_limit"]);
-    if let Some(path) = project_path {
-        cmd.current_dir(path);
-    }
+    run_rate_limit_command(cmd)
+}
 
+/// `gh api rate_limit` against a specific host. The scheduler's snapshot
+/// refresh for `gh` traffic; bypasses the scheduler like the above.
+pub(crate) fn query_gh_host_rate_limit(host: &str) -> Result<RateLimitStatus, String> {
+    let mut cmd = hidden_command("gh");
+    cmd.args(["api", "--hostname", host, "rate_limit"]);
+    run_rate_limit_command(cmd)
+}
+
+fn run_rate_limit_command(mut cmd: Command) -> Result<RateLimitStatus, String> {
     let output = cmd.output().map_err(|e| {
         if e.kind() == std::io::ErrorKind::NotFound {
             "GitHub CLI (gh) is not installed. Please install it from https://cli.github.com/"
@@ -68,7 +87,12 @@ pub fn fetch_rate_limit_status(project_path: Option<String>) -> Result<RateLimit
         return Err(gh_error_message(&String::from_utf8_lossy(&output.stderr)));
     }
 
-    let parsed: GhRateLimitResponse = serde_json::from_slice(&output.stdout)
+    parse_rate_limit_response(&output.stdout)
+}
+
+/// Parse a REST `/rate_limit` body (shared by the gh and API backends).
+pub(crate) fn parse_rate_limit_response(body: &[u8]) -> Result<RateLimitStatus, String> {
+    let parsed: GhRateLimitResponse = serde_json::from_slice(body)
         .map_err(|e| format!("Failed to parse rate_limit response: {}", e))?;
 
     Ok(RateLimitStatus {
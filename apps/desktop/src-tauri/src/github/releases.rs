@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::{hidden_command, run_gh_with_retry};
+use super::{backend_for, cached_fetch, gh_command, run_gh_with_retry};
 
 /// A GitHub release fetched from gh CLI
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,25 +26,16 @@ pub fn fetch_github_releases(
     project_path: Option<String>,
 ) -> Result<Vec<GitHubRelease>, String> {
     let limit_val = limit.unwrap_or(20);
+    // The REST release list for the same window doubles as the ETag probe.
+    let probe = format!("repos/{{owner}}/{{repo}}/releases?per_page={}", limit_val.min(100));
 
-    let output = run_gh_with_retry(move || {
-        let mut cmd = hidden_command("gh");
-        cmd.args([
-            "release",
-            "list",
-            "--json",
-            "tagName,name,isDraft,isPrerelease,publishedAt",
-            "--limit",
-            &limit_val.to_string(),
-        ]);
-        if let Some(ref path) = project_path {
-            cmd.current_dir(path);
-        }
-        cmd
-    })?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    serde_json::from_str(&stdout).map_err(|e| format!("Failed to parse releases: {}", e))
+    cached_fetch(
+        project_path.as_deref(),
+        &format!("releases-{}", limit_val),
+        &probe,
+        None,
+        || backend_for(project_path.as_deref())?.list_releases(limit_val),
+    )
 }
 
 /// Fetch the canonical GitHub Release URL for a tag, or `None` when the release
@@ -64,11 +55,8 @@ pub fn fetch_github_release_url(
     project_path: Option<String>,
 ) -> Result<Option<String>, String> {
     let output = match run_gh_with_retry(|| {
-        let mut cmd = hidden_command("gh");
+        let mut cmd = gh_command(project_path.as_deref());
         cmd.args(["release", "view", &version, "--json", "url"]);
-        if let Some(ref path) = project_path {
-            cmd.current_dir(path);
-        }
         cmd
     }) {
         Ok(o) => o,
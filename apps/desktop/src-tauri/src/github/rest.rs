@@ -0,0 +1,1167 @@
+//! Native GitHub REST/GraphQL backend (reqwest), selected with
+//! `"github": { "backend": "api" }` in `.tiki/config.json`.
+//!
+//! Needs no `gh` binary at runtime. The repo comes from `github.host` /
+//! `github.repo` when set, otherwise from the `origin` remote;
+//! the token is resolved in gh's own order of precedence, with a PAT stored
+//! via `set_github_token` taking priority:
+//! 1. stored PAT (per host, in the credential store; see `credentials`)
+//! 2. `GH_TOKEN` / `GITHUB_TOKEN` (`GH_ENTERPRISE_TOKEN` /
+//!    `GITHUB_ENTERPRISE_TOKEN` for non-github.com hosts)
+//! 3. `oauth_token` in gh's `hosts.yml`
+//! 4. `gh auth token` (gh ≥ 2.40 keeps tokens in the OS keyring)
+//!
+//! Every request goes through the same scheduler as the CLI path and feeds
+//! the `x-ratelimit-*` response headers back into it.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode, Url};
+use serde::Deserialize;
+
+use super::git::run_git;
+use super::{
+    acquire, block_bucket, gh_error_message, hidden_command, mark_exhausted,
+    parse_rate_limit_response, record_quota, GhBucket, GhPriority, GitHubBackend, GitHubComment,
+    GitHubCommentAuthor, GitHubIssue, GitHubLabel, GhTarget, GitHubPrAuthor, GitHubPrStatusCheck,
+    GitHubPullRequest, GitHubRelease, IssueEdit, LabelEdit, LabelInfo, NewIssue, Probe, RateLimitStatus,
+    GH_NETWORK_ERROR,
+};
+use crate::credentials;
+
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Backoff for secondary rate limits without a `Retry-After` header
+/// (mirrors the CLI path).
+const API_RETRY_BACKOFF_SECS: &[u64] = &[2, 4, 8];
+
+const PAGE_SIZE: u32 = 100;
+
+// ─── Stored Tokens ────────────────────────────────────────────────────────────
+
+/// The stored PAT for `host`, if any. A locked credential store counts as
+/// none, so resolution falls through to gh's own sources.
+fn stored_token(host: &str) -> Option<String> {
+    match credentials::get_secret(&credentials::github_token_account(host)) {
+        Ok(token) => token,
+        Err(e) => {
+            log::info!("Skipping stored GitHub token for {}: {}", host, e);
+            None
+        }
+    }
+}
+
+/// Store (or with `None`, remove) the personal access token for `host`
+/// (default "github.com") used by the API backend.
+#[tauri::command]
+pub fn set_github_token(token: Option<String>, host: Option<String>) -> Result<(), String> {
+    let host = host.unwrap_or_else(|| "github.com".to_string());
+    forget_token(&host);
+    let account = credentials::github_token_account(&host);
+    match token.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()) {
+        Some(t) => credentials::set_secret(&account, &t),
+        None => credentials::delete_secret(&account),
+    }
+}
+
+// ─── Token & Repo Resolution ──────────────────────────────────────────────────
+
+/// gh's config directory, following gh's own lookup rules.
+fn gh_config_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("GH_CONFIG_DIR").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    if let Some(xdg) = std::env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(xdg).join("gh"));
+    }
+    if cfg!(target_os = "windows") {
+        if let Some(app_data) = std::env::var_os("AppData") {
+            return Some(PathBuf::from(app_data).join("GitHub CLI"));
+        }
+    }
+    dirs::home_dir().map(|h| h.join(".config").join("gh"))
+}
+
+/// Extract `oauth_token` for `host` from gh's `hosts.yml`. A minimal reader:
+/// the file is a flat map of host → settings, so indentation is enough.
+fn token_from_hosts_yml(contents: &str, host: &str) -> Option<String> {
+    let mut in_host = false;
+    for line in contents.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let indented = line.starts_with(' ') || line.starts_with('\t');
+        if !indented {
+            in_host = line.trim_end().trim_end_matches(':').trim_matches('"') == host;
+            continue;
+        }
+        if in_host {
+            if let Some(value) = line.trim().strip_prefix("oauth_token:") {
+                let token = value.trim().trim_matches('"').trim_matches('\'');
+                if !token.is_empty() {
+                    return Some(token.to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Resolve a token for `host`; returns (token, source).
+fn resolve_token(host: &str) -> Result<(String, &'static str), String> {
+    if let Some(token) = stored_token(host) {
+        return Ok((token, "stored"));
+    }
+
+    let env_vars: &[&str] = if host == "github.com" {
+        &["GH_TOKEN", "GITHUB_TOKEN"]
+    } else {
+        &["GH_ENTERPRISE_TOKEN", "GITHUB_ENTERPRISE_TOKEN"]
+    };
+    for var in env_vars {
+        if let Ok(token) = std::env::var(var) {
+            if !token.trim().is_empty() {
+                return Ok((token.trim().to_string(), "env"));
+            }
+        }
+    }
+
+    if let Some(token) = gh_config_dir()
+        .and_then(|d| std::fs::read_to_string(d.join("hosts.yml")).ok())
+        .and_then(|c| token_from_hosts_yml(&c, host))
+    {
+        return Ok((token, "ghConfig"));
+    }
+
+    let mut cmd = hidden_command("gh");
+    cmd.args(["auth", "token", "--hostname", host]);
+    if let Ok(output) = cmd.output() {
+        let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if output.status.success() && !token.is_empty() {
+            return Ok((token, "ghCli"));
+        }
+    }
+
+    Err(format!(
+        "No GitHub token for {}. Add a personal access token in settings or run 'gh auth login'.",
+        host
+    ))
+}
+
+/// Tokens already resolved this session, keyed by host, so a backend per
+/// command doesn't re-run the lookup (and `gh auth token`) every time.
+fn resolved_tokens() -> &'static Mutex<HashMap<String, (String, &'static str)>> {
+    static RESOLVED: OnceLock<Mutex<HashMap<String, (String, &'static str)>>> = OnceLock::new();
+    RESOLVED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// `resolve_token`, memoized per host until `forget_token`.
+fn cached_token(host: &str) -> Result<(String, &'static str), String> {
+    if let Some(hit) = resolved_tokens().lock().unwrap().get(host) {
+        return Ok(hit.clone());
+    }
+    let resolved = resolve_token(host)?;
+    resolved_tokens()
+        .lock()
+        .unwrap()
+        .insert(host.to_string(), resolved.clone());
+    Ok(resolved)
+}
+
+/// Drop the memoized token for `host` (it was rejected or replaced).
+fn forget_token(host: &str) {
+    resolved_tokens().lock().unwrap().remove(host);
+}
+
+/// Parse (host, owner, repo) from an https, ssh or scp-style git remote URL.
+pub(crate) fn parse_remote_url(url: &str) -> Option<(String, String, String)> {
+    let url = url.trim();
+    let rest = if let Some((_, after)) = url.split_once("://") {
+        // https://host/owner/repo, ssh://git@host[:port]/owner/repo
+        after
+    } else {
+        // git@host:owner/repo
+        let (user_host, path) = url.split_once(':')?;
+        return split_path(user_host.rsplit('@').next()?, path);
+    };
+    let (authority, path) = rest.split_once('/')?;
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+    split_path(host, path)
+}
+
+fn split_path(host: &str, path: &str) -> Option<(String, String, String)> {
+    let mut parts = path.trim_matches('/').trim_end_matches(".git").rsplitn(2, '/');
+    let repo = parts.next()?.to_string();
+    let owner = parts.next()?.rsplit('/').next()?.to_string();
+    if host.is_empty() || owner.is_empty() || repo.is_empty() {
+        return None;
+    }
+    Some((host.to_string(), owner, repo))
+}
+
+/// REST and GraphQL endpoints for a host (github.com or GitHub Enterprise Server).
+fn api_urls(host: &str) -> (String, String) {
+    if host == "github.com" {
+        (
+            "https://api.github.com".to_string(),
+            "https://api.github.com/graphql".to_string(),
+        )
+    } else {
+        (
+            format!("https://{}/api/v3", host),
+            format!("https://{}/api/graphql", host),
+        )
+    }
+}
+
+// ─── REST Payloads ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct RestLabel {
+    node_id: String,
+    name: String,
+    color: String,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RestUser {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RestIssue {
+    number: u32,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    state: String,
+    #[serde(default)]
+    labels: Vec<RestLabel>,
+    html_url: String,
+    created_at: String,
+    updated_at: String,
+    /// Present when the "issue" is actually a pull request.
+    #[serde(default)]
+    pull_request: Option<serde_json::Value>,
+}
+
+impl From<RestIssue> for GitHubIssue {
+    fn from(issue: RestIssue) -> Self {
+        GitHubIssue {
+            number: issue.number,
+            title: issue.title,
+            body: issue.body,
+            // REST reports "open"/"closed"; gh (and the UI) use "OPEN"/"CLOSED".
+            state: issue.state.to_uppercase(),
+            labels: issue
+                .labels
+                .into_iter()
+                .map(|l| GitHubLabel {
+                    id: l.node_id,
+                    name: l.name,
+                    color: l.color,
+                    description: l.description,
+                })
+                .collect(),
+            url: issue.html_url,
+            created_at: issue.created_at,
+            updated_at: issue.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RestComment {
+    node_id: String,
+    #[serde(default)]
+    user: Option<RestUser>,
+    #[serde(default)]
+    body: Option<String>,
+    created_at: String,
+    html_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RestRelease {
+    tag_name: String,
+    #[serde(default)]
+    name: Option<String>,
+    draft: bool,
+    prerelease: bool,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    html_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiErrorBody {
+    #[serde(default)]
+    message: String,
+}
+
+// ─── GraphQL PR List ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct GqlNodes<T> {
+    #[serde(default = "Vec::new")]
+    nodes: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlPageInfo {
+    has_next_page: bool,
+    end_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlPullRequests {
+    page_info: GqlPageInfo,
+    nodes: Vec<GqlPullRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlPullRequest {
+    number: u32,
+    title: String,
+    state: String,
+    head_ref_name: String,
+    base_ref_name: String,
+    url: String,
+    is_draft: bool,
+    #[serde(default)]
+    review_decision: Option<String>,
+    #[serde(default)]
+    author: Option<GitHubPrAuthor>,
+    #[serde(default)]
+    body: Option<String>,
+    labels: GqlNodes<GitHubLabel>,
+    commits: GqlNodes<GqlCommitNode>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlCommitNode {
+    commit: GqlCommit,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlCommit {
+    #[serde(default)]
+    status_check_rollup: Option<GqlRollup>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GqlRollup {
+    contexts: GqlNodes<GqlCheckContext>,
+}
+
+/// A `CheckRun` or `StatusContext` from the rollup (fields of the other
+/// variant are simply absent).
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlCheckContext {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    conclusion: Option<String>,
+    #[serde(default)]
+    details_url: Option<String>,
+    #[serde(default)]
+    context: Option<String>,
+    #[serde(default)]
+    state: Option<String>,
+    #[serde(default)]
+    target_url: Option<String>,
+}
+
+impl From<GqlPullRequest> for GitHubPullRequest {
+    fn from(pr: GqlPullRequest) -> Self {
+        let status_check_rollup = pr
+            .commits
+            .nodes
+            .into_iter()
+            .next()
+            .and_then(|c| c.commit.status_check_rollup)
+            .map(|r| {
+                r.contexts
+                    .nodes
+                    .into_iter()
+                    .map(|c| GitHubPrStatusCheck {
+                        context: c.context,
+                        name: c.name,
+                        state: c.state,
+                        status: c.status,
+                        conclusion: c.conclusion,
+                        details_url: c.details_url.or(c.target_url),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        GitHubPullRequest {
+            number: pr.number,
+            title: pr.title,
+            state: pr.state,
+            head_ref_name: pr.head_ref_name,
+            base_ref_name: pr.base_ref_name,
+            url: pr.url,
+            is_draft: pr.is_draft,
+            review_decision: pr.review_decision,
+            author: pr.author,
+            labels: pr.labels.nodes,
+            body: pr.body,
+            status_check_rollup,
+        }
+    }
+}
+
+fn pr_states(state: &str) -> Result<&'static str, String> {
+    match state {
+        "open" => Ok("[OPEN]"),
+        "closed" => Ok("[CLOSED]"),
+        "merged" => Ok("[MERGED]"),
+        "all" => Ok("[OPEN, CLOSED, MERGED]"),
+        other => Err(format!("Unknown PR state filter: {}", other)),
+    }
+}
+
+fn pull_requests_query(states: &str) -> String {
+    format!(
+        "query($owner: String!, $name: String!, $first: Int!, $cursor: String) {{
+  repository(owner: $owner, name: $name) {{
+    pullRequests(first: $first, after: $cursor, states: {states}, orderBy: {{field: CREATED_AT, direction: DESC}}) {{
+      pageInfo {{ hasNextPage endCursor }}
+      nodes {{
+        number title state headRefName baseRefName url isDraft reviewDecision body
+        author {{ login }}
+        labels(first: 50) {{ nodes {{ id name color description }} }}
+        commits(last: 1) {{ nodes {{ commit {{ statusCheckRollup {{ contexts(first: 100) {{ nodes {{
+          ... on CheckRun {{ name status conclusion detailsUrl }}
+          ... on StatusContext {{ context state targetUrl }}
+        }} }} }} }} }} }}
+      }}
+    }}
+  }}
+}}"
+    )
+}
+
+// ─── Backend ──────────────────────────────────────────────────────────────────
+
+pub(crate) struct RestBackend {
+    client: Client,
+    /// GitHub host the quotas are tracked under, e.g. "github.com".
+    host: String,
+    api_base: String,
+    graphql_url: String,
+    owner: String,
+    repo: String,
+    token: String,
+    token_source: &'static str,
+}
+
+impl RestBackend {
+    /// Build a backend for the project's `github.host`/`github.repo`, falling
+    /// back to the repo behind the `origin` remote.
+    pub(crate) fn for_project(root: &Path) -> Result<Self, String> {
+        let target = GhTarget::load(root);
+        let (host, owner, repo) = match target.repo {
+            Some(ref full) => {
+                let (owner, repo) = full.split_once('/').unwrap_or((full, ""));
+                let host = target.host.clone().unwrap_or_else(|| "github.com".to_string());
+                (host, owner.to_string(), repo.to_string())
+            }
+            None => {
+                let remote =
+                    run_git(&["remote", "get-url", "origin"], Some(root), "read the origin remote")?;
+                let (host, owner, repo) = parse_remote_url(&remote).ok_or_else(|| {
+                    format!("Could not parse a GitHub repository from remote '{}'", remote.trim())
+                })?;
+                (target.host.clone().unwrap_or(host), owner, repo)
+            }
+        };
+        let (token, token_source) = cached_token(&host)?;
+        let (api_base, graphql_url) = api_urls(&host);
+        Self::new(host, api_base, graphql_url, owner, repo, token, token_source)
+    }
+
+    fn new(
+        host: String,
+        api_base: String,
+        graphql_url: String,
+        owner: String,
+        repo: String,
+        token: String,
+        token_source: &'static str,
+    ) -> Result<Self, String> {
+        let client = Client::builder()
+            .user_agent(concat!("tiki-desktop/", env!("CARGO_PKG_VERSION")))
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+        Ok(RestBackend {
+            client,
+            host,
+            api_base: api_base.trim_end_matches('/').to_string(),
+            graphql_url,
+            owner,
+            repo,
+            token,
+            token_source,
+        })
+    }
+
+    pub(crate) fn token_source(&self) -> &'static str {
+        self.token_source
+    }
+
+    /// `{api_base}/repos/{owner}/{repo}/<segments...>`, percent-encoding each segment.
+    fn repo_url(&self, segments: &[&str]) -> Result<Url, String> {
+        let mut url = Url::parse(&self.api_base).map_err(|e| e.to_string())?;
+        url.path_segments_mut()
+            .map_err(|_| format!("Invalid API base URL: {}", self.api_base))?
+            .pop_if_empty()
+            .extend(["repos", &self.owner, &self.repo])
+            .extend(segments);
+        Ok(url)
+    }
+
+    fn request(&self, method: Method, url: Url) -> RequestBuilder {
+        self.client
+            .request(method, url)
+            .bearer_auth(&self.token)
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28")
+    }
+
+    /// Feed `x-ratelimit-*` headers into the scheduler.
+    fn record_headers(&self, headers: &HeaderMap) {
+        let get = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        let bucket = match get("x-ratelimit-resource") {
+            Some("graphql") => GhBucket::Graphql,
+            Some("search") => GhBucket::Search,
+            Some("core") => GhBucket::Core,
+            _ => return,
+        };
+        if let (Some(limit), Some(remaining), Some(reset)) = (
+            get("x-ratelimit-limit").and_then(|v| v.parse().ok()),
+            get("x-ratelimit-remaining").and_then(|v| v.parse().ok()),
+            get("x-ratelimit-reset").and_then(|v| v.parse().ok()),
+        ) {
+            record_quota(&self.host, bucket, limit, remaining, reset);
+        }
+    }
+
+    /// Send through the scheduler with the CLI path's retry semantics.
+    /// 2xx and 304 responses are returned; everything else becomes an error.
+    fn send(
+        &self,
+        bucket: GhBucket,
+        priority: GhPriority,
+        build: impl Fn() -> RequestBuilder,
+    ) -> Result<Response, String> {
+        let attempts = API_RETRY_BACKOFF_SECS.len() + 1;
+        let mut last_err = String::new();
+
+        for attempt in 0..attempts {
+            // Responses carry the quota headers, so no snapshot refresh.
+            let permit = acquire(&self.host, bucket, priority, None);
+            let response = build().send().map_err(|e| {
+                if e.is_connect() || e.is_timeout() || e.is_request() {
+                    format!("{} ({})", GH_NETWORK_ERROR, e)
+                } else {
+                    format!("GitHub API request failed: {}", e)
+                }
+            })?;
+            drop(permit);
+
+            self.record_headers(response.headers());
+            let status = response.status();
+            if status.is_success() || status == StatusCode::NOT_MODIFIED {
+                return Ok(response);
+            }
+
+            let retry_after = response
+                .headers()
+                .get("retry-after")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok());
+            let exhausted = response
+                .headers()
+                .get("x-ratelimit-remaining")
+                .is_some_and(|v| v.as_bytes() == b"0");
+            let message = response
+                .json::<ApiErrorBody>()
+                .map(|b| b.message)
+                .unwrap_or_default();
+
+            match status {
+                StatusCode::UNAUTHORIZED => {
+                    forget_token(&self.host);
+                    return Err(format!(
+                        "GitHub rejected the {} token ({}). Update the personal access token or run 'gh auth login'.",
+                        self.token_source, message
+                    ));
+                }
+                StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS => {
+                    let secondary = retry_after.is_some()
+                        || message.to_lowercase().contains("secondary rate limit");
+                    if !secondary {
+                        if exhausted {
+                            mark_exhausted(&self.host, bucket);
+                            return Err(gh_error_message("API rate limit exceeded"));
+                        }
+                        return Err(format!("GitHub API error {}: {}", status.as_u16(), message));
+                    }
+                    if let Some(&backoff) = API_RETRY_BACKOFF_SECS.get(attempt) {
+                        let delay = retry_after.unwrap_or(backoff);
+                        log::warn!(
+                            "GitHub API secondary rate limit (attempt {}/{}); pausing {:?} requests for {}s",
+                            attempt + 1,
+                            attempts,
+                            bucket,
+                            delay
+                        );
+                        block_bucket(&self.host, bucket, delay);
+                    }
+                    last_err = gh_error_message("secondary rate limit");
+                }
+                _ => return Err(format!("GitHub API error {}: {}", status.as_u16(), message)),
+            }
+        }
+
+        Err(last_err)
+    }
+
+    fn get_json<T: serde::de::DeserializeOwned>(&self, url: Url) -> Result<T, String> {
+        self.send(GhBucket::Core, GhPriority::Normal, || {
+            self.request(Method::GET, url.clone())
+        })?
+        .json()
+        .map_err(|e| format!("Failed to parse GitHub API response: {}", e))
+    }
+
+    /// GET a paginated list endpoint until `limit` items pass `keep`.
+    fn get_paginated<T, F>(&self, segments: &[&str], query: &[(&str, &str)], limit: usize, keep: F) -> Result<Vec<T>, String>
+    where
+        T: serde::de::DeserializeOwned,
+        F: Fn(&T) -> bool,
+    {
+        let mut out = Vec::new();
+        let mut page = 1u32;
+        while out.len() < limit {
+            let mut url = self.repo_url(segments)?;
+            url.query_pairs_mut()
+                .extend_pairs(query)
+                .append_pair("per_page", &PAGE_SIZE.to_string())
+                .append_pair("page", &page.to_string());
+            let items: Vec<T> = self.get_json(url)?;
+            let full_page = items.len() as u32 == PAGE_SIZE;
+            out.extend(items.into_iter().filter(|i| keep(i)));
+            if !full_page {
+                break;
+            }
+            page += 1;
+        }
+        out.truncate(limit);
+        Ok(out)
+    }
+
+    /// REST takes a milestone number where gh takes its title.
+    fn milestone_number(&self, title: &str) -> Result<u32, String> {
+        #[derive(Deserialize)]
+        struct Milestone {
+            number: u32,
+            title: String,
+        }
+        let milestones: Vec<Milestone> =
+            self.get_paginated(&["milestones"], &[("state", "open")], usize::MAX, |_| true)?;
+        milestones
+            .into_iter()
+            .find(|m| m.title == title)
+            .map(|m| m.number)
+            .ok_or_else(|| format!("No open milestone titled '{}'", title))
+    }
+
+    fn mutate(
+        &self,
+        priority: GhPriority,
+        method: Method,
+        url: Url,
+        body: serde_json::Value,
+    ) -> Result<Response, String> {
+        self.send(GhBucket::Core, priority, || {
+            self.request(method.clone(), url.clone()).json(&body)
+        })
+    }
+}
+
+impl GitHubBackend for RestBackend {
+    fn list_issues(&self, state: &str, limit: u32) -> Result<Vec<GitHubIssue>, String> {
+        let issues: Vec<RestIssue> = self.get_paginated(
+            &["issues"],
+            &[("state", state), ("sort", "created"), ("direction", "desc")],
+            limit as usize,
+            |i: &RestIssue| i.pull_request.is_none(),
+        )?;
+        Ok(issues.into_iter().map(GitHubIssue::from).collect())
+    }
+
+    fn get_issue(&self, number: u32) -> Result<GitHubIssue, String> {
+        let issue: RestIssue = self.get_json(self.repo_url(&["issues", &number.to_string()])?)?;
+        Ok(issue.into())
+    }
+
+    fn create_issue(&self, issue: &NewIssue, priority: GhPriority) -> Result<GitHubIssue, String> {
+        let mut payload = serde_json::json!({
+            "title": issue.title,
+            "labels": issue.labels,
+            "assignees": issue.assignees,
+        });
+        if let Some(ref b) = issue.body {
+            payload["body"] = b.clone().into();
+        }
+        if let Some(ref title) = issue.milestone {
+            payload["milestone"] = self.milestone_number(title)?.into();
+        }
+        let issue: RestIssue = self
+            .mutate(priority, Method::POST, self.repo_url(&["issues"])?, payload)?
+            .json()
+            .map_err(|e| format!("Failed to parse issue: {}", e))?;
+        Ok(issue.into())
+    }
+
+    fn edit_issue(&self, number: u32, edit: &IssueEdit, priority: GhPriority) -> Result<(), String> {
+        let n = number.to_string();
+        let mut payload = serde_json::Map::new();
+        if let Some(ref t) = edit.title {
+            payload.insert("title".to_string(), t.clone().into());
+        }
+        if let Some(ref b) = edit.body {
+            payload.insert("body".to_string(), b.clone().into());
+        }
+        if !payload.is_empty() {
+            self.mutate(priority, Method::PATCH, self.repo_url(&["issues", &n])?, payload.into())?;
+        }
+        if !edit.add_labels.is_empty() {
+            self.mutate(
+                priority,
+                Method::POST,
+                self.repo_url(&["issues", &n, "labels"])?,
+                serde_json::json!({ "labels": edit.add_labels }),
+            )?;
+        }
+        for label in &edit.remove_labels {
+            let url = self.repo_url(&["issues", &n, "labels", label])?;
+            self.send(GhBucket::Core, priority, || {
+                self.request(Method::DELETE, url.clone())
+            })?;
+        }
+        Ok(())
+    }
+
+    fn close_issue(&self, number: u32, priority: GhPriority) -> Result<(), String> {
+        self.mutate(
+            priority,
+            Method::PATCH,
+            self.repo_url(&["issues", &number.to_string()])?,
+            serde_json::json!({ "state": "closed" }),
+        )
+        .map(|_| ())
+    }
+
+    fn list_issue_comments(&self, number: u32) -> Result<Vec<GitHubComment>, String> {
+        let comments: Vec<RestComment> = self.get_paginated(
+            &["issues", &number.to_string(), "comments"],
+            &[],
+            usize::MAX,
+            |_| true,
+        )?;
+        Ok(comments
+            .into_iter()
+            .map(|c| GitHubComment {
+                id: c.node_id,
+                author: GitHubCommentAuthor {
+                    login: c.user.map(|u| u.login).unwrap_or_else(|| "ghost".to_string()),
+                },
+                body: c.body.unwrap_or_default(),
+                created_at: c.created_at,
+                url: c.html_url,
+            })
+            .collect())
+    }
+
+    fn post_issue_comment(&self, number: u32, body: &str, priority: GhPriority) -> Result<(), String> {
+        self.mutate(
+            priority,
+            Method::POST,
+            self.repo_url(&["issues", &number.to_string(), "comments"])?,
+            serde_json::json!({ "body": body }),
+        )
+        .map(|_| ())
+    }
+
+    fn list_labels(&self) -> Result<Vec<LabelInfo>, String> {
+        self.get_paginated(&["labels"], &[], usize::MAX, |_| true)
+    }
+
+    fn create_label(&self, label: &LabelInfo, priority: GhPriority) -> Result<(), String> {
+        let mut payload = serde_json::json!({ "name": label.name, "color": label.color });
+        if let Some(ref d) = label.description {
+            payload["description"] = d.clone().into();
+        }
+        self.mutate(priority, Method::POST, self.repo_url(&["labels"])?, payload)
+            .map(|_| ())
+    }
+
+    fn update_label(&self, name: &str, edit: &LabelEdit, priority: GhPriority) -> Result<(), String> {
+        let mut payload = serde_json::Map::new();
+        if let Some(ref n) = edit.new_name {
+            payload.insert("new_name".to_string(), n.clone().into());
+        }
+        if let Some(ref c) = edit.color {
+            payload.insert("color".to_string(), c.clone().into());
+        }
+        if let Some(ref d) = edit.description {
+            payload.insert("description".to_string(), d.clone().into());
+        }
+        self.mutate(priority, Method::PATCH, self.repo_url(&["labels", name])?, payload.into())
+            .map(|_| ())
+    }
+
+    fn delete_label(&self, name: &str, priority: GhPriority) -> Result<(), String> {
+        let url = self.repo_url(&["labels", name])?;
+        self.send(GhBucket::Core, priority, || {
+            self.request(Method::DELETE, url.clone())
+        })
+        .map(|_| ())
+    }
+
+    fn list_releases(&self, limit: u32) -> Result<Vec<GitHubRelease>, String> {
+        let releases: Vec<RestRelease> =
+            self.get_paginated(&["releases"], &[], limit as usize, |_| true)?;
+        Ok(releases
+            .into_iter()
+            .map(|r| GitHubRelease {
+                tag_name: r.tag_name,
+                name: r.name,
+                is_draft: r.draft,
+                is_prerelease: r.prerelease,
+                published_at: r.published_at,
+                url: r.html_url,
+            })
+            .collect())
+    }
+
+    fn list_pull_requests(
+        &self,
+        state: &str,
+        limit: u32,
+    ) -> Result<Vec<GitHubPullRequest>, String> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Repo {
+            pull_requests: GqlPullRequests,
+        }
+        #[derive(Deserialize)]
+        struct Data {
+            repository: Option<Repo>,
+        }
+        #[derive(Deserialize)]
+        struct Body {
+            data: Option<Data>,
+            #[serde(default)]
+            errors: Vec<ApiErrorBody>,
+        }
+
+        let query = pull_requests_query(pr_states(state)?);
+        let mut out = Vec::new();
+        let mut cursor: Option<String> = None;
+        while out.len() < limit as usize {
+            let first = (limit as usize - out.len()).min(PAGE_SIZE as usize);
+            let mut variables = vec![("first", first.into())];
+            if let Some(ref c) = cursor {
+                variables.push(("cursor", c.clone().into()));
+            }
+            let body: Body = serde_json::from_slice(&self.graphql(&query, &variables, GhPriority::Normal)?)
+                .map_err(|e| format!("Failed to parse PR list: {}", e))?;
+            if !body.errors.is_empty() {
+                let messages: Vec<&str> = body.errors.iter().map(|e| e.message.as_str()).collect();
+                return Err(format!("GitHub GraphQL error: {}", messages.join("; ")));
+            }
+            let prs = body
+                .data
+                .and_then(|d| d.repository)
+                .ok_or_else(|| "Repository not found or not accessible.".to_string())?
+                .pull_requests;
+            out.extend(prs.nodes.into_iter().map(GitHubPullRequest::from));
+            match prs.page_info {
+                GqlPageInfo { has_next_page: true, end_cursor: Some(next) } => cursor = Some(next),
+                _ => break,
+            }
+        }
+        Ok(out)
+    }
+
+    fn graphql(
+        &self,
+        query: &str,
+        variables: &[(&str, serde_json::Value)],
+        priority: GhPriority,
+    ) -> Result<Vec<u8>, String> {
+        let mut vars = serde_json::Map::new();
+        vars.insert("owner".to_string(), self.owner.clone().into());
+        vars.insert("name".to_string(), self.repo.clone().into());
+        for (key, value) in variables {
+            vars.insert(key.to_string(), value.clone());
+        }
+        let payload = serde_json::json!({ "query": query, "variables": vars });
+
+        let response = self.send(GhBucket::Graphql, priority, || {
+            self.client
+                .post(&self.graphql_url)
+                .bearer_auth(&self.token)
+                .json(&payload)
+        })?;
+        response
+            .bytes()
+            .map(|b| b.to_vec())
+            .map_err(|e| format!("Failed to read GraphQL response: {}", e))
+    }
+
+    fn probe(&self, endpoint: &str, etag: Option<&str>) -> Result<Probe, String> {
+        let path = endpoint
+            .replace("{owner}", &self.owner)
+            .replace("{repo}", &self.repo);
+        let url = Url::parse(&format!("{}/{}", self.api_base, path.trim_start_matches('/')))
+            .map_err(|e| format!("Invalid probe endpoint '{}': {}", endpoint, e))?;
+
+        let response = self.send(GhBucket::Core, GhPriority::Normal, || {
+            let req = self.request(Method::GET, url.clone());
+            match etag {
+                Some(tag) => req.header("If-None-Match", tag),
+                None => req,
+            }
+        })?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(Probe::NotModified);
+        }
+        Ok(Probe::Modified {
+            etag: response
+                .headers()
+                .get("etag")
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string),
+        })
+    }
+
+    fn rate_limit(&self) -> Result<RateLimitStatus, String> {
+        // Direct request: the rate-limit endpoint is free, so it bypasses
+        // the scheduler.
+        let response = self
+            .request(Method::GET, Url::parse(&format!("{}/rate_limit", self.api_base)).map_err(|e| e.to_string())?)
+            .send()
+            .map_err(|e| format!("{} ({})", GH_NETWORK_ERROR, e))?;
+        if !response.status().is_success() {
+            return Err(format!("GitHub API error {} fetching rate limit", response.status().as_u16()));
+        }
+        let body = response
+            .bytes()
+            .map_err(|e| format!("Failed to read rate_limit response: {}", e))?;
+        parse_rate_limit_response(&body)
+    }
+
+    fn quota_host(&self) -> String {
+        self.host.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    /// Serve canned responses, one per connection, and report each request's
+    /// head (request line + headers) back to the test.
+    fn mock_server(responses: Vec<(u16, &'static str, String)>) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for (status, extra_headers, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut head = String::new();
+                let mut content_length = 0usize;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(v) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = v.trim().parse().unwrap_or(0);
+                    }
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                    head.push_str(&line);
+                }
+                let mut req_body = vec![0u8; content_length];
+                reader.read_exact(&mut req_body).unwrap();
+                head.push_str(&String::from_utf8_lossy(&req_body));
+                tx.send(head).unwrap();
+
+                let mut stream = reader.into_inner();
+                write!(
+                    stream,
+                    "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+                    status,
+                    body.len(),
+                    extra_headers,
+                    body
+                )
+                .unwrap();
+            }
+        });
+        (base, rx)
+    }
+
+    fn backend(base: &str) -> RestBackend {
+        let mut b = RestBackend::new(
+            base.to_string(),
+            base.to_string(),
+            format!("{}/graphql", base),
+            "octo".to_string(),
+            "repo".to_string(),
+            "t0ken".to_string(),
+            "stored",
+        )
+        .unwrap();
+        b.client = Client::builder().no_proxy().build().unwrap();
+        b
+    }
+
+    #[test]
+    fn lists_issues_without_pull_requests() {
+        let body = serde_json::json!([
+            {
+                "number": 7, "title": "Bug", "body": null, "state": "open",
+                "labels": [{ "node_id": "LA_1", "name": "bug", "color": "d73a4a", "description": null }],
+                "html_url": "https://github.com/octo/repo/issues/7",
+                "created_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-02T00:00:00Z"
+            },
+            {
+                "number": 8, "title": "A PR", "state": "open", "labels": [],
+                "html_url": "https://github.com/octo/repo/pull/8",
+                "created_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-02T00:00:00Z",
+                "pull_request": { "url": "x" }
+            }
+        ]);
+        let (base, requests) = mock_server(vec![(200, "", body.to_string())]);
+
+        let issues = backend(&base).list_issues("open", 30).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].number, 7);
+        assert_eq!(issues[0].state, "OPEN");
+        assert_eq!(issues[0].labels[0].id, "LA_1");
+
+        let head = requests.recv().unwrap();
+        assert!(head.starts_with("GET /repos/octo/repo/issues?state=open&sort=created&direction=desc&per_page=100&page=1 "));
+        assert!(head.to_lowercase().contains("authorization: bearer t0ken"));
+    }
+
+    #[test]
+    fn probe_sends_if_none_match_and_reads_etag() {
+        let (base, requests) = mock_server(vec![
+            (200, "ETag: W/\"v1\"\r\n", "[]".to_string()),
+            (304, "", String::new()),
+        ]);
+        let b = backend(&base);
+
+        let first = b.probe("repos/{owner}/{repo}/labels?per_page=100", None).unwrap();
+        assert_eq!(first, Probe::Modified { etag: Some("W/\"v1\"".to_string()) });
+        assert!(requests.recv().unwrap().starts_with("GET /repos/octo/repo/labels?per_page=100 "));
+
+        let second = b.probe("repos/{owner}/{repo}/labels?per_page=100", Some("W/\"v1\"")).unwrap();
+        assert_eq!(second, Probe::NotModified);
+        assert!(requests.recv().unwrap().to_lowercase().contains("if-none-match: w/\"v1\""));
+    }
+
+    #[test]
+    fn graphql_binds_repo_and_typed_variables() {
+        let (base, requests) = mock_server(vec![(200, "", r#"{"data":{}}"#.to_string())]);
+        let out = backend(&base)
+            .graphql(
+                "query { x }",
+                &[("first", 10.into()), ("cursor", "123".into())],
+                GhPriority::Normal,
+            )
+            .unwrap();
+        assert_eq!(out, br#"{"data":{}}"#.to_vec());
+
+        let head = requests.recv().unwrap();
+        assert!(head.starts_with("POST /graphql "));
+        let body: serde_json::Value =
+            serde_json::from_str(&head[head.find('{').unwrap()..]).unwrap();
+        assert_eq!(body["variables"]["owner"], "octo");
+        assert_eq!(body["variables"]["name"], "repo");
+        assert_eq!(body["variables"]["first"], 10);
+        // A numeric-looking cursor stays a string.
+        assert_eq!(body["variables"]["cursor"], "123");
+    }
+
+    #[test]
+    fn maps_auth_and_network_errors() {
+        let (base, _requests) =
+            mock_server(vec![(401, "", r#"{"message":"Bad credentials"}"#.to_string())]);
+        resolved_tokens()
+            .lock()
+            .unwrap()
+            .insert(base.clone(), ("t0ken".to_string(), "stored"));
+        let err = backend(&base).get_issue(1).unwrap_err();
+        assert!(err.contains("Bad credentials"), "{}", err);
+        assert!(err.contains("personal access token"), "{}", err);
+        // A rejected token is resolved afresh next time.
+        assert!(!resolved_tokens().lock().unwrap().contains_key(&base));
+
+        // Nothing listens on the port once the listener is dropped.
+        let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let err = backend(&format!("http://127.0.0.1:{}", port)).get_issue(1).unwrap_err();
+        assert!(err.starts_with(GH_NETWORK_ERROR), "{}", err);
+    }
+
+    #[test]
+    fn parses_remote_urls() {
+        let expected = Some(("github.com".to_string(), "octo".to_string(), "repo".to_string()));
+        assert_eq!(parse_remote_url("https://github.com/octo/repo.git"), expected);
+        assert_eq!(parse_remote_url("https://github.com/octo/repo"), expected);
+        assert_eq!(parse_remote_url("git@github.com:octo/repo.git"), expected);
+        assert_eq!(parse_remote_url("ssh://git@github.com:22/octo/repo.git\n"), expected);
+        assert_eq!(
+            parse_remote_url("https://ghe.example.com/team/octo/repo.git"),
+            Some(("ghe.example.com".to_string(), "octo".to_string(), "repo".to_string()))
+        );
+        assert_eq!(parse_remote_url("/local/path/repo"), None);
+    }
+
+    #[test]
+    fn reads_token_from_gh_hosts_yml() {
+        let hosts = "github.com:\n    user: octo\n    oauth_token: gho_abc\n    git_protocol: https\nghe.example.com:\n    oauth_token: \"ghe_xyz\"\n";
+        assert_eq!(token_from_hosts_yml(hosts, "github.com").as_deref(), Some("gho_abc"));
+        assert_eq!(token_from_hosts_yml(hosts, "ghe.example.com").as_deref(), Some("ghe_xyz"));
+        assert_eq!(token_from_hosts_yml(hosts, "other.host"), None);
+    }
+}
@@ -0,0 +1,449 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use super::{
+    gh_command, run_gh_with_priority, run_gh_with_retry, GhPriority, GitHubPrStatusCheck,
+};
+
+// ─── Review Comments & Reviews ────────────────────────────────────────────────
+
+/// A line-level review comment as returned by the REST pulls/comments API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHubReviewComment {
+    pub id: u64,
+    pub path: String,
+    #[serde(default)]
+    pub line: Option<u32>,
+    pub body: String,
+    /// The comment's web URL. REST payloads also carry an API `url`, which
+    /// is ignored.
+    #[serde(default, rename(deserialize = "html_url"))]
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhHeadOid {
+    #[serde(rename = "headRefOid")]
+    head_ref_oid: String,
+}
+
+/// Post a line-level review comment on a file in a pull request.
+/// - path: File path as listed in the PR's `files` (see `GitHubPrFile::path`)
+/// - line: Line number in the file (new side, unless `side` is "LEFT")
+/// - start_line: Optional first line for a multi-line comment ending at `line`
+/// - side: "RIGHT" (additions/context, default) or "LEFT" (deletions)
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn post_pr_review_comment(
+    number: u32,
+    path: String,
+    line: u32,
+    body: String,
+    start_line: Option<u32>,
+    side: Option<String>,
+    project_path: Option<String>,
+) -> Result<GitHubReviewComment, String> {
+    let side = side.unwrap_or_else(|| "RIGHT".to_string());
+    if side != "RIGHT" && side != "LEFT" {
+        return Err(format!("Invalid comment side: {}", side));
+    }
+
+    // The REST API anchors comments to a commit; use the PR's current head.
+    let head = run_gh_with_retry(|| {
+        let mut cmd = gh_command(project_path.as_deref());
+        cmd.args(["pr", "view", &number.to_string(), "--json", "headRefOid"]);
+        cmd
+    })?;
+    let head: GhHeadOid = serde_json::from_slice(&head.stdout)
+        .map_err(|e| format!("Failed to parse PR head commit: {}", e))?;
+
+    let output = run_gh_with_priority(GhPriority::User, || {
+        let mut cmd = gh_command(project_path.as_deref());
+        cmd.args([
+            "api",
+            "--method",
+            "POST",
+            &format!("repos/{{owner}}/{{repo}}/pulls/{}/comments", number),
+            "-f",
+            &format!("body={}", body),
+            "-f",
+            &format!("path={}", path),
+            "-f",
+            &format!("commit_id={}", head.head_ref_oid),
+            "-F",
+            &format!("line={}", line),
+            "-f",
+            &format!("side={}", side),
+        ]);
+        if let Some(start) = start_line {
+            cmd.args(["-F", &format!("start_line={}", start), "-f", &format!("start_side={}", side)]);
+        }
+        cmd
+    })?;
+
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse review comment: {}", e))
+}
+
+/// Submit a review on a pull request.
+/// - event: One of "approve", "request-changes", "comment"
+/// - body: Review summary. Required for "request-changes" and "comment".
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn submit_pr_review(
+    number: u32,
+    event: String,
+    body: Option<String>,
+    project_path: Option<String>,
+) -> Result<(), String> {
+    let flag = match event.as_str() {
+        "approve" => "--approve",
+        "request-changes" => "--request-changes",
+        "comment" => "--comment",
+        _ => return Err(format!("Unknown review event: {}", event)),
+    };
+    let body = body.filter(|b| !b.trim().is_empty());
+    if body.is_none() && flag != "--approve" {
+        return Err(format!("A review body is required for '{}'", event));
+    }
+
+    run_gh_with_priority(GhPriority::User, || {
+        let mut cmd = gh_command(project_path.as_deref());
+        cmd.args(["pr", "review", &number.to_string(), flag]);
+        if let Some(ref b) = body {
+            cmd.args(["--body", b]);
+        }
+        cmd
+    })?;
+    Ok(())
+}
+
+// ─── Checks ───────────────────────────────────────────────────────────────────
+
+/// Outcome of one `statusCheckRollup` entry, normalized across the two shapes
+/// GitHub returns: CheckRuns (`status` + `conclusion`) and legacy commit
+/// StatusContexts (`state`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CheckOutcome {
+    Pending,
+    Passed,
+    Failed,
+}
+
+fn check_outcome(check: &GitHubPrStatusCheck) -> CheckOutcome {
+    if let Some(state) = check.state.as_deref() {
+        return match state {
+            "SUCCESS" => CheckOutcome::Passed,
+            "FAILURE" | "ERROR" => CheckOutcome::Failed,
+            _ => CheckOutcome::Pending,
+        };
+    }
+    if check.status.as_deref() != Some("COMPLETED") {
+        return CheckOutcome::Pending;
+    }
+    match check.conclusion.as_deref() {
+        Some("SUCCESS") | Some("NEUTRAL") | Some("SKIPPED") => CheckOutcome::Passed,
+        _ => CheckOutcome::Failed,
+    }
+}
+
+/// Aggregate check state for a PR. Emitted as `pr-checks-progress` while
+/// `wait_for_checks` polls, and returned when it finishes.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrChecksSummary {
+    pub number: u32,
+    pub total: usize,
+    pub pending: usize,
+    pub passed: usize,
+    pub failed: usize,
+    /// True once no check is pending. An empty rollup only counts once the
+    /// grace period for checks to appear has passed.
+    pub settled: bool,
+    /// True when `wait_for_checks` gave up before the rollup settled.
+    pub timed_out: bool,
+    pub checks: Vec<GitHubPrStatusCheck>,
+}
+
+/// `allow_empty`: whether a PR with no checks at all counts as settled.
+fn summarize_checks(number: u32, checks: Vec<GitHubPrStatusCheck>, allow_empty: bool) -> PrChecksSummary {
+    let outcomes: Vec<CheckOutcome> = checks.iter().map(check_outcome).collect();
+    let count = |o: CheckOutcome| outcomes.iter().filter(|x| **x == o).count();
+    let pending = count(CheckOutcome::Pending);
+    PrChecksSummary {
+        number,
+        total: checks.len(),
+        pending,
+        passed: count(CheckOutcome::Passed),
+        failed: count(CheckOutcome::Failed),
+        settled: pending == 0 && (!checks.is_empty() || allow_empty),
+        timed_out: false,
+        checks,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhStatusRollup {
+    #[serde(default)]
+    status_check_rollup: Vec<GitHubPrStatusCheck>,
+}
+
+fn fetch_status_rollup(
+    number: u32,
+    priority: GhPriority,
+    project_path: Option<&str>,
+) -> Result<Vec<GitHubPrStatusCheck>, String> {
+    let output = run_gh_with_priority(priority, || {
+        let mut cmd = gh_command(project_path);
+        cmd.args(["pr", "view", &number.to_string(), "--json", "statusCheckRollup"]);
+        cmd
+    })?;
+    let parsed: GhStatusRollup = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse status checks: {}", e))?;
+    Ok(parsed.status_check_rollup)
+}
+
+/// Extract the Actions run id from a check's details URL
+/// (`https://github.com/o/r/actions/runs/<run>/job/<job>`).
+fn actions_run_id(details_url: &str) -> Option<u64> {
+    let (_, rest) = details_url.split_once("/actions/runs/")?;
+    rest.split('/').next()?.parse().ok()
+}
+
+/// Re-run the failed jobs of every GitHub Actions run with a failed check on
+/// the PR. Returns the run ids that were re-run. Checks from external CI
+/// (no Actions run URL) can't be re-run from here and are skipped.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn rerun_failed_checks(number: u32, project_path: Option<String>) -> Result<Vec<u64>, String> {
+    let checks = fetch_status_rollup(number, GhPriority::User, project_path.as_deref())?;
+    let run_ids: BTreeSet<u64> = checks
+        .iter()
+        .filter(|c| check_outcome(c) == CheckOutcome::Failed)
+        .filter_map(|c| c.details_url.as_deref().and_then(actions_run_id))
+        .collect();
+
+    for run_id in &run_ids {
+        run_gh_with_priority(GhPriority::User, || {
+            let mut cmd = gh_command(project_path.as_deref());
+            cmd.args(["run", "rerun", &run_id.to_string(), "--failed"]);
+            cmd
+        })?;
+    }
+
+    Ok(run_ids.into_iter().collect())
+}
+
+/// Default poll interval and overall budget for `wait_for_checks`.
+const CHECKS_POLL_INTERVAL_SECS: u64 = 15;
+const CHECKS_TIMEOUT_SECS: u64 = 30 * 60;
+/// How long a PR with no checks yet is given for CI to register some before
+/// the empty rollup counts as settled.
+const CHECKS_APPEAR_GRACE_SECS: u64 = 2 * 60;
+/// How often a sleeping wait checks for cancellation.
+const CHECKS_CANCEL_POLL: Duration = Duration::from_millis(250);
+
+/// Cancellation flags of running `wait_for_checks` calls, by project and PR.
+type CheckWaitKey = (Option<String>, u32);
+
+fn check_waits() -> &'static Mutex<HashMap<CheckWaitKey, Arc<AtomicBool>>> {
+    static WAITS: OnceLock<Mutex<HashMap<CheckWaitKey, Arc<AtomicBool>>>> = OnceLock::new();
+    WAITS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Registers a wait's cancellation flag for as long as it's alive.
+struct CheckWaitGuard(CheckWaitKey);
+
+impl CheckWaitGuard {
+    /// Fails if a wait on the same PR is already running.
+    fn register(key: CheckWaitKey) -> Result<(Self, Arc<AtomicBool>), String> {
+        let mut map = check_waits().lock().map_err(|e| e.to_string())?;
+        if map.contains_key(&key) {
+            return Err(format!("Already waiting for checks on PR #{}", key.1));
+        }
+        let flag = Arc::new(AtomicBool::new(false));
+        map.insert(key.clone(), flag.clone());
+        Ok((CheckWaitGuard(key), flag))
+    }
+}
+
+impl Drop for CheckWaitGuard {
+    fn drop(&mut self) {
+        if let Ok(mut map) = check_waits().lock() {
+            map.remove(&self.0);
+        }
+    }
+}
+
+fn wait_for_checks_blocking(
+    app: AppHandle,
+    number: u32,
+    interval: Duration,
+    timeout: Duration,
+    project_path: Option<String>,
+) -> Result<PrChecksSummary, String> {
+    let (_guard, cancel) = CheckWaitGuard::register((project_path.clone(), number))?;
+    let started = Instant::now();
+    let deadline = started + timeout;
+    let grace = Duration::from_secs(CHECKS_APPEAR_GRACE_SECS);
+
+    loop {
+        // Polling is background work: it yields to user actions and stops
+        // short of the reserve when the core bucket runs low.
+        let checks = fetch_status_rollup(number, GhPriority::Background, project_path.as_deref())?;
+        let mut summary = summarize_checks(number, checks, started.elapsed() >= grace);
+        let out_of_time = Instant::now() + interval > deadline;
+        summary.timed_out = !summary.settled && out_of_time;
+
+        if let Err(e) = app.emit("pr-checks-progress", summary.clone()) {
+            log::error!("Failed to emit pr-checks-progress event: {}", e);
+        }
+
+        if summary.settled || out_of_time {
+            return Ok(summary);
+        }
+        let wake = Instant::now() + interval;
+        while Instant::now() < wake {
+            if cancel.load(Ordering::SeqCst) {
+                return Err(format!("Waiting for checks on PR #{} cancelled", number));
+            }
+            std::thread::sleep(CHECKS_CANCEL_POLL);
+        }
+    }
+}
+
+/// Long-poll a PR's status checks until none are pending, emitting a
+/// `pr-checks-progress` event with a `PrChecksSummary` after every poll.
+/// Returns the final summary; `timedOut` is set if the budget ran out first.
+/// Only one wait per PR runs at a time; stop it with `cancel_wait_for_checks`.
+/// - interval_secs: Seconds between polls. Defaults to 15 (minimum 5).
+/// - timeout_secs: Overall budget. Defaults to 30 minutes.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub async fn wait_for_checks(
+    app: AppHandle,
+    number: u32,
+    interval_secs: Option<u64>,
+    timeout_secs: Option<u64>,
+    project_path: Option<String>,
+) -> Result<PrChecksSummary, String> {
+    let interval = Duration::from_secs(interval_secs.unwrap_or(CHECKS_POLL_INTERVAL_SECS).max(5));
+    let timeout = Duration::from_secs(timeout_secs.unwrap_or(CHECKS_TIMEOUT_SECS));
+    tauri::async_runtime::spawn_blocking(move || {
+        wait_for_checks_blocking(app, number, interval, timeout, project_path)
+    })
+    .await
+    .map_err(|e| format!("Check wait task failed: {}", e))?
+}
+
+/// Stop a running `wait_for_checks` on a PR. Returns false if none is
+/// running (it may already have finished).
+/// - project_path: Must match the one the wait was started with.
+#[tauri::command]
+pub fn cancel_wait_for_checks(number: u32, project_path: Option<String>) -> Result<bool, String> {
+    let map = check_waits().lock().map_err(|e| e.to_string())?;
+    match map.get(&(project_path, number)) {
+        Some(flag) => {
+            flag.store(true, Ordering::SeqCst);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(value: serde_json::Value) -> GitHubPrStatusCheck {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn classifies_check_runs_and_status_contexts() {
+        let cases = [
+            (serde_json::json!({ "status": "IN_PROGRESS" }), CheckOutcome::Pending),
+            (serde_json::json!({ "status": "COMPLETED", "conclusion": "SUCCESS" }), CheckOutcome::Passed),
+            (serde_json::json!({ "status": "COMPLETED", "conclusion": "SKIPPED" }), CheckOutcome::Passed),
+            (serde_json::json!({ "status": "COMPLETED", "conclusion": "TIMED_OUT" }), CheckOutcome::Failed),
+            (serde_json::json!({ "context": "ci/legacy", "state": "PENDING" }), CheckOutcome::Pending),
+            (serde_json::json!({ "context": "ci/legacy", "state": "ERROR" }), CheckOutcome::Failed),
+            (serde_json::json!({ "context": "ci/legacy", "state": "SUCCESS" }), CheckOutcome::Passed),
+        ];
+        for (payload, expected) in cases {
+            assert_eq!(check_outcome(&check(payload.clone())), expected, "{}", payload);
+        }
+    }
+
+    #[test]
+    fn summary_counts_and_settles() {
+        let pending = summarize_checks(
+            5,
+            vec![
+                check(serde_json::json!({ "status": "COMPLETED", "conclusion": "SUCCESS" })),
+                check(serde_json::json!({ "status": "QUEUED" })),
+            ],
+            false,
+        );
+        assert_eq!((pending.total, pending.passed, pending.pending), (2, 1, 1));
+        assert!(!pending.settled);
+
+        let done = summarize_checks(
+            5,
+            vec![check(serde_json::json!({ "status": "COMPLETED", "conclusion": "FAILURE" }))],
+            false,
+        );
+        assert!(done.settled);
+        assert_eq!(done.failed, 1);
+        let value = serde_json::to_value(&done).unwrap();
+        assert_eq!(value["timedOut"], false);
+
+        // No checks yet only settles once the grace period is over.
+        assert!(!summarize_checks(5, Vec::new(), false).settled);
+        assert!(summarize_checks(5, Vec::new(), true).settled);
+    }
+
+    #[test]
+    fn one_wait_per_pr_until_dropped() {
+        let key = (Some("/tmp/tiki-checks-test".to_string()), 77);
+        let (guard, flag) = CheckWaitGuard::register(key.clone()).unwrap();
+        assert!(CheckWaitGuard::register(key.clone()).is_err());
+        assert!(cancel_wait_for_checks(77, key.0.clone()).unwrap());
+        assert!(flag.load(Ordering::SeqCst));
+        drop(guard);
+        assert!(!cancel_wait_for_checks(77, key.0.clone()).unwrap());
+        assert!(CheckWaitGuard::register(key).is_ok());
+    }
+
+    #[test]
+    fn extracts_actions_run_id() {
+        assert_eq!(
+            actions_run_id("https://github.com/o/r/actions/runs/12345/job/678"),
+            Some(12345)
+        );
+        assert_eq!(actions_run_id("https://ci.example.com/build/1"), None);
+    }
+
+    #[test]
+    fn deserializes_rest_review_comment() {
+        let payload = serde_json::json!({
+            "id": 99,
+            "path": "src/lib.rs",
+            "line": 12,
+            "body": "nit",
+            "url": "https://api.github.com/repos/o/r/pulls/comments/99",
+            "html_url": "https://github.com/o/r/pull/1#discussion_r99",
+            "commit_id": "abc"
+        });
+        let comment: GitHubReviewComment = serde_json::from_value(payload).unwrap();
+        assert_eq!(comment.id, 99);
+        assert_eq!(comment.line, Some(12));
+        assert_eq!(comment.url, "https://github.com/o/r/pull/1#discussion_r99");
+    }
+}
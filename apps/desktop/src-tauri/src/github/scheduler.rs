@@ -0,0 +1,541 @@
+//! Rate-limit-aware admission control for every `gh` call.
+//!
+//! All GitHub traffic funnels through `acquire`, which tracks the remaining
+//! quota per host and bucket (seeded from `gh api rate_limit`, decremented
+//! locally per request), limits concurrency, and admits each bucket's waiting
+//! requests in priority order: user-initiated mutations first, ordinary fetches next, background
+//! polling and bulk jobs last. Lower priorities keep a larger share of each
+//! bucket in reserve, so a background label sync can never drain the core
+//! quota the user needs for their next click.
+//!
+//! Secondary rate limits (`Retry-After`) and primary-limit exhaustion block
+//! the affected bucket until the server says it's safe again; waits are
+//! reported via a `rate-limit-throttled` event so the UI can explain stalls.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use super::RateLimitStatus;
+
+/// Maximum concurrent `gh` processes.
+const MAX_IN_FLIGHT: usize = 4;
+
+/// Re-read `gh api rate_limit` (free) when the local snapshot is this old.
+const SNAPSHOT_MAX_AGE_SECS: u64 = 300;
+
+/// Quota host for `gh` calls that don't pin one with `GH_HOST`.
+pub(crate) const DEFAULT_GH_HOST: &str = "github.com";
+
+/// Re-reads a host's quota snapshot. `None` for transports whose responses
+/// already carry the `x-ratelimit-*` headers.
+pub(crate) type QuotaRefresh<'a> = Option<&'a dyn Fn() -> Result<RateLimitStatus, String>>;
+
+/// Who asked for a request. Ordered highest priority first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GhPriority {
+    /// Direct user action (create/edit/merge/comment).
+    User,
+    /// Regular data fetches backing the current view.
+    Normal,
+    /// Polling, bulk operations, background refresh.
+    Background,
+}
+
+impl GhPriority {
+    /// Share of a bucket's limit this priority must leave untouched.
+    fn reserve(self, limit: u32) -> u32 {
+        match self {
+            GhPriority::User => 0,
+            GhPriority::Normal => limit / 20,
+            GhPriority::Background => limit / 4,
+        }
+    }
+}
+
+/// The `gh api rate_limit` bucket a request draws from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GhBucket {
+    Core,
+    Graphql,
+    Search,
+}
+
+/// Payload of the `rate-limit-throttled` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitThrottledEvent {
+    pub host: String,
+    pub bucket: GhBucket,
+    pub priority: GhPriority,
+    /// "quota" (bucket below this priority's reserve) or "retryAfter"
+    /// (secondary rate limit / abuse detection backoff).
+    pub reason: String,
+    /// Unix epoch seconds when the request can proceed.
+    pub resume_at_epoch: u64,
+    pub remaining: Option<u32>,
+    /// Expected wait in seconds from when the event was sent.
+    pub wait_secs: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BucketQuota {
+    limit: u32,
+    remaining: u32,
+    reset: u64,
+}
+
+/// Everything known about one host's quotas. Each GitHub host (github.com,
+/// every GHE instance) has its own independent limits.
+#[derive(Debug, Default)]
+struct HostQuotas {
+    quotas: HashMap<GhBucket, BucketQuota>,
+    /// Per-bucket "don't send before" from Retry-After / secondary limits.
+    blocked_until: HashMap<GhBucket, u64>,
+    snapshot_at: u64,
+    refreshing: bool,
+}
+
+impl HostQuotas {
+    fn record_status(&mut self, status: &RateLimitStatus) {
+        for (bucket, b) in [
+            (GhBucket::Core, &status.core),
+            (GhBucket::Graphql, &status.graphql),
+            (GhBucket::Search, &status.search),
+        ] {
+            self.quotas.insert(
+                bucket,
+                BucketQuota {
+                    limit: b.limit,
+                    remaining: b.remaining,
+                    reset: b.reset,
+                },
+            );
+        }
+        self.snapshot_at = status.fetched_at_epoch;
+    }
+}
+
+/// One quota: a bucket on a host.
+type QuotaKey = (String, GhBucket);
+
+#[derive(Debug, Default)]
+struct SchedulerState {
+    hosts: HashMap<String, HostQuotas>,
+    in_flight: usize,
+    /// Waiting tickets per quota, ordered by (priority, arrival). Quotas are
+    /// independent, so a waiter only queues behind others on the same one.
+    queues: HashMap<QuotaKey, BTreeSet<(GhPriority, u64)>>,
+    next_ticket: u64,
+}
+
+/// Admission decision for one waiting ticket.
+#[derive(Debug, PartialEq)]
+enum Admission {
+    Go,
+    /// Another ticket is ahead, or all slots are busy.
+    WaitTurn,
+    /// Quota/backoff block until the given epoch.
+    WaitUntil { epoch: u64, reason: &'static str },
+}
+
+impl SchedulerState {
+    fn host(&mut self, host: &str) -> &mut HostQuotas {
+        self.hosts.entry(host.to_string()).or_default()
+    }
+
+    fn admission(&self, ticket: (GhPriority, u64), key: &QuotaKey, now: u64) -> Admission {
+        let (host, bucket) = key;
+        if let Some(quotas) = self.hosts.get(host) {
+            if let Some(&until) = quotas.blocked_until.get(bucket) {
+                if until > now {
+                    return Admission::WaitUntil { epoch: until, reason: "retryAfter" };
+                }
+            }
+            if let Some(q) = quotas.quotas.get(bucket) {
+                if q.reset > now && q.remaining <= ticket.0.reserve(q.limit) {
+                    return Admission::WaitUntil { epoch: q.reset, reason: "quota" };
+                }
+            }
+        }
+        let higher_waiting = self
+            .queues
+            .get(key)
+            .and_then(|queue| queue.iter().next())
+            .is_some_and(|&(p, _)| p < ticket.0);
+        if higher_waiting || self.in_flight >= MAX_IN_FLIGHT {
+            return Admission::WaitTurn;
+        }
+        Admission::Go
+    }
+
+    fn consume(&mut self, key: &QuotaKey, now: u64) {
+        let (host, bucket) = key;
+        if let Some(q) = self.hosts.get_mut(host).and_then(|h| h.quotas.get_mut(bucket)) {
+            if q.reset <= now {
+                // Window rolled over since the snapshot; assume a full bucket.
+                q.remaining = q.limit;
+            }
+            q.remaining = q.remaining.saturating_sub(1);
+        }
+    }
+
+    fn enqueue(&mut self, key: &QuotaKey, ticket: (GhPriority, u64)) {
+        self.queues.entry(key.clone()).or_default().insert(ticket);
+    }
+
+    fn dequeue(&mut self, key: &QuotaKey, ticket: (GhPriority, u64)) {
+        if let Some(queue) = self.queues.get_mut(key) {
+            queue.remove(&ticket);
+            if queue.is_empty() {
+                self.queues.remove(key);
+            }
+        }
+    }
+}
+
+struct Scheduler {
+    state: Mutex<SchedulerState>,
+    cond: Condvar,
+}
+
+fn scheduler() -> &'static Scheduler {
+    static SCHEDULER: OnceLock<Scheduler> = OnceLock::new();
+    SCHEDULER.get_or_init(|| Scheduler {
+        state: Mutex::new(SchedulerState::default()),
+        cond: Condvar::new(),
+    })
+}
+
+fn app_handle() -> &'static OnceLock<AppHandle> {
+    static APP: OnceLock<AppHandle> = OnceLock::new();
+    &APP
+}
+
+/// Give the scheduler an `AppHandle` for `rate-limit-throttled` events.
+/// Called once from the app's `setup` hook.
+pub fn init_gh_scheduler(app: AppHandle) {
+    let _ = app_handle().set(app);
+}
+
+fn now_epoch() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn emit_throttled(event: RateLimitThrottledEvent) {
+    log::warn!(
+        "gh request throttled: {} {:?}/{:?} {} until {} ({}s)",
+        event.host,
+        event.bucket,
+        event.priority,
+        event.reason,
+        event.resume_at_epoch,
+        event.wait_secs
+    );
+    if let Some(app) = app_handle().get() {
+        if let Err(e) = app.emit("rate-limit-throttled", event) {
+            log::error!("Failed to emit rate-limit-throttled event: {}", e);
+        }
+    }
+}
+
+/// Refresh `host`'s quota snapshot if it's stale. Only one thread refreshes
+/// a host; the rest proceed with the old numbers.
+fn maybe_refresh_snapshot(host: &str, refresh: QuotaRefresh) {
+    let Some(refresh) = refresh else {
+        return;
+    };
+    let sched = scheduler();
+    {
+        let mut state = sched.state.lock().unwrap_or_else(|e| e.into_inner());
+        let quotas = state.host(host);
+        if quotas.refreshing || now_epoch().saturating_sub(quotas.snapshot_at) < SNAPSHOT_MAX_AGE_SECS {
+            return;
+        }
+        quotas.refreshing = true;
+    }
+    let result = refresh();
+    let mut state = sched.state.lock().unwrap_or_else(|e| e.into_inner());
+    let quotas = state.host(host);
+    quotas.refreshing = false;
+    match result {
+        Ok(status) => quotas.record_status(&status),
+        Err(e) => {
+            log::debug!("Rate limit snapshot refresh for {} failed: {}", host, e);
+            // Don't hammer a failing endpoint; try again next window.
+            quotas.snapshot_at = now_epoch();
+        }
+    }
+}
+
+/// A granted slot. Dropping it frees the slot for the next waiter.
+pub(crate) struct GhPermit(());
+
+impl Drop for GhPermit {
+    fn drop(&mut self) {
+        let sched = scheduler();
+        let mut state = sched.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.in_flight = state.in_flight.saturating_sub(1);
+        sched.cond.notify_all();
+    }
+}
+
+/// Wait for permission to send one request from `host`'s `bucket` at
+/// `priority`. `refresh` re-reads the host's quotas when the snapshot is
+/// stale. However long the wait, the request stays queued; the expected wait
+/// is reported through `rate-limit-throttled` whenever it changes, and giving
+/// up is left to the caller's timeout or cancel.
+pub(crate) fn acquire(
+    host: &str,
+    bucket: GhBucket,
+    priority: GhPriority,
+    refresh: QuotaRefresh,
+) -> GhPermit {
+    maybe_refresh_snapshot(host, refresh);
+
+    let sched = scheduler();
+    let mut state = sched.state.lock().unwrap_or_else(|e| e.into_inner());
+    let key: QuotaKey = (host.to_string(), bucket);
+    let ticket = (priority, state.next_ticket);
+    state.next_ticket += 1;
+    state.enqueue(&key, ticket);
+    let mut announced: Option<u64> = None;
+
+    loop {
+        let now = now_epoch();
+        match state.admission(ticket, &key, now) {
+            Admission::Go => {
+                state.dequeue(&key, ticket);
+                state.in_flight += 1;
+                state.consume(&key, now);
+                return GhPermit(());
+            }
+            Admission::WaitTurn => {
+                state = sched
+                    .cond
+                    .wait_timeout(state, Duration::from_secs(1))
+                    .unwrap_or_else(|e| e.into_inner())
+                    .0;
+            }
+            Admission::WaitUntil { epoch, reason } => {
+                let remaining = state
+                    .hosts
+                    .get(host)
+                    .and_then(|h| h.quotas.get(&bucket))
+                    .map(|q| q.remaining);
+                if announced != Some(epoch) {
+                    announced = Some(epoch);
+                    emit_throttled(RateLimitThrottledEvent {
+                        host: host.to_string(),
+                        bucket,
+                        priority,
+                        reason: reason.to_string(),
+                        resume_at_epoch: epoch,
+                        remaining,
+                        wait_secs: epoch.saturating_sub(now),
+                    });
+                }
+                state = sched
+                    .cond
+                    .wait_timeout(state, Duration::from_secs(epoch.saturating_sub(now).clamp(1, 5)))
+                    .unwrap_or_else(|e| e.into_inner())
+                    .0;
+            }
+        }
+    }
+}
+
+/// Block `host`'s `bucket` for `secs` (Retry-After / secondary rate limit
+/// backoff).
+pub(crate) fn block_bucket(host: &str, bucket: GhBucket, secs: u64) {
+    let sched = scheduler();
+    let mut state = sched.state.lock().unwrap_or_else(|e| e.into_inner());
+    let until = now_epoch() + secs;
+    let entry = state.host(host).blocked_until.entry(bucket).or_insert(0);
+    *entry = (*entry).max(until);
+}
+
+/// The primary limit was hit: mark the bucket empty and force a snapshot
+/// refresh so the real reset time is picked up.
+pub(crate) fn mark_exhausted(host: &str, bucket: GhBucket) {
+    let sched = scheduler();
+    let mut state = sched.state.lock().unwrap_or_else(|e| e.into_inner());
+    let quotas = state.host(host);
+    if let Some(q) = quotas.quotas.get_mut(&bucket) {
+        q.remaining = 0;
+    }
+    quotas.snapshot_at = 0;
+}
+
+/// Record one bucket's quota from `x-ratelimit-*` response headers (API backend).
+pub(crate) fn record_quota(host: &str, bucket: GhBucket, limit: u32, remaining: u32, reset: u64) {
+    let sched = scheduler();
+    let mut state = sched.state.lock().unwrap_or_else(|e| e.into_inner());
+    state.host(host).quotas.insert(
+        bucket,
+        BucketQuota {
+            limit,
+            remaining,
+            reset,
+        },
+    );
+    sched.cond.notify_all();
+}
+
+/// Feed a fresh `gh api rate_limit` snapshot for `host` into the scheduler.
+pub(crate) fn record_rate_limit_status(host: &str, status: &RateLimitStatus) {
+    let sched = scheduler();
+    let mut state = sched.state.lock().unwrap_or_else(|e| e.into_inner());
+    state.host(host).record_status(status);
+    sched.cond.notify_all();
+}
+
+/// Extract a `Retry-After` delay (seconds) from gh stderr, if present.
+pub(crate) fn parse_retry_after(stderr: &str) -> Option<u64> {
+    let lower = stderr.to_lowercase();
+    let idx = lower.find("retry-after").or_else(|| lower.find("retry after"))?;
+    lower[idx + "retry-after".len()..]
+        .trim_start_matches(|c: char| c == ':' || c.is_whitespace())
+        .split(|c: char| !c.is_ascii_digit())
+        .next()
+        .and_then(|n| n.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::RateLimitBucket;
+
+    fn status(core_remaining: u32, reset: u64) -> RateLimitStatus {
+        let bucket = |remaining| RateLimitBucket {
+            limit: 5000,
+            used: 5000 - remaining,
+            remaining,
+            reset,
+        };
+        RateLimitStatus {
+            core: bucket(core_remaining),
+            search: bucket(30),
+            graphql: bucket(5000),
+            fetched_at_epoch: 1_000,
+        }
+    }
+
+    fn key(host: &str, bucket: GhBucket) -> QuotaKey {
+        (host.to_string(), bucket)
+    }
+
+    #[test]
+    fn lower_priorities_keep_a_reserve() {
+        let mut state = SchedulerState::default();
+        // 1000 of 5000 left: below the background reserve (1250), above normal (250).
+        state.host("github.com").record_status(&status(1000, 2_000));
+        let core = key("github.com", GhBucket::Core);
+
+        assert_eq!(
+            state.admission((GhPriority::Background, 0), &core, 1_500),
+            Admission::WaitUntil { epoch: 2_000, reason: "quota" }
+        );
+        assert_eq!(state.admission((GhPriority::Normal, 0), &core, 1_500), Admission::Go);
+        // Other buckets and hosts are unaffected.
+        assert_eq!(
+            state.admission((GhPriority::Background, 0), &key("github.com", GhBucket::Graphql), 1_500),
+            Admission::Go
+        );
+        assert_eq!(
+            state.admission((GhPriority::Background, 0), &key("ghe.example.com", GhBucket::Core), 1_500),
+            Admission::Go
+        );
+        // After the reset the reserve no longer applies.
+        assert_eq!(state.admission((GhPriority::Background, 0), &core, 2_001), Admission::Go);
+    }
+
+    #[test]
+    fn higher_priority_waiters_go_first() {
+        let mut state = SchedulerState::default();
+        let core = key("github.com", GhBucket::Core);
+        state.enqueue(&core, (GhPriority::User, 5));
+        state.enqueue(&core, (GhPriority::Background, 1));
+
+        assert_eq!(state.admission((GhPriority::Background, 1), &core, 0), Admission::WaitTurn);
+        assert_eq!(state.admission((GhPriority::User, 5), &core, 0), Admission::Go);
+
+        state.in_flight = MAX_IN_FLIGHT;
+        assert_eq!(state.admission((GhPriority::User, 5), &core, 0), Admission::WaitTurn);
+    }
+
+    #[test]
+    fn waiters_only_queue_behind_their_own_quota() {
+        let mut state = SchedulerState::default();
+        state.enqueue(&key("github.com", GhBucket::Graphql), (GhPriority::User, 1));
+        state.enqueue(&key("ghe.example.com", GhBucket::Core), (GhPriority::User, 2));
+
+        let core = key("github.com", GhBucket::Core);
+        state.enqueue(&core, (GhPriority::Background, 3));
+        assert_eq!(state.admission((GhPriority::Background, 3), &core, 0), Admission::Go);
+
+        state.dequeue(&core, (GhPriority::Background, 3));
+        assert!(!state.queues.contains_key(&core));
+    }
+
+    #[test]
+    fn retry_after_blocks_bucket_for_everyone() {
+        let mut state = SchedulerState::default();
+        state.host("github.com").blocked_until.insert(GhBucket::Core, 130);
+        let core = key("github.com", GhBucket::Core);
+        assert_eq!(
+            state.admission((GhPriority::User, 0), &core, 100),
+            Admission::WaitUntil { epoch: 130, reason: "retryAfter" }
+        );
+        assert_eq!(state.admission((GhPriority::User, 0), &core, 131), Admission::Go);
+        assert_eq!(
+            state.admission((GhPriority::User, 0), &key("ghe.example.com", GhBucket::Core), 100),
+            Admission::Go
+        );
+    }
+
+    #[test]
+    fn consume_refills_after_reset() {
+        let mut state = SchedulerState::default();
+        state.host("github.com").record_status(&status(10, 2_000));
+        let core = key("github.com", GhBucket::Core);
+        state.consume(&core, 1_500);
+        assert_eq!(state.hosts["github.com"].quotas[&GhBucket::Core].remaining, 9);
+        state.consume(&core, 2_500);
+        assert_eq!(state.hosts["github.com"].quotas[&GhBucket::Core].remaining, 4999);
+    }
+
+    #[test]
+    fn snapshot_refresh_uses_the_injected_fetcher_per_host() {
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+        let fetch = || {
+            calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let mut fresh = status(42, now_epoch() + 600);
+            fresh.fetched_at_epoch = now_epoch();
+            Ok(fresh)
+        };
+        let host = "snapshot-test.example.com";
+        maybe_refresh_snapshot(host, Some(&fetch));
+        // Fresh now: no second fetch.
+        maybe_refresh_snapshot(host, Some(&fetch));
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        let state = scheduler().state.lock().unwrap();
+        assert_eq!(state.hosts[host].quotas[&GhBucket::Core].remaining, 42);
+    }
+
+    #[test]
+    fn parses_retry_after() {
+        assert_eq!(parse_retry_after("HTTP 403: secondary rate limit\nRetry-After: 60"), Some(60));
+        assert_eq!(parse_retry_after("please retry after 30 seconds"), Some(30));
+        assert_eq!(parse_retry_after("HTTP 403: secondary rate limit"), None);
+    }
+}
@@ -0,0 +1,202 @@
+//! Per-project GitHub host/repo targeting.
+//!
+//! By default `gh` talks to github.com (or whatever host the git remote
+//! points at) and picks the repo from the cwd's remotes, which goes wrong for
+//! GitHub Enterprise checkouts and for forks with several remotes. Projects
+//! can pin both in `.tiki/config.json`:
+//!
+//! ```json
+//! { "github": { "host": "github.example.com", "repo": "team/service" } }
+//! ```
+//!
+//! Every `gh` spawn goes through `gh_command`, which applies the target via
+//! `GH_HOST` and `GH_REPO` — gh's environment equivalents of `--hostname` and
+//! `-R`. The environment form also reaches `gh api`, where `{owner}`/`{repo}`
+//! placeholders are expanded from `GH_REPO` but `-R` is not accepted.
+
+use std::path::Path;
+use std::process::Command;
+
+use super::hidden_command;
+use crate::config::load_tiki_config;
+use crate::fs_utils::project_root;
+
+/// Host and repo overrides from `github.host` / `github.repo`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct GhTarget {
+    /// Bare hostname, e.g. "github.example.com".
+    pub(crate) host: Option<String>,
+    /// "owner/repo".
+    pub(crate) repo: Option<String>,
+}
+
+impl GhTarget {
+    /// Read the target from a project's `.tiki/config.json`. Invalid values
+    /// are logged and ignored so a typo falls back to gh's own detection.
+    pub(crate) fn load(root: &Path) -> Self {
+        let Some(github) = load_tiki_config(&root.join(".tiki")).github else {
+            return GhTarget::default();
+        };
+
+        let host = github.host.as_deref().and_then(normalize_host);
+        let repo = github.repo.as_deref().and_then(|r| {
+            let normalized = normalize_repo(r);
+            if normalized.is_none() {
+                log::warn!("Ignoring github.repo '{}': expected \"owner/repo\"", r);
+            }
+            normalized
+        });
+        GhTarget { host, repo }
+    }
+
+    pub(crate) fn for_project(project_path: Option<&str>) -> Self {
+        match project_root(project_path) {
+            Ok(root) => GhTarget::load(&root),
+            Err(_) => GhTarget::default(),
+        }
+    }
+
+    /// `GH_REPO` value in gh's `[HOST/]OWNER/REPO` form.
+    pub(crate) fn gh_repo(&self) -> Option<String> {
+        let repo = self.repo.as_deref()?;
+        Some(match self.host {
+            Some(ref host) => format!("{}/{}", host, repo),
+            None => repo.to_string(),
+        })
+    }
+
+    /// The git remote that points at the pinned repo, from `git remote -v`
+    /// output. `None` when no repo is pinned or no remote matches.
+    pub(crate) fn matching_remote(&self, remotes: &str) -> Option<String> {
+        let repo = self.repo.as_deref()?.to_lowercase();
+        remotes.lines().find_map(|line| {
+            let mut fields = line.split_whitespace();
+            let (name, url) = (fields.next()?, fields.next()?);
+            let url = url.to_lowercase();
+            let url = url.trim_end_matches('/').trim_end_matches(".git");
+            let on_host = self.host.as_deref().map_or(true, |h| url.contains(h));
+            let path_matches = url
+                .strip_suffix(repo.as_str())
+                .is_some_and(|rest| rest.ends_with('/') || rest.ends_with(':'));
+            (on_host && path_matches).then(|| name.to_string())
+        })
+    }
+
+    /// Point a `gh` command at this target.
+    pub(crate) fn apply(&self, cmd: &mut Command) {
+        if let Some(ref host) = self.host {
+            cmd.env("GH_HOST", host);
+        }
+        if let Some(repo) = self.gh_repo() {
+            cmd.env("GH_REPO", repo);
+        }
+    }
+}
+
+/// Accept "github.example.com", "https://github.example.com/" and the like.
+fn normalize_host(host: &str) -> Option<String> {
+    let host = host.trim();
+    let host = host.split_once("://").map_or(host, |(_, rest)| rest);
+    let host = host.trim_end_matches('/');
+    (!host.is_empty() && !host.contains('/')).then(|| host.to_lowercase())
+}
+
+/// Accept "owner/repo" (optionally with a trailing ".git").
+fn normalize_repo(repo: &str) -> Option<String> {
+    let repo = repo.trim().trim_end_matches(".git");
+    let (owner, name) = repo.split_once('/')?;
+    let valid = |s: &str| !s.is_empty() && !s.contains(char::is_whitespace);
+    (valid(owner) && valid(name) && !name.contains('/')).then(|| repo.to_string())
+}
+
+/// A `gh` command for a project: runs in the project directory (so gh can
+/// still fall back to the git remotes) and targets the configured host/repo.
+pub(crate) fn gh_command(project_path: Option<&str>) -> Command {
+    let mut cmd = hidden_command("gh");
+    if let Some(path) = project_path {
+        cmd.current_dir(path);
+    }
+    GhTarget::for_project(project_path).apply(&mut cmd);
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_project(tag: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("tiki-gh-target-{}-{}", tag, nanos));
+        std::fs::create_dir_all(dir.join(".tiki")).unwrap();
+        dir
+    }
+
+    fn env_of(cmd: &Command, key: &str) -> Option<String> {
+        cmd.get_envs()
+            .find(|(k, _)| *k == key)
+            .and_then(|(_, v)| v.map(|v| v.to_string_lossy().into_owned()))
+    }
+
+    #[test]
+    fn loads_and_applies_configured_target() {
+        let dir = temp_project("configured");
+        std::fs::write(
+            dir.join(".tiki/config.json"),
+            r#"{ "github": { "host": "https://GHE.example.com/", "repo": "team/service.git" } }"#,
+        )
+        .unwrap();
+
+        let target = GhTarget::load(&dir);
+        assert_eq!(target.host.as_deref(), Some("ghe.example.com"));
+        assert_eq!(target.repo.as_deref(), Some("team/service"));
+
+        let cmd = gh_command(dir.to_str());
+        assert_eq!(env_of(&cmd, "GH_HOST").as_deref(), Some("ghe.example.com"));
+        assert_eq!(env_of(&cmd, "GH_REPO").as_deref(), Some("ghe.example.com/team/service"));
+        assert_eq!(cmd.get_current_dir(), Some(dir.as_path()));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unconfigured_project_leaves_gh_detection_alone() {
+        let dir = temp_project("default");
+        let cmd = gh_command(dir.to_str());
+        assert_eq!(env_of(&cmd, "GH_HOST"), None);
+        assert_eq!(env_of(&cmd, "GH_REPO"), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn finds_the_remote_for_the_pinned_repo() {
+        let remotes = "\
+origin\tgit@github.com:me/service.git (fetch)
+origin\tgit@github.com:me/service.git (push)
+upstream\thttps://ghe.example.com/team/service.git (fetch)
+upstream\thttps://ghe.example.com/team/service.git (push)
+";
+        let target = GhTarget {
+            host: Some("ghe.example.com".to_string()),
+            repo: Some("team/service".to_string()),
+        };
+        assert_eq!(target.matching_remote(remotes).as_deref(), Some("upstream"));
+        let fork = GhTarget { host: None, repo: Some("me/service".to_string()) };
+        assert_eq!(fork.matching_remote(remotes).as_deref(), Some("origin"));
+        let other = GhTarget { host: None, repo: Some("team/other".to_string()) };
+        assert_eq!(other.matching_remote(remotes), None);
+        assert_eq!(GhTarget::default().matching_remote(remotes), None);
+    }
+
+    #[test]
+    fn rejects_malformed_repo_and_host() {
+        assert_eq!(normalize_repo("owner/repo").as_deref(), Some("owner/repo"));
+        assert_eq!(normalize_repo("just-a-name"), None);
+        assert_eq!(normalize_repo("a/b/c"), None);
+        assert_eq!(normalize_repo("/repo"), None);
+        assert_eq!(normalize_host("  "), None);
+        assert_eq!(normalize_host("host.example.com/path"), None);
+    }
+}
@@ -0,0 +1,448 @@
+//! Repository issue templates (`.github/ISSUE_TEMPLATE/`).
+//!
+//! Two kinds exist: Markdown templates (`*.md`, YAML front matter + a body
+//! to pre-fill) and issue forms (`*.yml`/`*.yaml`, a list of typed fields).
+//! Forms are rendered the way github.com renders a submitted form: one
+//! `### Label` section per field, `_No response_` for empty optional fields.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::fs_utils::project_root;
+
+const TEMPLATE_DIR: &str = ".github/ISSUE_TEMPLATE";
+
+/// What GitHub writes for an optional form field left empty.
+const NO_RESPONSE: &str = "_No response_";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueTemplateKind {
+    Markdown,
+    Form,
+}
+
+/// An issue template, as listed by `list_issue_templates`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueTemplate {
+    /// File name within `.github/ISSUE_TEMPLATE/`; pass this to `create_github_issue`.
+    pub file: String,
+    pub kind: IssueTemplateKind,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Default title (usually a prefix such as "[Bug]: ").
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub labels: Vec<String>,
+    pub assignees: Vec<String>,
+    /// Markdown templates: the body to pre-fill.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Issue forms: the fields, in order.
+    pub fields: Vec<IssueTemplateField>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueTemplateField {
+    /// "markdown", "textarea", "input", "dropdown" or "checkboxes".
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Key for field values. Falls back to the label when the form gives no id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub attributes: FieldAttributes,
+    #[serde(default)]
+    pub validations: FieldValidations,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldAttributes {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+    /// Default value; for `markdown` fields, the text shown in the form.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<FieldOption>,
+    #[serde(default)]
+    pub multiple: bool,
+    /// Textarea: render the value as a fenced code block in this language.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub render: Option<String>,
+}
+
+/// A dropdown option (plain string in YAML) or a checkbox (`{label, required}`).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldOption {
+    pub label: String,
+    pub required: bool,
+}
+
+impl<'de> Deserialize<'de> for FieldOption {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Plain(String),
+            Checkbox {
+                label: String,
+                #[serde(default)]
+                required: bool,
+            },
+        }
+        Ok(match Raw::deserialize(deserializer)? {
+            Raw::Plain(label) => FieldOption { label, required: false },
+            Raw::Checkbox { label, required } => FieldOption { label, required },
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FieldValidations {
+    #[serde(default)]
+    pub required: bool,
+}
+
+impl IssueTemplateField {
+    /// Key callers use in the field-value map.
+    pub fn key(&self) -> Option<&str> {
+        self.id.as_deref().or(self.attributes.label.as_deref())
+    }
+}
+
+/// `labels: bug, triage` and `labels: [bug, triage]` are both valid.
+fn string_or_list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match Option::<Raw>::deserialize(deserializer)? {
+        None => Vec::new(),
+        Some(Raw::One(s)) => s
+            .split(',')
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect(),
+        Some(Raw::Many(v)) => v,
+    })
+}
+
+/// Front matter of a Markdown template / header of an issue form.
+#[derive(Debug, Deserialize)]
+struct TemplateHeader {
+    name: String,
+    #[serde(default, alias = "about")]
+    description: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default, deserialize_with = "string_or_list")]
+    labels: Vec<String>,
+    #[serde(default, deserialize_with = "string_or_list")]
+    assignees: Vec<String>,
+    /// Issue forms only.
+    #[serde(default)]
+    body: Vec<IssueTemplateField>,
+}
+
+fn parse_markdown_template(file: &str, contents: &str) -> Result<IssueTemplate, String> {
+    let rest = contents
+        .trim_start_matches('\u{feff}')
+        .strip_prefix("---")
+        .ok_or_else(|| format!("{}: missing front matter", file))?;
+    let end = rest
+        .find("\n---")
+        .ok_or_else(|| format!("{}: unterminated front matter", file))?;
+    let header: TemplateHeader = serde_yaml::from_str(&rest[..end])
+        .map_err(|e| format!("{}: invalid front matter: {}", file, e))?;
+    let body = rest[end + 4..].trim_start_matches(['\r', '\n']).to_string();
+
+    Ok(IssueTemplate {
+        file: file.to_string(),
+        kind: IssueTemplateKind::Markdown,
+        name: header.name,
+        description: header.description,
+        title: header.title.filter(|t| !t.is_empty()),
+        labels: header.labels,
+        assignees: header.assignees,
+        body: (!body.trim().is_empty()).then_some(body),
+        fields: Vec::new(),
+    })
+}
+
+fn parse_form_template(file: &str, contents: &str) -> Result<IssueTemplate, String> {
+    let header: TemplateHeader =
+        serde_yaml::from_str(contents).map_err(|e| format!("{}: invalid issue form: {}", file, e))?;
+    Ok(IssueTemplate {
+        file: file.to_string(),
+        kind: IssueTemplateKind::Form,
+        name: header.name,
+        description: header.description,
+        title: header.title.filter(|t| !t.is_empty()),
+        labels: header.labels,
+        assignees: header.assignees,
+        body: None,
+        fields: header.body,
+    })
+}
+
+fn read_templates(root: &Path) -> Vec<IssueTemplate> {
+    let dir = root.join(TEMPLATE_DIR);
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    files.sort();
+
+    let mut templates = Vec::new();
+    for path in files {
+        let Some(file) = path.file_name().and_then(|n| n.to_str()).map(str::to_string) else {
+            continue;
+        };
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+        // config.yml configures the chooser; it isn't a template.
+        if file.starts_with("config.") || !matches!(ext.as_str(), "md" | "yml" | "yaml") {
+            continue;
+        }
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(|e| format!("{}: {}", file, e))
+            .and_then(|c| match ext.as_str() {
+                "md" => parse_markdown_template(&file, &c),
+                _ => parse_form_template(&file, &c),
+            });
+        match parsed {
+            Ok(t) => templates.push(t),
+            Err(e) => log::warn!("Skipping issue template {}", e),
+        }
+    }
+    templates
+}
+
+/// List the repo's issue templates (Markdown templates and issue forms).
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn list_issue_templates(project_path: Option<String>) -> Result<Vec<IssueTemplate>, String> {
+    Ok(read_templates(&project_root(project_path.as_deref())?))
+}
+
+pub(crate) fn find_issue_template(root: &Path, file: &str) -> Result<IssueTemplate, String> {
+    read_templates(root)
+        .into_iter()
+        .find(|t| t.file == file || t.name == file)
+        .ok_or_else(|| format!("Issue template '{}' not found in {}", file, TEMPLATE_DIR))
+}
+
+// ─── Rendering ────────────────────────────────────────────────────────────────
+
+/// Field value as a list of strings: a JSON string, array, bool or number.
+fn value_strings(value: Option<&serde_json::Value>) -> Vec<String> {
+    match value {
+        None | Some(serde_json::Value::Null) => Vec::new(),
+        Some(serde_json::Value::String(s)) => {
+            if s.trim().is_empty() {
+                Vec::new()
+            } else {
+                vec![s.clone()]
+            }
+        }
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .flat_map(|v| value_strings(Some(v)))
+            .collect(),
+        Some(other) => vec![other.to_string()],
+    }
+}
+
+/// Render an issue form's body from field values keyed by field id (or
+/// label). Every validation problem is collected before failing.
+pub(crate) fn render_issue_form(
+    template: &IssueTemplate,
+    values: &HashMap<String, serde_json::Value>,
+) -> Result<String, String> {
+    let mut sections = Vec::new();
+    let mut errors = Vec::new();
+
+    for field in &template.fields {
+        if field.kind == "markdown" {
+            continue;
+        }
+        let Some(key) = field.key() else {
+            continue;
+        };
+        let label = field.attributes.label.as_deref().unwrap_or(key);
+        let given = value_strings(values.get(key));
+        let given = if given.is_empty() {
+            value_strings(field.attributes.value.clone().map(serde_json::Value::String).as_ref())
+        } else {
+            given
+        };
+
+        let rendered = match field.kind.as_str() {
+            "checkboxes" => {
+                let mut lines = Vec::new();
+                for option in &field.attributes.options {
+                    let checked = given.iter().any(|g| g == &option.label);
+                    if option.required && !checked {
+                        errors.push(format!("'{}' must be checked", option.label));
+                    }
+                    lines.push(format!("- [{}] {}", if checked { "X" } else { " " }, option.label));
+                }
+                lines.join("\n")
+            }
+            kind => {
+                if kind == "dropdown" {
+                    if given.len() > 1 && !field.attributes.multiple {
+                        errors.push(format!("'{}' takes a single option", label));
+                    }
+                    for g in &given {
+                        if !field.attributes.options.iter().any(|o| &o.label == g) {
+                            errors.push(format!("'{}' is not an option for '{}'", g, label));
+                        }
+                    }
+                }
+                if given.is_empty() {
+                    if field.validations.required {
+                        errors.push(format!("'{}' is required", label));
+                    }
+                    NO_RESPONSE.to_string()
+                } else {
+                    let text = given.join(", ");
+                    match field.attributes.render {
+                        Some(ref lang) => format!("```{}\n{}\n```", lang, text),
+                        None => text,
+                    }
+                }
+            }
+        };
+        sections.push(format!("### {}\n\n{}", label, rendered));
+    }
+
+    if !errors.is_empty() {
+        return Err(format!(
+            "Issue template '{}' is incomplete: {}",
+            template.name,
+            errors.join("; ")
+        ));
+    }
+    Ok(sections.join("\n\n"))
+}
+
+/// Prefix a title with the template's default unless it already carries it.
+pub(crate) fn apply_title_prefix(template: &IssueTemplate, title: &str) -> String {
+    match template.title.as_deref() {
+        Some(prefix) if !title.starts_with(prefix.trim_end()) => format!("{}{}", prefix, title),
+        _ => title.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUG_FORM: &str = r#"
+name: Bug report
+description: File a bug report
+title: "[Bug]: "
+labels: ["bug", "triage"]
+assignees: octocat
+body:
+  - type: markdown
+    attributes:
+      value: Thanks for taking the time!
+  - type: textarea
+    id: what-happened
+    attributes:
+      label: What happened?
+    validations:
+      required: true
+  - type: dropdown
+    id: version
+    attributes:
+      label: Version
+      options:
+        - "1.0"
+        - "2.0"
+  - type: textarea
+    id: logs
+    attributes:
+      label: Logs
+      render: shell
+  - type: checkboxes
+    id: terms
+    attributes:
+      label: Code of Conduct
+      options:
+        - label: I agree to follow the Code of Conduct
+          required: true
+"#;
+
+    #[test]
+    fn parses_markdown_template() {
+        let md = "---\nname: Feature request\nabout: Suggest an idea\ntitle: ''\nlabels: enhancement, ui\nassignees: ''\n---\n\n**Is your feature request related to a problem?**\n";
+        let t = parse_markdown_template("feature.md", md).unwrap();
+        assert_eq!(t.kind, IssueTemplateKind::Markdown);
+        assert_eq!(t.name, "Feature request");
+        assert_eq!(t.description.as_deref(), Some("Suggest an idea"));
+        assert_eq!(t.title, None);
+        assert_eq!(t.labels, vec!["enhancement", "ui"]);
+        assert!(t.assignees.is_empty());
+        assert!(t.body.unwrap().starts_with("**Is your feature request"));
+    }
+
+    #[test]
+    fn renders_issue_form() {
+        let t = parse_form_template("bug.yml", BUG_FORM).unwrap();
+        assert_eq!(t.labels, vec!["bug", "triage"]);
+        assert_eq!(t.assignees, vec!["octocat"]);
+        assert_eq!(t.fields.len(), 5);
+        assert!(t.fields[4].attributes.options[0].required);
+
+        let values: HashMap<String, serde_json::Value> = serde_json::from_value(serde_json::json!({
+            "what-happened": "It crashed",
+            "version": "2.0",
+            "logs": "panic at main.rs:1",
+            "terms": ["I agree to follow the Code of Conduct"]
+        }))
+        .unwrap();
+        let body = render_issue_form(&t, &values).unwrap();
+        assert_eq!(
+            body,
+            "### What happened?\n\nIt crashed\n\n### Version\n\n2.0\n\n### Logs\n\n```shell\npanic at main.rs:1\n```\n\n### Code of Conduct\n\n- [X] I agree to follow the Code of Conduct"
+        );
+        assert_eq!(apply_title_prefix(&t, "Crash on start"), "[Bug]: Crash on start");
+        assert_eq!(apply_title_prefix(&t, "[Bug]: Crash"), "[Bug]: Crash");
+    }
+
+    #[test]
+    fn validates_required_fields_and_options() {
+        let t = parse_form_template("bug.yml", BUG_FORM).unwrap();
+        let values: HashMap<String, serde_json::Value> =
+            serde_json::from_value(serde_json::json!({ "version": "3.0" })).unwrap();
+        let err = render_issue_form(&t, &values).unwrap_err();
+        assert!(err.contains("'What happened?' is required"), "{}", err);
+        assert!(err.contains("'3.0' is not an option for 'Version'"), "{}", err);
+        assert!(err.contains("must be checked"), "{}", err);
+
+        let values: HashMap<String, serde_json::Value> = serde_json::from_value(serde_json::json!({
+            "what-happened": "x",
+            "terms": ["I agree to follow the Code of Conduct"]
+        }))
+        .unwrap();
+        let body = render_issue_form(&t, &values).unwrap();
+        assert!(body.contains("### Version\n\n_No response_"));
+    }
+}
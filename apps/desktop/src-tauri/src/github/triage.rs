@@ -0,0 +1,478 @@
+//! AI-assisted triage of unlabeled issues.
+//!
+//! `triage_issues` proposes labels, a priority and likely duplicates for each
+//! open issue without labels; nothing is written until the reviewed proposals
+//! are passed to `apply_triage_proposals`. Duplicate candidates come from a
+//! local word-overlap pass over titles and bodies, which Claude then confirms
+//! or rejects.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    backend_for, run_streaming, spawn_claude_print, BulkLabelFailure, BulkLabelResult, GhPriority,
+    GitHubIssue, IssueEdit, LabelInfo,
+};
+
+const DEFAULT_TRIAGE_LIMIT: usize = 20;
+const MAX_TRIAGE_LIMIT: usize = 50;
+/// Open issues scanned for duplicates (and for unlabeled issues to triage).
+const OPEN_ISSUE_SCAN_LIMIT: u32 = 500;
+const TRIAGE_TIMEOUT_SECS: u64 = 300;
+/// Issue bodies are cut to this many characters in the prompt.
+const PROMPT_BODY_CHARS: usize = 1500;
+
+/// Minimum similarity for a pair to be offered as a duplicate candidate.
+const DUPLICATE_THRESHOLD: f64 = 0.35;
+const MAX_DUPLICATE_CANDIDATES: usize = 3;
+
+const PRIORITIES: [&str; 3] = ["high", "medium", "low"];
+
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "with", "that", "this", "from", "when", "not", "are", "was", "but",
+    "have", "has", "can", "should", "would", "will", "into", "there", "their", "what", "which",
+    "issue", "does", "doesn", "don", "it's", "its", "all", "any", "get", "use", "using",
+];
+
+// ─── Similarity ───────────────────────────────────────────────────────────────
+
+/// Crude suffix stripping so "crashes"/"crashed"/"crash" and
+/// "resizing"/"resize" compare equal.
+fn stem(word: &str) -> &str {
+    for suffix in ["ing", "ed", "es", "e", "s"] {
+        if let Some(stripped) = word.strip_suffix(suffix) {
+            if stripped.len() >= 3 {
+                return stripped;
+            }
+        }
+    }
+    word
+}
+
+fn tokens(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|w| w.chars().count() >= 3 && !STOPWORDS.contains(&w.as_str()))
+        .map(|w| stem(&w).to_string())
+        .collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / a.union(b).count() as f64
+}
+
+struct IssueTokens {
+    title: HashSet<String>,
+    all: HashSet<String>,
+}
+
+impl IssueTokens {
+    fn of(issue: &GitHubIssue) -> Self {
+        let title = tokens(&issue.title);
+        let mut all = tokens(issue.body.as_deref().unwrap_or(""));
+        all.extend(title.iter().cloned());
+        IssueTokens { title, all }
+    }
+
+    /// Titles carry most of the signal; bodies break ties and catch rewording.
+    fn similarity(&self, other: &IssueTokens) -> f64 {
+        0.6 * jaccard(&self.title, &other.title) + 0.4 * jaccard(&self.all, &other.all)
+    }
+}
+
+/// A possible duplicate of the issue being triaged.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateCandidate {
+    pub number: u32,
+    pub title: String,
+    /// Word-overlap similarity, 0..1.
+    pub score: f64,
+}
+
+/// The most similar other open issues to each of `targets`, best first.
+fn duplicate_candidates(
+    targets: &[&GitHubIssue],
+    open: &[GitHubIssue],
+) -> HashMap<u32, Vec<DuplicateCandidate>> {
+    let open_tokens: Vec<IssueTokens> = open.iter().map(IssueTokens::of).collect();
+    targets
+        .iter()
+        .map(|issue| {
+            let mine = IssueTokens::of(issue);
+            let mut found: Vec<DuplicateCandidate> = open
+                .iter()
+                .zip(&open_tokens)
+                .filter(|(other, _)| other.number != issue.number)
+                .map(|(other, t)| DuplicateCandidate {
+                    number: other.number,
+                    title: other.title.clone(),
+                    score: (mine.similarity(t) * 100.0).round() / 100.0,
+                })
+                .filter(|c| c.score >= DUPLICATE_THRESHOLD)
+                .collect();
+            found.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.number.cmp(&b.number)));
+            found.truncate(MAX_DUPLICATE_CANDIDATES);
+            (issue.number, found)
+        })
+        .collect()
+}
+
+// ─── Prompt ───────────────────────────────────────────────────────────────────
+
+fn build_prompt(
+    issues: &[&GitHubIssue],
+    labels: &[LabelInfo],
+    candidates: &HashMap<u32, Vec<DuplicateCandidate>>,
+) -> String {
+    let label_list: Vec<String> = labels
+        .iter()
+        .map(|l| match l.description {
+            Some(ref d) if !d.is_empty() => format!("- {}: {}", l.name, d),
+            _ => format!("- {}", l.name),
+        })
+        .collect();
+    let issue_list: Vec<serde_json::Value> = issues
+        .iter()
+        .map(|i| {
+            let body: String = i.body.as_deref().unwrap_or("").chars().take(PROMPT_BODY_CHARS).collect();
+            let dupes: Vec<serde_json::Value> = candidates
+                .get(&i.number)
+                .into_iter()
+                .flatten()
+                .map(|c| serde_json::json!({ "number": c.number, "title": c.title }))
+                .collect();
+            serde_json::json!({
+                "number": i.number,
+                "title": i.title,
+                "body": body,
+                "possibleDuplicates": dupes,
+            })
+        })
+        .collect();
+
+    format!(
+        "You are triaging GitHub issues. For each issue below, choose labels ONLY from the \
+         repository's existing labels, a priority (one of: high, medium, low), and, if the issue \
+         clearly duplicates one of its possibleDuplicates, that issue's number.\n\n\
+         Respond with ONLY a JSON array, no prose and no code fences, one object per issue:\n\
+         [{{\"number\": 1, \"labels\": [\"bug\"], \"priority\": \"medium\", \"duplicateOf\": null, \"reason\": \"one sentence\"}}]\n\n\
+         Repository labels:\n{}\n\nIssues:\n{}",
+        label_list.join("\n"),
+        serde_json::to_string_pretty(&issue_list).unwrap_or_default()
+    )
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClaudeTriage {
+    number: u32,
+    #[serde(default)]
+    labels: Vec<String>,
+    #[serde(default)]
+    priority: Option<String>,
+    #[serde(default)]
+    duplicate_of: Option<u32>,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// Pull the JSON array out of the CLI's reply, tolerating code fences or a
+/// stray sentence around it.
+fn parse_reply(reply: &str) -> Result<Vec<ClaudeTriage>, String> {
+    let start = reply.find('[');
+    let end = reply.rfind(']');
+    let json = match (start, end) {
+        (Some(s), Some(e)) if s < e => &reply[s..=e],
+        _ => return Err("Claude's triage reply contained no JSON array".to_string()),
+    };
+    serde_json::from_str(json).map_err(|e| format!("Couldn't parse Claude's triage reply: {}", e))
+}
+
+// ─── Proposals ────────────────────────────────────────────────────────────────
+
+/// A reviewable triage suggestion for one issue. Edit it freely before
+/// passing it to `apply_triage_proposals`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriageProposal {
+    pub number: u32,
+    #[serde(default)]
+    pub title: String,
+    /// Existing repo labels to add.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// "high", "medium" or "low".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    /// The repo label that carries `priority`, if the repo has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_label: Option<String>,
+    /// Issue this one duplicates, as confirmed by Claude.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<u32>,
+    /// Similar open issues from the local similarity pass.
+    #[serde(default)]
+    pub duplicates: Vec<DuplicateCandidate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Find a label like `priority: high`, `priority/high` or `high-priority`.
+fn priority_label(labels: &[LabelInfo], priority: &str) -> Option<String> {
+    labels
+        .iter()
+        .find(|l| {
+            let name = l.name.to_lowercase();
+            name.contains("priority") && tokens(&name).contains(stem(priority))
+        })
+        .map(|l| l.name.clone())
+}
+
+/// Merge Claude's reply with the local pass, dropping anything that doesn't
+/// refer to a real label, priority or open issue.
+fn build_proposals(
+    issues: &[&GitHubIssue],
+    open: &[GitHubIssue],
+    labels: &[LabelInfo],
+    mut candidates: HashMap<u32, Vec<DuplicateCandidate>>,
+    reply: Vec<ClaudeTriage>,
+) -> Vec<TriageProposal> {
+    let by_lower: HashMap<String, &str> = labels
+        .iter()
+        .map(|l| (l.name.to_lowercase(), l.name.as_str()))
+        .collect();
+    let open_numbers: HashSet<u32> = open.iter().map(|i| i.number).collect();
+    let mut replies: HashMap<u32, ClaudeTriage> = reply.into_iter().map(|r| (r.number, r)).collect();
+
+    issues
+        .iter()
+        .map(|issue| {
+            let duplicates = candidates.remove(&issue.number).unwrap_or_default();
+            let Some(r) = replies.remove(&issue.number) else {
+                return TriageProposal {
+                    number: issue.number,
+                    title: issue.title.clone(),
+                    labels: Vec::new(),
+                    priority: None,
+                    priority_label: None,
+                    duplicate_of: None,
+                    duplicates,
+                    reason: None,
+                };
+            };
+
+            let mut proposed: Vec<String> = Vec::new();
+            for name in r.labels {
+                if let Some(real) = by_lower.get(&name.to_lowercase()) {
+                    if !proposed.iter().any(|p| p == real) {
+                        proposed.push(real.to_string());
+                    }
+                }
+            }
+            let priority = r
+                .priority
+                .map(|p| p.to_lowercase())
+                .filter(|p| PRIORITIES.contains(&p.as_str()));
+            let duplicate_of = r
+                .duplicate_of
+                .filter(|n| *n != issue.number && open_numbers.contains(n));
+
+            TriageProposal {
+                number: issue.number,
+                title: issue.title.clone(),
+                labels: proposed,
+                priority_label: priority.as_deref().and_then(|p| priority_label(labels, p)),
+                priority,
+                duplicate_of,
+                duplicates,
+                reason: r.reason.filter(|s| !s.is_empty()),
+            }
+        })
+        .collect()
+}
+
+fn triage_blocking(
+    limit: Option<u32>,
+    project_path: Option<String>,
+) -> Result<Vec<TriageProposal>, String> {
+    let backend = backend_for(project_path.as_deref())?;
+    let open = backend.list_issues("open", OPEN_ISSUE_SCAN_LIMIT)?;
+    let limit = limit
+        .map(|l| l as usize)
+        .unwrap_or(DEFAULT_TRIAGE_LIMIT)
+        .clamp(1, MAX_TRIAGE_LIMIT);
+    let targets: Vec<&GitHubIssue> = open.iter().filter(|i| i.labels.is_empty()).take(limit).collect();
+    if targets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let labels = backend.list_labels()?;
+    let candidates = duplicate_candidates(&targets, &open);
+    let prompt = build_prompt(&targets, &labels, &candidates);
+
+    let child = spawn_claude_print(project_path.as_deref())?;
+    let reply = run_streaming(
+        child,
+        prompt,
+        Duration::from_secs(TRIAGE_TIMEOUT_SECS),
+        &AtomicBool::new(false),
+        |_| {},
+    )?;
+
+    Ok(build_proposals(&targets, &open, &labels, candidates, parse_reply(&reply)?))
+}
+
+/// Propose labels, a priority and likely duplicates for open issues that
+/// have no labels yet. Read-only; review the result, then pass the proposals
+/// you keep to `apply_triage_proposals`.
+/// - limit: Maximum issues to triage in one pass. Defaults to 20 (at most 50)
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub async fn triage_issues(
+    limit: Option<u32>,
+    project_path: Option<String>,
+) -> Result<Vec<TriageProposal>, String> {
+    tauri::async_runtime::spawn_blocking(move || triage_blocking(limit, project_path))
+        .await
+        .map_err(|e| format!("Triage task failed: {}", e))?
+}
+
+/// Apply reviewed triage proposals: add each proposal's labels and priority
+/// label, and for a confirmed duplicate add the repo's `duplicate` label (if
+/// it has one) and comment with the original. Issues are never closed.
+/// Runs at background priority; a failure on one issue doesn't stop the rest.
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn apply_triage_proposals(
+    proposals: Vec<TriageProposal>,
+    project_path: Option<String>,
+) -> Result<BulkLabelResult, String> {
+    let backend = backend_for(project_path.as_deref())?;
+    let duplicate_label = if proposals.iter().any(|p| p.duplicate_of.is_some()) {
+        backend
+            .list_labels()?
+            .into_iter()
+            .find(|l| l.name.eq_ignore_ascii_case("duplicate"))
+            .map(|l| l.name)
+    } else {
+        None
+    };
+
+    let mut result = BulkLabelResult::default();
+    let priority = GhPriority::Background;
+    for proposal in proposals {
+        let duplicate = proposal.duplicate_of.and(duplicate_label.as_ref());
+        let mut add_labels: Vec<String> = Vec::new();
+        for name in proposal.labels.iter().chain(&proposal.priority_label).chain(duplicate) {
+            if !add_labels.contains(name) {
+                add_labels.push(name.clone());
+            }
+        }
+
+        let applied = (|| {
+            if !add_labels.is_empty() {
+                backend.edit_issue(
+                    proposal.number,
+                    &IssueEdit {
+                        add_labels,
+                        ..Default::default()
+                    },
+                    priority,
+                )?;
+            }
+            if let Some(original) = proposal.duplicate_of {
+                backend.post_issue_comment(
+                    proposal.number,
+                    &format!("Possible duplicate of #{}", original),
+                    priority,
+                )?;
+            }
+            Ok::<(), String>(())
+        })();
+
+        match applied {
+            Ok(()) => result.updated.push(proposal.number),
+            Err(error) => {
+                log::warn!("triage: issue #{} failed: {}", proposal.number, error);
+                result.failed.push(BulkLabelFailure {
+                    number: proposal.number,
+                    error,
+                });
+            }
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(number: u32, title: &str, body: &str, labels: &[&str]) -> GitHubIssue {
+        serde_json::from_value(serde_json::json!({
+            "number": number,
+            "title": title,
+            "body": body,
+            "state": "OPEN",
+            "labels": labels.iter().map(|l| serde_json::json!({ "id": l, "name": l, "color": "ffffff" })).collect::<Vec<_>>(),
+            "url": format!("https://github.com/o/r/issues/{}", number),
+            "createdAt": "2026-01-01T00:00:00Z",
+            "updatedAt": "2026-01-01T00:00:00Z",
+        }))
+        .unwrap()
+    }
+
+    fn label(name: &str) -> LabelInfo {
+        LabelInfo {
+            name: name.to_string(),
+            color: "ffffff".to_string(),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn finds_similar_open_issues() {
+        let open = vec![
+            issue(1, "Terminal crashes when resizing window", "Resizing the window crashes the terminal pane", &[]),
+            issue(2, "Crash on terminal resize", "The terminal crashes after resizing the window", &["bug"]),
+            issue(3, "Add dark mode toggle", "Settings should offer a dark theme", &[]),
+        ];
+        let targets: Vec<&GitHubIssue> = vec![&open[0]];
+        let found = duplicate_candidates(&targets, &open);
+        let dupes = &found[&1];
+        assert_eq!(dupes.len(), 1);
+        assert_eq!(dupes[0].number, 2);
+        assert!(dupes[0].score >= DUPLICATE_THRESHOLD);
+    }
+
+    #[test]
+    fn validates_claude_reply_against_repo() {
+        let open = vec![issue(1, "A", "", &[]), issue(2, "B", "", &[])];
+        let targets: Vec<&GitHubIssue> = open.iter().collect();
+        let labels = vec![label("bug"), label("Priority: High"), label("docs")];
+        let reply = parse_reply(
+            "Here you go:\n```json\n[\
+             {\"number\": 1, \"labels\": [\"BUG\", \"made-up\"], \"priority\": \"High\", \"duplicateOf\": 2, \"reason\": \"same crash\"},\
+             {\"number\": 2, \"labels\": [\"docs\"], \"priority\": \"urgent\", \"duplicateOf\": 99}\
+             ]\n```",
+        )
+        .unwrap();
+
+        let proposals = build_proposals(&targets, &open, &labels, HashMap::new(), reply);
+        assert_eq!(proposals[0].labels, vec!["bug"]);
+        assert_eq!(proposals[0].priority.as_deref(), Some("high"));
+        assert_eq!(proposals[0].priority_label.as_deref(), Some("Priority: High"));
+        assert_eq!(proposals[0].duplicate_of, Some(2));
+        assert_eq!(proposals[1].priority, None);
+        assert_eq!(proposals[1].duplicate_of, None);
+
+        assert!(parse_reply("no json here").is_err());
+    }
+}
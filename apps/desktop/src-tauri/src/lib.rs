@@ -1,8 +1,12 @@
+mod claude_sessions;
+mod claude_tokens;
 mod claude_usage;
 mod commands;
 mod config;
+mod credentials;
 mod fs_utils;
 mod github;
+mod runner;
 pub mod state;
 mod state_transition;
 mod terminal;
@@ -15,6 +19,7 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
         .setup(|app| {
             // Initialize logging in debug mode
             if cfg!(debug_assertions) {
@@ -33,6 +38,15 @@ pub fn run() {
                 fs_utils::cleanup_stale_tmp_files(&cwd.join(".tiki"));
             }
 
+            // Let the gh request scheduler emit rate-limit-throttled events
+            github::init_gh_scheduler(app.handle().clone());
+
+            // Sample Claude plan usage for history and threshold alerts
+            claude_usage::start_usage_monitor(app.handle().clone());
+
+            // Kill terminal sessions left idle by a crashed or reloaded UI
+            terminal::start_reaper();
+
             // Start file watcher for .tiki directory
             let app_handle = app.handle().clone();
             std::thread::spawn(move || {
@@ -71,32 +85,100 @@ pub fn run() {
             config::read_tiki_config,
             config::save_tiki_config,
             state_transition::state_transition,
+            runner::start_claude_run,
+            runner::stop_claude_run,
+            runner::restart_claude_run,
+            runner::list_claude_runs,
             github::check_claude_cli,
             github::check_gh_auth,
             github::fetch_rate_limit_status,
             github::fetch_github_issues,
+            github::fetch_github_issues_bulk,
+            github::get_github_cache_status,
+            github::set_github_offline_mode,
+            github::clear_github_cache,
+            github::get_github_backend_status,
+            github::set_github_token,
             github::fetch_github_releases,
             github::fetch_github_release_url,
             github::fetch_github_labels,
+            github::create_github_label,
+            github::update_github_label,
+            github::delete_github_label,
+            github::bulk_edit_issue_labels,
+            github::sync_github_labels,
+            github::fetch_github_milestones,
+            github::create_github_milestone,
+            github::close_github_milestone,
+            github::sync_release_milestone,
+            github::add_issue_to_github_project,
             github::create_github_issue,
+            github::list_issue_templates,
             github::edit_github_issue,
             github::close_github_issue,
             github::fetch_github_issue_by_number,
             github::fetch_issue_comments,
             github::post_issue_comment,
+            github::list_enhancement_prompts,
             github::enhance_issue_description,
+            github::cancel_issue_enhancement,
+            github::triage_issues,
+            github::apply_triage_proposals,
             github::get_current_branch,
             github::list_git_branches,
+            github::git_status,
+            github::create_issue_branch,
+            github::checkout_branch,
+            github::create_worktree_for_issue,
+            github::list_worktrees,
+            github::remove_worktree,
             github::fetch_github_prs,
             github::fetch_github_pr_detail,
+            github::create_pull_request,
+            github::update_pull_request,
+            github::mark_ready_for_review,
+            github::merge_pull_request,
+            github::post_pr_review_comment,
+            github::submit_pr_review,
+            github::rerun_failed_checks,
+            github::wait_for_checks,
+            github::cancel_wait_for_checks,
             terminal::commands::create_terminal,
             terminal::commands::write_terminal,
+            terminal::commands::write_terminal_bytes,
             terminal::commands::resize_terminal,
+            terminal::commands::get_terminal_scrollback,
+            terminal::commands::list_terminals,
+            terminal::commands::attach_terminal,
+            terminal::commands::terminal_heartbeat,
+            terminal::commands::get_terminal_idle_timeout,
+            terminal::commands::set_terminal_idle_timeout,
+            terminal::commands::list_terminal_profiles,
+            terminal::commands::save_terminal_profile,
+            terminal::commands::delete_terminal_profile,
+            terminal::commands::get_terminal_triggers,
+            terminal::commands::set_terminal_triggers,
+            terminal::commands::start_recording,
+            terminal::commands::stop_recording,
+            terminal::commands::list_recordings,
+            terminal::commands::replay_recording,
+            terminal::commands::stop_replay,
+            terminal::commands::signal_terminal,
             terminal::commands::destroy_terminal,
+            claude_sessions::list_issue_sessions,
+            claude_sessions::read_session_transcript,
+            claude_tokens::get_issue_token_usage,
+            claude_tokens::get_release_token_usage,
             claude_usage::get_claude_usage,
             claude_usage::save_claude_session_key,
             claude_usage::has_claude_session_key,
             claude_usage::clear_claude_session_key,
+            claude_usage::get_usage_history,
+            claude_usage::get_usage_thresholds,
+            claude_usage::set_usage_thresholds,
+            claude_usage::estimate_yolo_run,
+            credentials::get_credential_store_status,
+            credentials::unlock_credential_store,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,427 @@
+//! Supervised Claude CLI runs for pipeline steps.
+//!
+//! A run is `claude "<step>"` (e.g. `/tiki:execute 42`) inside a PTY session
+//! from the `terminal` module, under the terminal ID `run:<work_id>`, so the
+//! frontend can show it like any other terminal tab. There is at most one run
+//! per work_id. When the process exits the run records its exit code and
+//! moves the work item along:
+//!
+//! - exit 0: nothing — the step updates state.json itself;
+//! - stopped via `stop_claude_run`, interrupted (SIGINT/SIGTERM exit codes),
+//!   or its terminal closed: `Paused`;
+//! - any other non-zero exit: `Failed`.
+//!
+//! Transitions that don't apply (no state entry, or illegal — e.g. the step
+//! already marked the work `Completed`) are logged and skipped.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use portable_pty::CommandBuilder;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::github::require_claude_cli;
+use crate::state::WorkStatus;
+use crate::state_transition::{state_transition, TransitionInput};
+use crate::terminal::commands::{terminal_log_dir, tiki_env};
+use crate::terminal::{get_terminal_manager, shutdown_session, start_output_reader, TerminalSession};
+
+/// Exit codes a shell reports for SIGINT and SIGTERM.
+const INTERRUPTED_EXIT_CODES: [i32; 2] = [130, 143];
+/// How long `restart_claude_run` waits for the old process to go away.
+const RESTART_WAIT: Duration = Duration::from_secs(5);
+/// Time a stopped run gets to exit after SIGTERM before it's killed; well
+/// under `RESTART_WAIT`.
+const STOP_GRACE: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    Running,
+    Succeeded,
+    Failed,
+    Stopped,
+}
+
+/// A supervised run, as returned by the run commands and emitted as
+/// `claude-run-status` whenever it changes.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeRun {
+    pub work_id: String,
+    /// Terminal session the run is attached to; pass to `write_terminal` etc.
+    pub terminal_id: String,
+    pub step: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_path: Option<String>,
+    pub status: RunStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    pub started_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<String>,
+    /// Work status the run's exit moved the item to, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transitioned_to: Option<WorkStatus>,
+    #[serde(skip)]
+    stop_requested: bool,
+    /// Set by `restart_claude_run`: the exit is expected, so no transition.
+    #[serde(skip)]
+    restarting: bool,
+}
+
+fn runs() -> &'static Mutex<HashMap<String, ClaudeRun>> {
+    static RUNS: OnceLock<Mutex<HashMap<String, ClaudeRun>>> = OnceLock::new();
+    RUNS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn terminal_id(work_id: &str) -> String {
+    format!("run:{}", work_id)
+}
+
+/// Final status of a run and the work status its exit implies. `None` exit
+/// code means the terminal was torn down before the process exited.
+fn classify_exit(exit_code: Option<i32>, stop_requested: bool) -> (RunStatus, Option<WorkStatus>) {
+    match exit_code {
+        _ if stop_requested => (RunStatus::Stopped, Some(WorkStatus::Paused)),
+        None => (RunStatus::Stopped, Some(WorkStatus::Paused)),
+        Some(0) => (RunStatus::Succeeded, None),
+        Some(code) if INTERRUPTED_EXIT_CODES.contains(&code) => {
+            (RunStatus::Stopped, Some(WorkStatus::Paused))
+        }
+        Some(_) => (RunStatus::Failed, Some(WorkStatus::Failed)),
+    }
+}
+
+fn tiki_path_for(project_path: Option<&str>) -> Option<String> {
+    project_path.map(|p| {
+        std::path::Path::new(p)
+            .join(".tiki")
+            .to_string_lossy()
+            .into_owned()
+    })
+}
+
+fn transition_after_exit(run: &ClaudeRun, to: WorkStatus) -> Option<WorkStatus> {
+    let input = TransitionInput {
+        work_id: run.work_id.clone(),
+        to_status: to.clone(),
+        to_step: None,
+        phase: None,
+        parallel_execution: None,
+        parent_release: None,
+        issue: None,
+        release: None,
+        tiki_path: tiki_path_for(run.project_path.as_deref()),
+    };
+    match state_transition(input) {
+        Ok(_) => Some(to),
+        Err(e) => {
+            log::warn!("Run for {} exited; not moving it to {:?}: {}", run.work_id, to, e);
+            None
+        }
+    }
+}
+
+fn emit_run(app: &AppHandle, run: &ClaudeRun) {
+    if let Err(e) = app.emit("claude-run-status", run.clone()) {
+        log::error!("Failed to emit claude-run-status event: {}", e);
+    }
+}
+
+fn on_run_exit(app: &AppHandle, work_id: &str, exit_code: Option<i32>) {
+    let (mut run, to) = {
+        let Ok(mut map) = runs().lock() else {
+            return;
+        };
+        let Some(run) = map.get_mut(work_id) else {
+            return;
+        };
+        let (status, to) = classify_exit(exit_code, run.stop_requested);
+        run.status = status;
+        run.exit_code = exit_code;
+        run.finished_at = Some(chrono::Utc::now().to_rfc3339());
+        let to = if run.restarting { None } else { to };
+        (run.clone(), to)
+    };
+    log::info!("Run for {} exited ({:?}, exit code {:?})", work_id, run.status, exit_code);
+
+    if let Some(to) = to {
+        run.transitioned_to = transition_after_exit(&run, to);
+        if let Ok(mut map) = runs().lock() {
+            if let Some(stored) = map.get_mut(work_id) {
+                stored.transitioned_to = run.transitioned_to.clone();
+            }
+        }
+    }
+    emit_run(app, &run);
+}
+
+fn spawn_run(
+    app: AppHandle,
+    work_id: String,
+    step: String,
+    project_path: Option<String>,
+) -> Result<ClaudeRun, String> {
+    if !work_id.starts_with("issue:") && !work_id.starts_with("release:") {
+        return Err(format!(
+            "invalid work_id '{}': must start with 'issue:' or 'release:'",
+            work_id
+        ));
+    }
+    if step.trim().is_empty() {
+        return Err("Step cannot be empty".to_string());
+    }
+
+    let argv = require_claude_cli()?.argv();
+    let mut cmd = CommandBuilder::from_argv(argv.iter().map(Into::into).collect());
+    cmd.arg(&step);
+    if let Some(ref p) = project_path {
+        cmd.cwd(p);
+    }
+    for (key, value) in tiki_env(Some(&work_id), None, project_path.as_deref()) {
+        cmd.env(key, value);
+    }
+
+    // Record the run before spawning so the exit hook always finds it, even
+    // for a process that dies immediately. The runs and terminal-manager
+    // locks are never held together: the exit hook can fire with the
+    // manager locked (see `ExitHook`).
+    let terminal_id = terminal_id(&work_id);
+    let run = ClaudeRun {
+        work_id: work_id.clone(),
+        terminal_id: terminal_id.clone(),
+        step,
+        project_path,
+        status: RunStatus::Running,
+        exit_code: None,
+        started_at: chrono::Utc::now().to_rfc3339(),
+        finished_at: None,
+        transitioned_to: None,
+        stop_requested: false,
+        restarting: false,
+    };
+    let previous = {
+        let mut map = runs().lock().map_err(|e| format!("Lock error: {}", e))?;
+        if map.get(&work_id).is_some_and(|r| r.status == RunStatus::Running) {
+            return Err(format!("A run for {} is already in progress", work_id));
+        }
+        map.insert(work_id.clone(), run.clone())
+    };
+
+    let spawned = attach_session(&app, &work_id, &terminal_id, run.project_path.as_deref(), cmd);
+    if let Err(e) = spawned {
+        if let Ok(mut map) = runs().lock() {
+            match previous {
+                Some(p) => map.insert(work_id, p),
+                None => map.remove(&work_id),
+            };
+        }
+        return Err(e);
+    }
+
+    log::info!("Started run for {}: {}", run.work_id, run.step);
+    emit_run(&app, &run);
+    Ok(run)
+}
+
+/// Spawn `cmd` in a PTY session under `terminal_id`, wired to report its
+/// exit to the run for `work_id`, and start streaming its output.
+fn attach_session(
+    app: &AppHandle,
+    work_id: &str,
+    terminal_id: &str,
+    project_path: Option<&str>,
+    cmd: CommandBuilder,
+) -> Result<(), String> {
+    {
+        let manager = get_terminal_manager();
+        let mut guard = manager.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if guard.get_session(terminal_id).is_some() {
+            return Err(format!("Terminal session '{}' already exists", terminal_id));
+        }
+        let mut session = TerminalSession::spawn(cmd)?;
+        session.set_work_id(work_id);
+        // Claude runs are long and unattended: always keep a log.
+        if let Err(e) = terminal_log_dir(project_path).and_then(|dir| session.enable_log(&dir, terminal_id)) {
+            log::warn!("Run {} will have no terminal log: {}", work_id, e);
+        }
+        let hook_app = app.clone();
+        let hook_work_id = work_id.to_string();
+        session.set_exit_hook(Box::new(move |exit_code| {
+            on_run_exit(&hook_app, &hook_work_id, exit_code)
+        }));
+        guard.add_session(terminal_id.to_string(), session)?;
+    }
+
+    if let Err(e) = start_output_reader(terminal_id.to_string(), app.clone()) {
+        // Nobody will ever see this process's output: tear it down quietly.
+        let manager = get_terminal_manager();
+        let mut guard = manager.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if let Some(mut session) = guard.remove_session(terminal_id) {
+            session.take_exit_hook();
+            let _ = session.kill();
+        }
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Flag the run and shut its process group down (SIGTERM, then SIGKILL after
+/// `STOP_GRACE`), node children included. The exit is then reported through
+/// the terminal's exit hook like any other.
+fn kill_run(work_id: &str, restarting: bool) -> Result<ClaudeRun, String> {
+    let run = {
+        let mut map = runs().lock().map_err(|e| format!("Lock error: {}", e))?;
+        let run = map
+            .get_mut(work_id)
+            .filter(|r| r.status == RunStatus::Running)
+            .ok_or_else(|| format!("No run in progress for {}", work_id))?;
+        run.stop_requested = true;
+        run.restarting = restarting;
+        run.clone()
+    };
+
+    {
+        let manager = get_terminal_manager();
+        let guard = manager.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if guard.get_session(&run.terminal_id).is_none() {
+            return Err(format!("Terminal for {} has already exited", work_id));
+        }
+    }
+    let terminal_id = run.terminal_id.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = shutdown_session(&terminal_id, STOP_GRACE) {
+            log::warn!("Failed to stop terminal '{}': {}", terminal_id, e);
+        }
+    });
+    Ok(run)
+}
+
+/// Start a pipeline step for a work item in a supervised Claude CLI process.
+/// The run's output streams as `terminal-output` events for its `terminalId`.
+/// - work_id: e.g. "issue:42" or "release:v1.2"
+/// - step: What to pass to claude, e.g. "/tiki:execute 42"
+/// - project_path: Optional path to the project directory. If not provided, uses current working directory.
+#[tauri::command]
+pub fn start_claude_run(
+    app: AppHandle,
+    work_id: String,
+    step: String,
+    project_path: Option<String>,
+) -> Result<ClaudeRun, String> {
+    spawn_run(app, work_id, step, project_path)
+}
+
+/// Stop a work item's run. Its exit moves the item to Paused.
+#[tauri::command]
+pub fn stop_claude_run(work_id: String) -> Result<ClaudeRun, String> {
+    kill_run(&work_id, false)
+}
+
+/// Re-run a work item's last step. A run still in progress is stopped first
+/// (without the Paused transition).
+#[tauri::command]
+pub fn restart_claude_run(app: AppHandle, work_id: String) -> Result<ClaudeRun, String> {
+    let previous = runs()
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .get(&work_id)
+        .cloned()
+        .ok_or_else(|| format!("No previous run for {}", work_id))?;
+
+    if previous.status == RunStatus::Running {
+        kill_run(&work_id, true)?;
+        let deadline = Instant::now() + RESTART_WAIT;
+        loop {
+            let still_running = runs()
+                .lock()
+                .map_err(|e| format!("Lock error: {}", e))?
+                .get(&work_id)
+                .is_some_and(|r| r.status == RunStatus::Running);
+            if !still_running {
+                break;
+            }
+            if Instant::now() >= deadline {
+                return Err(format!("Run for {} didn't stop in time", work_id));
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    spawn_run(app, work_id, previous.step, previous.project_path)
+}
+
+/// All runs started this session, most recent first.
+#[tauri::command]
+pub fn list_claude_runs() -> Result<Vec<ClaudeRun>, String> {
+    let mut list: Vec<ClaudeRun> = runs()
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .values()
+        .cloned()
+        .collect();
+    list.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{TikiState, WorkContext};
+
+    #[test]
+    fn classifies_exits() {
+        assert_eq!(classify_exit(Some(0), false), (RunStatus::Succeeded, None));
+        assert_eq!(classify_exit(Some(1), false), (RunStatus::Failed, Some(WorkStatus::Failed)));
+        assert_eq!(classify_exit(Some(130), false), (RunStatus::Stopped, Some(WorkStatus::Paused)));
+        assert_eq!(classify_exit(None, false), (RunStatus::Stopped, Some(WorkStatus::Paused)));
+        // A requested stop wins even if the process managed a clean exit.
+        assert_eq!(classify_exit(Some(0), true), (RunStatus::Stopped, Some(WorkStatus::Paused)));
+    }
+
+    #[test]
+    fn failed_run_transitions_existing_work_only() {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let project = std::env::temp_dir().join(format!("tiki-runner-{}", nanos));
+        let tiki = project.join(".tiki");
+        std::fs::create_dir_all(&tiki).unwrap();
+        std::fs::write(
+            tiki.join("state.json"),
+            r#"{"schemaVersion":1,"activeWork":{"issue:42":{"type":"issue","issue":{"number":42},"status":"executing","createdAt":"2026-01-01T00:00:00Z"}}}"#,
+        )
+        .unwrap();
+
+        let mut run = ClaudeRun {
+            work_id: "issue:42".to_string(),
+            terminal_id: terminal_id("issue:42"),
+            step: "/tiki:execute 42".to_string(),
+            project_path: Some(project.to_string_lossy().into_owned()),
+            status: RunStatus::Failed,
+            exit_code: Some(1),
+            started_at: String::new(),
+            finished_at: None,
+            transitioned_to: None,
+            stop_requested: false,
+            restarting: false,
+        };
+        assert_eq!(transition_after_exit(&run, WorkStatus::Failed), Some(WorkStatus::Failed));
+        let state: TikiState =
+            serde_json::from_str(&std::fs::read_to_string(tiki.join("state.json")).unwrap()).unwrap();
+        match &state.active_work["issue:42"] {
+            WorkContext::Issue(ctx) => assert_eq!(ctx.status, WorkStatus::Failed),
+            other => panic!("unexpected entry: {:?}", other),
+        }
+
+        // No state entry to move: skipped rather than created.
+        run.work_id = "issue:7".to_string();
+        assert_eq!(transition_after_exit(&run, WorkStatus::Failed), None);
+
+        std::fs::remove_dir_all(&project).unwrap();
+    }
+}
@@ -53,6 +53,9 @@ pub struct IssueContext {
     pub yolo: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub commit: Option<String>,
+    /// Number of the pull request opened for this issue (set by `create_pull_request`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pull_request: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent_release: Option<String>,
 }
@@ -109,6 +112,8 @@ struct RawIssueContext {
     #[serde(default)]
     commit: Option<String>,
     #[serde(default)]
+    pull_request: Option<u32>,
+    #[serde(default)]
     parent_release: Option<String>,
     #[serde(default)]
     pipeline_history: Option<Vec<PipelineStepRecord>>,
@@ -293,6 +298,7 @@ impl<'de> Deserialize<'de> for IssueContext {
             audit_passed: raw.audit_passed,
             yolo: raw.yolo,
             commit: raw.commit,
+            pull_request: raw.pull_request,
             parent_release: raw.parent_release,
         })
     }
@@ -544,6 +550,10 @@ pub struct SuccessCriterion {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub category: Option<String>,
     pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified_at: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -200,6 +200,7 @@ pub fn apply_transition(state: &mut TikiState, input: TransitionInput) -> Result
                     audit_passed: None,
                     yolo: None,
                     commit: None,
+                    pull_request: None,
                     parent_release: input.parent_release.clone(),
                 }
             }
@@ -350,6 +351,7 @@ mod tests {
             audit_passed: None,
             yolo: None,
             commit: None,
+            pull_request: None,
             parent_release: parent.map(|s| s.to_string()),
         };
         state
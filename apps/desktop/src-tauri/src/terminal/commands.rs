@@ -1,13 +1,83 @@
-use super::{get_terminal_manager, start_output_reader};
+use super::config::{load_config, resolve_spec, save_config, TerminalProfile};
+use super::pty::{shutdown_session, ShellSpec, TerminalSignal, DEFAULT_COLS, DEFAULT_ROWS};
+use super::recording::{self, RecordingInfo};
+use super::triggers::{self, OutputTrigger};
+use super::{get_terminal_manager, start_output_reader, TerminalInfo, TerminalScrollback};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tauri::AppHandle;
 
+/// Where session logs go: `.tiki/terminal-logs/` under the terminal's working
+/// directory, or the current directory.
+pub(crate) fn terminal_log_dir(cwd: Option<&str>) -> Result<PathBuf, String> {
+    let base = match cwd {
+        Some(dir) => PathBuf::from(dir),
+        None => std::env::current_dir().map_err(|e| e.to_string())?,
+    };
+    Ok(base.join(".tiki").join("terminal-logs"))
+}
+
+/// `TIKI_WORK_ID` and `TIKI_PATH` for a session, so scripts it runs know
+/// their context. `TIKI_PATH` defaults to `<cwd>/.tiki` when that exists.
+pub(crate) fn tiki_env(
+    work_id: Option<&str>,
+    tiki_path: Option<&str>,
+    cwd: Option<&str>,
+) -> BTreeMap<String, String> {
+    let mut env = BTreeMap::new();
+    if let Some(work_id) = work_id {
+        env.insert("TIKI_WORK_ID".to_string(), work_id.to_string());
+    }
+    let tiki_path = match (tiki_path, cwd) {
+        (Some(path), _) => Some(PathBuf::from(path)),
+        (None, Some(cwd)) => Some(Path::new(cwd).join(".tiki")).filter(|p| p.is_dir()),
+        (None, None) => None,
+    };
+    if let Some(path) = tiki_path {
+        env.insert("TIKI_PATH".to_string(), path.to_string_lossy().into_owned());
+    }
+    env
+}
+
+/// Extra options for `create_terminal`
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTerminalOptions {
+    /// Name of a saved profile supplying shell, args, env and cwd defaults
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Environment overrides, applied over the profile's
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    pub rows: Option<u16>,
+    pub cols: Option<u16>,
+    /// Run the session belongs to; exported as `TIKI_WORK_ID`
+    pub work_id: Option<String>,
+    /// Exported as `TIKI_PATH`; defaults to `<cwd>/.tiki`
+    pub tiki_path: Option<String>,
+    /// Emit output as base64 `terminal-output-bytes` events carrying the
+    /// bytes exactly as read, instead of decoded `terminal-output` text
+    #[serde(default)]
+    pub binary_output: bool,
+}
+
 /// Create a new terminal session
+/// - log_output: Also write output to `.tiki/terminal-logs/<id>.log` (and an
+///   ANSI-stripped `<id>.plain.log`) under `cwd`.
+/// - options: Profile, arguments, environment, initial size and Tiki context.
 #[tauri::command]
 pub fn create_terminal(
     app: AppHandle,
     id: String,
     shell: Option<String>,
     cwd: Option<String>,
+    log_output: Option<bool>,
+    options: Option<CreateTerminalOptions>,
 ) -> Result<(), String> {
     log::info!(
         "create_terminal called: id='{}', shell={:?}, cwd={:?}",
@@ -21,11 +91,55 @@ pub fn create_terminal(
         return Err("Terminal ID cannot be empty".to_string());
     }
 
+    let options = options.unwrap_or_default();
+    let config = load_config();
+    let profile = match options.profile.as_deref() {
+        Some(name) => Some(
+            config
+                .profile(name)
+                .ok_or_else(|| format!("Terminal profile '{}' not found", name))?,
+        ),
+        None => None,
+    };
+    let mut spec = resolve_spec(
+        profile,
+        ShellSpec {
+            shell,
+            args: options.args,
+            env: options.env,
+            cwd,
+        },
+    );
+    let context = tiki_env(
+        options.work_id.as_deref(),
+        options.tiki_path.as_deref(),
+        spec.cwd.as_deref(),
+    );
+    for (key, value) in context {
+        spec.env.entry(key).or_insert(value);
+    }
+    let rows = options.rows.filter(|&r| r > 0).unwrap_or(DEFAULT_ROWS);
+    let cols = options.cols.filter(|&c| c > 0).unwrap_or(DEFAULT_COLS);
+
     // First create the session
     {
         let manager = get_terminal_manager();
         let mut guard = manager.lock().map_err(|e| format!("Lock error: {}", e))?;
-        guard.create_session(id.clone(), shell, cwd)?;
+        let log_dir = match log_output {
+            Some(true) => Some(terminal_log_dir(spec.cwd.as_deref())?),
+            _ => None,
+        };
+        guard.create_session(id.clone(), spec, rows, cols)?;
+        let session = guard.get_session_mut(&id).ok_or("Terminal session vanished")?;
+        if let Some(work_id) = options.work_id.as_deref() {
+            session.set_work_id(work_id);
+        }
+        session.set_binary_output(options.binary_output);
+        if let Some(dir) = log_dir {
+            if let Err(e) = session.enable_log(&dir, &id) {
+                log::warn!("Terminal '{}' will run without a log: {}", id, e);
+            }
+        }
     }
 
     // Then start the output reader thread
@@ -51,6 +165,24 @@ pub fn write_terminal(id: String, data: String) -> Result<(), String> {
     session.write(&data)
 }
 
+/// Write raw bytes, base64 encoded, to a terminal session
+#[tauri::command]
+pub fn write_terminal_bytes(id: String, data: String) -> Result<(), String> {
+    if id.is_empty() {
+        return Err("Terminal ID cannot be empty".to_string());
+    }
+    let bytes = BASE64
+        .decode(data.as_bytes())
+        .map_err(|e| format!("Invalid base64 terminal input: {}", e))?;
+
+    let manager = get_terminal_manager();
+    let mut guard = manager.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let session = guard
+        .get_session_mut(&id)
+        .ok_or_else(|| format!("Terminal session '{}' not found. It may have been destroyed or exited.", id))?;
+    session.write_bytes(&bytes)
+}
+
 /// Resize a terminal session
 #[tauri::command]
 pub fn resize_terminal(id: String, rows: u16, cols: u16) -> Result<(), String> {
@@ -78,9 +210,212 @@ pub fn resize_terminal(id: String, rows: u16, cols: u16) -> Result<(), String> {
     Ok(())
 }
 
-/// Destroy a terminal session
+/// Recent output of a live or recently exited terminal session, for replay
+/// after the UI reloads
+#[tauri::command]
+pub fn get_terminal_scrollback(id: String) -> Result<TerminalScrollback, String> {
+    let manager = get_terminal_manager();
+    let guard = manager.lock().map_err(|e| format!("Lock error: {}", e))?;
+    guard
+        .scrollback(&id)
+        .ok_or_else(|| format!("Terminal session '{}' not found", id))
+}
+
+/// Live and recently exited terminal sessions
+#[tauri::command]
+pub fn list_terminals() -> Result<Vec<TerminalInfo>, String> {
+    let manager = get_terminal_manager();
+    let guard = manager.lock().map_err(|e| format!("Lock error: {}", e))?;
+    Ok(guard.list())
+}
+
+/// Reattach to an existing session, e.g. after the UI reloads: restarts its
+/// output reader if it isn't running and returns the scrollback to replay
+#[tauri::command]
+pub fn attach_terminal(app: AppHandle, id: String) -> Result<TerminalScrollback, String> {
+    log::info!("attach_terminal called: id='{}'", id);
+
+    let restart = {
+        let manager = get_terminal_manager();
+        let guard = manager.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let session = guard
+            .get_session(&id)
+            .ok_or_else(|| format!("Terminal session '{}' not found. It may have been destroyed or exited.", id))?;
+        session.touch();
+        !session.reader_active()
+    };
+    if restart {
+        start_output_reader(id.clone(), app)?;
+    }
+
+    let manager = get_terminal_manager();
+    let guard = manager.lock().map_err(|e| format!("Lock error: {}", e))?;
+    guard
+        .scrollback(&id)
+        .ok_or_else(|| format!("Terminal session '{}' not found", id))
+}
+
+/// Mark sessions as still in use by the UI so the idle reaper leaves them
+/// alone. Unknown IDs are ignored.
+#[tauri::command]
+pub fn terminal_heartbeat(ids: Vec<String>) -> Result<(), String> {
+    let manager = get_terminal_manager();
+    let guard = manager.lock().map_err(|e| format!("Lock error: {}", e))?;
+    for id in &ids {
+        if let Some(session) = guard.get_session(id) {
+            session.touch();
+        }
+    }
+    Ok(())
+}
+
+/// Minutes of inactivity before an unsupervised session is reaped (0 = never)
+#[tauri::command]
+pub fn get_terminal_idle_timeout() -> u64 {
+    load_config().idle_timeout_minutes()
+}
+
+#[tauri::command]
+pub fn set_terminal_idle_timeout(minutes: u64) -> Result<(), String> {
+    let mut config = load_config();
+    config.idle_timeout_minutes = Some(minutes);
+    save_config(&config)
+}
+
+/// Saved terminal profiles
+#[tauri::command]
+pub fn list_terminal_profiles() -> Vec<TerminalProfile> {
+    load_config().profiles
+}
+
+/// Add a profile, or replace the one with the same name
+#[tauri::command]
+pub fn save_terminal_profile(profile: TerminalProfile) -> Result<(), String> {
+    if profile.name.trim().is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    let mut config = load_config();
+    match config.profiles.iter_mut().find(|p| p.name == profile.name) {
+        Some(existing) => *existing = profile,
+        None => config.profiles.push(profile),
+    }
+    save_config(&config)
+}
+
+#[tauri::command]
+pub fn delete_terminal_profile(name: String) -> Result<(), String> {
+    let mut config = load_config();
+    let before = config.profiles.len();
+    config.profiles.retain(|p| p.name != name);
+    if config.profiles.len() == before {
+        return Err(format!("Terminal profile '{}' not found", name));
+    }
+    save_config(&config)
+}
+
+/// Output triggers watched on every session
+#[tauri::command]
+pub fn get_terminal_triggers() -> Vec<OutputTrigger> {
+    triggers::get_triggers()
+}
+
+/// Replace the output triggers; applies to running sessions too
+#[tauri::command]
+pub fn set_terminal_triggers(triggers: Vec<OutputTrigger>) -> Result<(), String> {
+    triggers::set_triggers(triggers)
+}
+
+/// Start recording a session to asciicast v2 under `.tiki/recordings/`,
+/// filed under `issue-N/` for `issue` or the issue the session belongs to.
+/// Returns the recording's path.
 #[tauri::command]
-pub fn destroy_terminal(id: String) -> Result<(), String> {
+pub fn start_recording(id: String, issue: Option<u32>) -> Result<String, String> {
+    let manager = get_terminal_manager();
+    let guard = manager.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let session = guard
+        .get_session(&id)
+        .ok_or_else(|| format!("Terminal session '{}' not found", id))?;
+    let tiki_dir = session
+        .tiki_dir()
+        .ok_or("Terminal has no working directory to store recordings in")?;
+    let issue = issue.or_else(|| session.issue_number());
+    let path = recording::recording_path(&tiki_dir, issue, &id);
+    session.start_recording(&path, issue, &id)?;
+    log::info!("Recording terminal '{}' to {}", id, path.display());
+    Ok(path.to_string_lossy().into_owned())
+}
+
+#[tauri::command]
+pub fn stop_recording(id: String) -> Result<RecordingInfo, String> {
+    let manager = get_terminal_manager();
+    let guard = manager.lock().map_err(|e| format!("Lock error: {}", e))?;
+    guard
+        .get_session(&id)
+        .ok_or_else(|| format!("Terminal session '{}' not found", id))?
+        .stop_recording()
+}
+
+/// Recordings in a project, newest first; only `issue`'s if given
+#[tauri::command]
+pub fn list_recordings(project_path: String, issue: Option<u32>) -> Vec<RecordingInfo> {
+    recording::list_recordings(&Path::new(&project_path).join(".tiki"), issue)
+}
+
+/// Play a recording back as `terminal-output` events under `id`, which must
+/// not be a live session. `speed` defaults to 1 and is clamped to 0.1-100;
+/// idle gaps are capped at `max_idle` seconds (default 2).
+#[tauri::command]
+pub fn replay_recording(
+    app: AppHandle,
+    id: String,
+    path: String,
+    speed: Option<f64>,
+    max_idle: Option<f64>,
+) -> Result<(), String> {
+    {
+        let manager = get_terminal_manager();
+        let guard = manager.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if guard.get_session(&id).is_some() {
+            return Err(format!("Terminal session '{}' is live; replay into a new ID", id));
+        }
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let cast = recording::parse_cast(&content)?;
+    let speed = speed.filter(|s| *s > 0.0).unwrap_or(1.0);
+    let max_idle = max_idle.filter(|m| *m >= 0.0).unwrap_or(2.0);
+    recording::start_replay(app, id, cast, speed, max_idle)
+}
+
+#[tauri::command]
+pub fn stop_replay(id: String) -> Result<(), String> {
+    if recording::stop_replay(&id) {
+        Ok(())
+    } else {
+        Err(format!("No replay running for '{}'", id))
+    }
+}
+
+/// Send SIGHUP, SIGINT, SIGTERM or SIGKILL to a session's processes.
+/// SIGINT reaches the foreground job, like Ctrl-C.
+#[tauri::command]
+pub fn signal_terminal(id: String, signal: TerminalSignal) -> Result<(), String> {
+    log::info!("signal_terminal called: id='{}', signal={:?}", id, signal);
+
+    let manager = get_terminal_manager();
+    let mut guard = manager.lock().map_err(|e| format!("Lock error: {}", e))?;
+    guard
+        .get_session_mut(&id)
+        .ok_or_else(|| format!("Terminal session '{}' not found. It may have been destroyed or exited.", id))?
+        .signal(signal)
+}
+
+/// Time `destroy_terminal` gives a process to exit after SIGTERM
+const DEFAULT_DESTROY_GRACE_MS: u64 = 3000;
+
+/// Destroy a terminal session: SIGHUP/SIGTERM, then SIGKILL if it's still
+/// running after `grace_ms`
+#[tauri::command]
+pub async fn destroy_terminal(id: String, grace_ms: Option<u64>) -> Result<(), String> {
     log::info!("destroy_terminal called: id='{}'", id);
 
     // Validate id
@@ -88,11 +423,11 @@ pub fn destroy_terminal(id: String) -> Result<(), String> {
         return Err("Terminal ID cannot be empty".to_string());
     }
 
-    let manager = get_terminal_manager();
-    let mut guard = manager.lock().map_err(|e| format!("Lock error: {}", e))?;
-    guard
-        .remove_session(&id)
-        .ok_or_else(|| format!("Terminal session '{}' not found. It may have already been destroyed or exited.", id))?;
+    let grace = Duration::from_millis(grace_ms.unwrap_or(DEFAULT_DESTROY_GRACE_MS));
+    let task_id = id.clone();
+    tauri::async_runtime::spawn_blocking(move || shutdown_session(&task_id, grace))
+        .await
+        .map_err(|e| format!("Shutdown task failed: {}", e))??;
 
     log::info!("Terminal '{}' destroyed", id);
     Ok(())
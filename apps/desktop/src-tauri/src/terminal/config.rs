@@ -0,0 +1,132 @@
+use super::pty::ShellSpec;
+use super::triggers::OutputTrigger;
+use crate::fs_utils;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Idle sessions are reaped after this long unless configured otherwise.
+pub const DEFAULT_IDLE_TIMEOUT_MINUTES: u64 = 60;
+
+/// Backend terminal settings, stored in config_dir/tiki-desktop/terminal.json
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalConfig {
+    /// Minutes without output, input or a UI heartbeat before an unsupervised
+    /// session is killed; 0 disables reaping
+    #[serde(default)]
+    pub idle_timeout_minutes: Option<u64>,
+    #[serde(default)]
+    pub profiles: Vec<TerminalProfile>,
+    /// Output triggers; the built-in defaults until first saved
+    #[serde(default)]
+    pub triggers: Option<Vec<OutputTrigger>>,
+}
+
+/// Named template for new sessions, e.g. "node REPL" or "docker exec"
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalProfile {
+    pub name: String,
+    /// Program to run; the default shell if unset
+    #[serde(default)]
+    pub shell: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub cwd: Option<String>,
+}
+
+impl TerminalConfig {
+    pub fn idle_timeout_minutes(&self) -> u64 {
+        self.idle_timeout_minutes.unwrap_or(DEFAULT_IDLE_TIMEOUT_MINUTES)
+    }
+
+    pub fn profile(&self, name: &str) -> Option<&TerminalProfile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+}
+
+/// Combine a profile with the values given when creating a session. Explicit
+/// values win; args go with the shell they were given for, and `env` is
+/// merged key by key.
+pub fn resolve_spec(profile: Option<&TerminalProfile>, request: ShellSpec) -> ShellSpec {
+    let Some(profile) = profile else {
+        return request;
+    };
+    let (shell, args) = match request.shell {
+        Some(shell) => (Some(shell), request.args),
+        None if !request.args.is_empty() => (profile.shell.clone(), request.args),
+        None => (profile.shell.clone(), profile.args.clone()),
+    };
+    let mut env = profile.env.clone();
+    env.extend(request.env);
+    ShellSpec {
+        shell,
+        args,
+        env,
+        cwd: request.cwd.or_else(|| profile.cwd.clone()),
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("tiki-desktop").join("terminal.json"))
+}
+
+pub fn load_config() -> TerminalConfig {
+    config_path()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_config(config: &TerminalConfig) -> Result<(), String> {
+    let path = config_path().ok_or("No config directory")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    fs_utils::atomic_write(&path, &json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_values_override_the_profile() {
+        let profile = TerminalProfile {
+            name: "docker exec".to_string(),
+            shell: Some("docker".to_string()),
+            args: vec!["exec".to_string(), "-it".to_string(), "app".to_string(), "sh".to_string()],
+            env: BTreeMap::from([
+                ("A".to_string(), "profile".to_string()),
+                ("B".to_string(), "profile".to_string()),
+            ]),
+            cwd: Some("/srv".to_string()),
+        };
+        let request = ShellSpec {
+            env: BTreeMap::from([("B".to_string(), "request".to_string())]),
+            ..Default::default()
+        };
+        let spec = resolve_spec(Some(&profile), request);
+        assert_eq!(spec.shell.as_deref(), Some("docker"));
+        assert_eq!(spec.args.len(), 4);
+        assert_eq!(spec.env["A"], "profile");
+        assert_eq!(spec.env["B"], "request");
+        assert_eq!(spec.cwd.as_deref(), Some("/srv"));
+
+        // A different shell doesn't inherit the profile's arguments.
+        let request = ShellSpec {
+            shell: Some("bash".to_string()),
+            cwd: Some("/project".to_string()),
+            ..Default::default()
+        };
+        let spec = resolve_spec(Some(&profile), request);
+        assert_eq!(spec.shell.as_deref(), Some("bash"));
+        assert!(spec.args.is_empty());
+        assert_eq!(spec.cwd.as_deref(), Some("/project"));
+    }
+}
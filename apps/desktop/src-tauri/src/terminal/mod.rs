@@ -1,5 +1,13 @@
 pub mod commands;
+mod config;
+mod output;
 mod pty;
+mod reaper;
+mod recording;
+mod scrollback;
+mod triggers;
 
 // Re-export internal functions used by commands
-pub(crate) use pty::{get_terminal_manager, start_output_reader};
+pub(crate) use pty::{get_terminal_manager, shutdown_session, start_output_reader, TerminalInfo, TerminalSession};
+pub use reaper::start_reaper;
+pub(crate) use scrollback::TerminalScrollback;
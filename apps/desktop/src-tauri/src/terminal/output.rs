@@ -0,0 +1,239 @@
+use std::time::{Duration, Instant};
+
+/// Coalesce window. Short enough that keystroke echo / shell prompts feel
+/// immediate (~4ms floor), but the 64KB size threshold below still bounds
+/// IPC volume during bursts (tab-completion, file dumps). See #264.
+pub const FLUSH_INTERVAL: Duration = Duration::from_millis(4);
+pub const FLUSH_SIZE_BYTES: usize = 64 * 1024;
+
+/// A batch of output ready to emit
+pub struct OutputChunk {
+    /// Decoded text, for scrollback, logs, triggers and text events
+    pub text: String,
+    /// The bytes exactly as read, in binary mode
+    pub raw: Option<Vec<u8>>,
+}
+
+/// Turns PTY reads into coalesced output chunks: flushed every
+/// FLUSH_INTERVAL or at FLUSH_SIZE_BYTES, which stops PowerShell
+/// tab-completion from firing hundreds of IPC events.
+pub struct OutputCoalescer {
+    binary: bool,
+    /// Trailing bytes from a multi-byte UTF-8 sequence that landed across a
+    /// read boundary. Prepended to the next read so split codepoints don't
+    /// get mojibake'd into U+FFFD.
+    leftover: Vec<u8>,
+    pending: String,
+    /// Undecoded bytes since the last flush (binary mode only)
+    raw: Vec<u8>,
+    last_flush: Instant,
+}
+
+impl OutputCoalescer {
+    pub fn new(binary: bool) -> Self {
+        Self {
+            binary,
+            leftover: Vec::with_capacity(8),
+            pending: String::new(),
+            raw: Vec::new(),
+            last_flush: Instant::now(),
+        }
+    }
+
+    /// Nothing is waiting to be flushed
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty() && self.raw.is_empty()
+    }
+
+    /// Add bytes read from the PTY
+    pub fn push(&mut self, bytes: &[u8]) {
+        if self.binary {
+            self.raw.extend_from_slice(bytes);
+        }
+        // Combine any leftover from the previous read with the freshly read
+        // bytes, then peel off the longest valid-UTF-8 prefix.
+        let combined: Vec<u8> = if self.leftover.is_empty() {
+            bytes.to_vec()
+        } else {
+            let mut c = std::mem::take(&mut self.leftover);
+            c.extend_from_slice(bytes);
+            c
+        };
+
+        match std::str::from_utf8(&combined) {
+            Ok(s) => self.pending.push_str(s),
+            Err(e) => {
+                let valid_up_to = e.valid_up_to();
+                // SAFETY: bytes[..valid_up_to] is guaranteed valid UTF-8
+                // by the Utf8Error contract.
+                let valid = unsafe { std::str::from_utf8_unchecked(&combined[..valid_up_to]) };
+                self.pending.push_str(valid);
+                match e.error_len() {
+                    None => {
+                        // Incomplete multi-byte sequence at end — carry over.
+                        self.leftover.extend_from_slice(&combined[valid_up_to..]);
+                    }
+                    Some(_) => {
+                        // Genuine mid-stream garbage — lossy-replace this chunk's remainder.
+                        // Don't try to carry over after mid-stream garbage; recovery is best-effort.
+                        self.pending
+                            .push_str(&String::from_utf8_lossy(&combined[valid_up_to..]));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Take the pending output if the coalesce interval has elapsed or it
+    /// has reached the size threshold
+    pub fn take_due(&mut self, now: Instant) -> Option<OutputChunk> {
+        if self.is_empty() {
+            return None;
+        }
+        let size = self.pending.len().max(self.raw.len());
+        if now.duration_since(self.last_flush) < FLUSH_INTERVAL && size < FLUSH_SIZE_BYTES {
+            return None;
+        }
+        self.last_flush = now;
+        Some(self.take())
+    }
+
+    /// Take everything at EOF. Residual partial UTF-8 bytes are genuine
+    /// garbage there, and U+FFFD is the correct rendering.
+    pub fn finish(&mut self) -> Option<OutputChunk> {
+        if !self.leftover.is_empty() {
+            let leftover = std::mem::take(&mut self.leftover);
+            self.pending.push_str(&String::from_utf8_lossy(&leftover));
+        }
+        if self.is_empty() {
+            return None;
+        }
+        Some(self.take())
+    }
+
+    fn take(&mut self) -> OutputChunk {
+        OutputChunk {
+            text: std::mem::take(&mut self.pending),
+            raw: self.binary.then(|| std::mem::take(&mut self.raw)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed `input` in PTY-sized reads, flushing as the reader loop does with
+    /// a clock advancing `tick` per read
+    fn pump(c: &mut OutputCoalescer, input: &[u8], tick: Duration) -> Vec<OutputChunk> {
+        let mut now = Instant::now();
+        let mut chunks = Vec::new();
+        for read in input.chunks(4096) {
+            c.push(read);
+            now += tick;
+            chunks.extend(c.take_due(now));
+        }
+        chunks.extend(c.finish());
+        chunks
+    }
+
+    #[test]
+    fn coalesces_without_delaying_echo() {
+        let mut c = OutputCoalescer::new(false);
+        let start = Instant::now();
+        // Flushing an empty buffer is a no-op and doesn't reset the clock.
+        assert!(c.take_due(start + FLUSH_INTERVAL).is_none());
+
+        // A burst of small reads within the window goes out as one chunk.
+        for _ in 0..100 {
+            c.push(b"x");
+        }
+        let flushed = start + FLUSH_INTERVAL;
+        assert_eq!(c.take_due(flushed).unwrap().text.len(), 100);
+
+        // Keystroke echo waits at most FLUSH_INTERVAL.
+        c.push(b"l");
+        assert!(c.take_due(flushed + Duration::from_millis(1)).is_none());
+        assert_eq!(c.take_due(flushed + FLUSH_INTERVAL).unwrap().text, "l");
+
+        // Size threshold flushes immediately.
+        c.push(&vec![b'y'; FLUSH_SIZE_BYTES]);
+        assert!(c.take_due(flushed + FLUSH_INTERVAL).is_some());
+    }
+
+    #[test]
+    fn binary_mode_keeps_bytes_exactly() {
+        // Split multi-byte chars, invalid bytes, a zmodem-style header.
+        let mut input = Vec::new();
+        for i in 0..20_000u32 {
+            input.extend_from_slice("é€😀".as_bytes());
+            input.extend_from_slice(&[0xff, 0xfe, 0x00, 0x18, b'B', (i % 256) as u8]);
+        }
+
+        let mut c = OutputCoalescer::new(true);
+        let chunks = pump(&mut c, &input, Duration::from_millis(1));
+        let raw: Vec<u8> = chunks.iter().flat_map(|c| c.raw.clone().unwrap()).collect();
+        assert_eq!(raw, input);
+        // The text side is best-effort for invalid input but still complete.
+        assert!(chunks.iter().map(|c| c.text.matches('€').count()).sum::<usize>() >= 19_000);
+
+        // Text mode decodes split sequences without loss.
+        let valid = "é€😀 ok\r\n".repeat(10_000);
+        let mut c = OutputCoalescer::new(false);
+        let chunks = pump(&mut c, valid.as_bytes(), Duration::from_millis(1));
+        assert!(chunks.iter().all(|c| c.raw.is_none()));
+        let text: String = chunks.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(text, valid);
+    }
+
+    /// Build-log style output of roughly `bytes` bytes: ANSI colors and
+    /// multi-byte characters on every line.
+    fn build_output(bytes: usize) -> Vec<u8> {
+        let pattern = "build output ✓ 🚀 \x1b[32mok\x1b[0m\r\n";
+        pattern.repeat(bytes / pattern.len()).into_bytes()
+    }
+
+    #[test]
+    fn sustained_output_goes_out_in_full_chunks() {
+        let input = build_output(2 * 1024 * 1024);
+        for binary in [false, true] {
+            let mut c = OutputCoalescer::new(binary);
+            // No simulated time passes, so only the size threshold flushes.
+            let chunks = pump(&mut c, &input, Duration::ZERO);
+
+            let total: usize = chunks.iter().map(|c| c.text.len()).sum();
+            assert_eq!(total, input.len());
+            assert!(chunks.iter().all(|c| c.text.len() <= FLUSH_SIZE_BYTES + 4096));
+            // Every chunk but the last is full, give or take a carried-over
+            // partial character.
+            assert!(chunks.len() <= input.len() / (FLUSH_SIZE_BYTES - 4) + 1);
+        }
+    }
+
+    /// Byte-level throughput of the read → decode → coalesce path. Timing
+    /// depends on the machine, so it only runs on request:
+    /// `cargo test reader_path_throughput -- --ignored`.
+    #[test]
+    #[ignore]
+    fn reader_path_throughput() {
+        const MIN_MIB_PER_SEC: f64 = 20.0;
+        let input = build_output(32 * 1024 * 1024);
+
+        for binary in [false, true] {
+            let mut c = OutputCoalescer::new(binary);
+            let started = Instant::now();
+            let chunks = pump(&mut c, &input, Duration::ZERO);
+            let elapsed = started.elapsed().as_secs_f64();
+            assert_eq!(chunks.iter().map(|c| c.text.len()).sum::<usize>(), input.len());
+
+            let rate = input.len() as f64 / (1024.0 * 1024.0) / elapsed;
+            assert!(
+                rate >= MIN_MIB_PER_SEC,
+                "reader path (binary: {}) at {:.1} MiB/s, expected at least {}",
+                binary,
+                rate,
+                MIN_MIB_PER_SEC
+            );
+        }
+    }
+}
@@ -1,9 +1,18 @@
-use portable_pty::{native_pty_system, Child as PtyChild, CommandBuilder, PtyPair, PtySize};
-use serde::Serialize;
-use std::collections::HashMap;
+use super::output::{OutputChunk, OutputCoalescer};
+use super::recording::{Recorder, RecordingInfo};
+use super::scrollback::{Scrollback, SessionLog, TerminalScrollback, DEFAULT_SCROLLBACK_BYTES};
+use super::triggers::TriggerMatcher;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use portable_pty::{native_pty_system, Child as PtyChild, CommandBuilder, ExitStatus, PtyPair, PtySize};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::mpsc::{self, Sender};
 use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
 
 /// Event emitted when terminal produces output
@@ -12,6 +21,20 @@ use tauri::{AppHandle, Emitter};
 pub struct TerminalOutputEvent {
     pub id: String,
     pub data: String,
+    /// Stream offset of `data`, comparable with `TerminalScrollback` offsets.
+    pub offset: u64,
+}
+
+/// Output of a session in binary mode: the bytes exactly as read, base64
+/// encoded. Replaces `terminal-output` for that session.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalOutputBytesEvent {
+    pub id: String,
+    pub data: String,
+    /// Offset of `data` in the raw byte stream. Scrollback offsets count
+    /// decoded text instead, so the two only agree for valid UTF-8.
+    pub offset: u64,
 }
 
 /// Event emitted when terminal exits
@@ -19,9 +42,133 @@ pub struct TerminalOutputEvent {
 #[serde(rename_all = "camelCase")]
 pub struct TerminalExitEvent {
     pub id: String,
+    /// Exit code, or 128 + the signal number (as a shell reports it) when
+    /// the process was killed by a signal
+    pub exit_code: Option<i32>,
+    /// Signal that ended the process, e.g. `SIGINT`
+    pub signal: Option<String>,
+}
+
+/// Signals `signal_terminal` can send
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TerminalSignal {
+    Sighup,
+    Sigint,
+    Sigterm,
+    Sigkill,
+}
+
+#[cfg(unix)]
+impl TerminalSignal {
+    fn number(self) -> libc::c_int {
+        match self {
+            TerminalSignal::Sighup => libc::SIGHUP,
+            TerminalSignal::Sigint => libc::SIGINT,
+            TerminalSignal::Sigterm => libc::SIGTERM,
+            TerminalSignal::Sigkill => libc::SIGKILL,
+        }
+    }
+}
+
+/// Exit code and signal name for a finished process. portable-pty only keeps
+/// the signal's description (from `strsignal`), so map it back to a name.
+fn describe_exit(status: &ExitStatus) -> (i32, Option<String>) {
+    let text = status.to_string();
+    let Some(description) = text.strip_prefix("Terminated by ") else {
+        return (status.exit_code() as i32, None);
+    };
+    #[cfg(unix)]
+    {
+        const SIGNALS: [(libc::c_int, &str); 13] = [
+            (libc::SIGHUP, "SIGHUP"),
+            (libc::SIGINT, "SIGINT"),
+            (libc::SIGQUIT, "SIGQUIT"),
+            (libc::SIGILL, "SIGILL"),
+            (libc::SIGABRT, "SIGABRT"),
+            (libc::SIGBUS, "SIGBUS"),
+            (libc::SIGFPE, "SIGFPE"),
+            (libc::SIGKILL, "SIGKILL"),
+            (libc::SIGUSR1, "SIGUSR1"),
+            (libc::SIGSEGV, "SIGSEGV"),
+            (libc::SIGUSR2, "SIGUSR2"),
+            (libc::SIGPIPE, "SIGPIPE"),
+            (libc::SIGTERM, "SIGTERM"),
+        ];
+        let matches = |signo: libc::c_int| {
+            // SAFETY: strsignal returns a NUL-terminated string (or null)
+            // that stays valid until the next call; it's copied right away.
+            let name = unsafe { libc::strsignal(signo) };
+            !name.is_null()
+                && unsafe { std::ffi::CStr::from_ptr(name) }.to_string_lossy() == description
+        };
+        let signo = SIGNALS
+            .iter()
+            .find(|(signo, _)| matches(*signo))
+            .map(|&(signo, name)| (signo, name.to_string()))
+            .or_else(|| {
+                let n = description.strip_prefix("Signal ")?.parse().ok()?;
+                Some((n, format!("signal {}", n)))
+            });
+        if let Some((signo, name)) = signo {
+            return (128 + signo, Some(name));
+        }
+    }
+    (status.exit_code() as i32, Some(description.to_string()))
+}
+
+/// A session as reported by `list_terminals`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalInfo {
+    pub id: String,
+    /// Program the session runs (the shell for interactive terminals)
+    pub shell: String,
+    pub cwd: Option<String>,
+    pub pid: Option<u32>,
+    pub started_at: String,
+    /// Last output, input, resize, attach or UI heartbeat
+    pub last_activity: String,
+    pub alive: bool,
+    /// Set once the process has exited
     pub exit_code: Option<i32>,
+    pub signal: Option<String>,
+    /// Run that owns the session, for supervised Claude sessions
+    pub work_id: Option<String>,
+    /// Killed by the idle reaper
+    pub reaped: bool,
+}
+
+fn now_millis() -> u64 {
+    chrono::Utc::now().timestamp_millis().max(0) as u64
 }
 
+fn millis_to_rfc3339(ms: u64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms as i64)
+        .unwrap_or_default()
+        .to_rfc3339()
+}
+
+/// Size a session starts at unless the caller knows better
+pub const DEFAULT_ROWS: u16 = 24;
+pub const DEFAULT_COLS: u16 = 80;
+
+/// What to run in a new interactive session
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShellSpec {
+    /// Program to run; the platform's default shell if unset
+    pub shell: Option<String>,
+    pub args: Vec<String>,
+    /// Set on top of the app's own environment
+    pub env: BTreeMap<String, String>,
+    pub cwd: Option<String>,
+}
+
+/// Called once with the exit code after a session's process exits (or with
+/// `None` if the session is dropped while it's still running). Runs on the
+/// reader thread or inside `Drop`, so it must not lock the terminal manager.
+pub type ExitHook = Box<dyn FnOnce(Option<i32>) + Send>;
+
 /// A terminal session wrapping a pseudo-terminal
 pub struct TerminalSession {
     pty_pair: PtyPair,
@@ -30,27 +177,34 @@ pub struct TerminalSession {
     child: Option<Box<dyn PtyChild + Send + Sync>>,
     /// Stop signal sender for the reader thread (set when streaming is started)
     stop_signal: Option<Sender<()>>,
+    on_exit: Option<ExitHook>,
+    /// Recent output, shared with the reader thread
+    scrollback: Arc<Mutex<Scrollback>>,
+    /// On-disk log, handed to the reader thread when streaming starts
+    log: Option<SessionLog>,
+    shell: String,
+    cwd: Option<String>,
+    pid: Option<u32>,
+    started_at: u64,
+    work_id: Option<String>,
+    /// `TIKI_PATH` from the session's environment, for trigger actions
+    tiki_path: Option<String>,
+    /// Unix millis of the last activity, bumped by the reader thread too
+    last_activity: Arc<AtomicU64>,
+    /// True while an output reader thread is running
+    reader_active: Arc<AtomicBool>,
+    reaped: bool,
+    /// Active asciicast recording, shared with the reader thread
+    recorder: Arc<Mutex<Option<Recorder>>>,
+    /// Emit raw bytes (`terminal-output-bytes`) instead of decoded text
+    binary: bool,
 }
 
 impl TerminalSession {
-    /// Create a new terminal session with the specified shell and working directory
-    pub fn new(shell: Option<String>, cwd: Option<String>) -> Result<Self, String> {
-        let pty_system = native_pty_system();
-
-        // Default terminal size
-        let size = PtySize {
-            rows: 24,
-            cols: 80,
-            pixel_width: 0,
-            pixel_height: 0,
-        };
-
-        let pty_pair = pty_system
-            .openpty(size)
-            .map_err(|e| format!("Failed to open PTY: {}", e))?;
-
+    /// Create a new terminal session running `spec` at the given size
+    pub fn new(spec: ShellSpec, rows: u16, cols: u16) -> Result<Self, String> {
         // Build the shell command
-        let mut cmd = if let Some(shell_path) = shell {
+        let mut cmd = if let Some(shell_path) = spec.shell {
             CommandBuilder::new(shell_path)
         } else {
             // Platform-specific default shell
@@ -64,16 +218,48 @@ impl TerminalSession {
             }
         };
 
+        cmd.args(&spec.args);
+        for (key, value) in &spec.env {
+            cmd.env(key, value);
+        }
+
         // Set working directory if specified
-        if let Some(working_dir) = cwd {
+        if let Some(working_dir) = spec.cwd {
             cmd.cwd(working_dir);
         }
 
-        // Spawn the shell process
+        Self::spawn_sized(cmd, rows, cols)
+    }
+
+    /// Create a terminal session running an arbitrary command instead of a shell
+    pub fn spawn(cmd: CommandBuilder) -> Result<Self, String> {
+        Self::spawn_sized(cmd, DEFAULT_ROWS, DEFAULT_COLS)
+    }
+
+    fn spawn_sized(cmd: CommandBuilder, rows: u16, cols: u16) -> Result<Self, String> {
+        let pty_system = native_pty_system();
+
+        let size = PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        };
+
+        let pty_pair = pty_system
+            .openpty(size)
+            .map_err(|e| format!("Failed to open PTY: {}", e))?;
+
+        let program = cmd.get_argv().first().map(|a| a.to_string_lossy().into_owned()).unwrap_or_default();
+        let cwd = cmd.get_cwd().map(|c| c.to_string_lossy().into_owned());
+        let env_var = |key: &str| cmd.get_env(key).map(|v| v.to_string_lossy().into_owned());
+        let work_id = env_var("TIKI_WORK_ID");
+        let tiki_path = env_var("TIKI_PATH");
         let child = pty_pair
             .slave
             .spawn_command(cmd)
-            .map_err(|e| format!("Failed to spawn shell: {}", e))?;
+            .map_err(|e| format!("Failed to spawn {}: {}", program, e))?;
+        let pid = child.process_id();
 
         // Get the writer for sending input to the terminal
         let writer = pty_pair
@@ -86,13 +272,33 @@ impl TerminalSession {
             writer,
             child: Some(child),
             stop_signal: None,
+            on_exit: None,
+            scrollback: Arc::new(Mutex::new(Scrollback::new(DEFAULT_SCROLLBACK_BYTES))),
+            log: None,
+            shell: program,
+            cwd,
+            pid,
+            started_at: now_millis(),
+            work_id,
+            tiki_path,
+            last_activity: Arc::new(AtomicU64::new(now_millis())),
+            reader_active: Arc::new(AtomicBool::new(false)),
+            reaped: false,
+            recorder: Arc::new(Mutex::new(None)),
+            binary: false,
         })
     }
 
     /// Write data to the terminal
     pub fn write(&mut self, data: &str) -> Result<(), String> {
+        self.write_bytes(data.as_bytes())
+    }
+
+    /// Write raw bytes to the terminal
+    pub fn write_bytes(&mut self, data: &[u8]) -> Result<(), String> {
+        self.touch();
         self.writer
-            .write_all(data.as_bytes())
+            .write_all(data)
             .map_err(|e| format!("Failed to write to PTY: {}", e))?;
         self.writer
             .flush()
@@ -102,6 +308,7 @@ impl TerminalSession {
 
     /// Resize the terminal
     pub fn resize(&self, rows: u16, cols: u16) -> Result<(), String> {
+        self.touch();
         let size = PtySize {
             rows,
             cols,
@@ -111,7 +318,13 @@ impl TerminalSession {
         self.pty_pair
             .master
             .resize(size)
-            .map_err(|e| format!("Failed to resize PTY: {}", e))
+            .map_err(|e| format!("Failed to resize PTY: {}", e))?;
+        if let Ok(mut recorder) = self.recorder.lock() {
+            if let Some(recorder) = recorder.as_mut() {
+                recorder.resize(cols, rows);
+            }
+        }
+        Ok(())
     }
 
     /// Take the reader for output streaming (can only be called once)
@@ -132,6 +345,171 @@ impl TerminalSession {
         self.child.take()
     }
 
+    /// Register a hook to run when the process exits
+    pub fn set_exit_hook(&mut self, hook: ExitHook) {
+        self.on_exit = Some(hook);
+    }
+
+    /// Take the exit hook (the reader thread calls it after the process exits)
+    pub fn take_exit_hook(&mut self) -> Option<ExitHook> {
+        self.on_exit.take()
+    }
+
+    /// Send `signal` to the session's processes. SIGINT goes to the
+    /// foreground process group only, as Ctrl-C would; the others also reach
+    /// the session leader's group, so children a shell or node spawned go
+    /// too. Windows has no signals: SIGINT is sent as Ctrl-C and the rest
+    /// kill the process.
+    pub fn signal(&mut self, signal: TerminalSignal) -> Result<(), String> {
+        if self.child.is_none() {
+            return Err("Process has already exited".to_string());
+        }
+        self.touch();
+        #[cfg(unix)]
+        {
+            let pid = self.pid.ok_or("Process ID unknown")? as libc::pid_t;
+            let foreground = self.pty_pair.master.process_group_leader().filter(|&g| g > 0);
+            let mut groups = match (signal, foreground) {
+                (TerminalSignal::Sigint, Some(fg)) => vec![fg],
+                (_, Some(fg)) if fg != pid => vec![fg, pid],
+                _ => vec![pid],
+            };
+            groups.dedup();
+            let mut delivered = false;
+            let mut error = None;
+            for group in groups {
+                // SAFETY: killpg has no memory-safety preconditions.
+                if unsafe { libc::killpg(group, signal.number()) } == 0 {
+                    delivered = true;
+                } else {
+                    let e = std::io::Error::last_os_error();
+                    if e.raw_os_error() != Some(libc::ESRCH) {
+                        error = Some(e);
+                    }
+                }
+            }
+            match error {
+                Some(e) if !delivered => Err(format!("Failed to send {:?}: {}", signal, e)),
+                _ => Ok(()),
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            match signal {
+                TerminalSignal::Sigint => self.write("\x03"),
+                _ => self.kill(),
+            }
+        }
+    }
+
+    /// Kill the process. The reader thread then sees EOF and reports the exit.
+    pub fn kill(&mut self) -> Result<(), String> {
+        match self.child.as_mut() {
+            Some(child) => child.kill().map_err(|e| format!("Failed to kill process: {}", e)),
+            None => Err("Process has already exited".to_string()),
+        }
+    }
+
+    /// Also write output to `<dir>/<id>.log` and an ANSI-stripped
+    /// `<dir>/<id>.plain.log`. Must be called before streaming starts.
+    pub fn enable_log(&mut self, dir: &Path, id: &str) -> Result<(), String> {
+        self.log = Some(SessionLog::open(dir, id)?);
+        Ok(())
+    }
+
+    /// Stream output as raw bytes. Must be called before streaming starts.
+    pub fn set_binary_output(&mut self, binary: bool) {
+        self.binary = binary;
+    }
+
+    /// Handle to the session's scrollback buffer
+    pub fn scrollback(&self) -> Arc<Mutex<Scrollback>> {
+        self.scrollback.clone()
+    }
+
+    /// Associate the session with a run
+    pub fn set_work_id(&mut self, work_id: &str) {
+        self.work_id = Some(work_id.to_string());
+    }
+
+    /// Record activity, postponing idle reaping
+    pub fn touch(&self) {
+        self.last_activity.fetch_max(now_millis(), Ordering::Relaxed);
+    }
+
+    /// Milliseconds since the last activity
+    pub fn idle_millis(&self, now: u64) -> u64 {
+        now.saturating_sub(self.last_activity.load(Ordering::Relaxed))
+    }
+
+    pub fn reader_active(&self) -> bool {
+        self.reader_active.load(Ordering::Relaxed)
+    }
+
+    /// Supervised sessions are ended by their run, not the idle reaper
+    pub fn supervised(&self) -> bool {
+        self.on_exit.is_some()
+    }
+
+    /// Directory the session's Tiki context lives in: `TIKI_PATH`, else
+    /// `<cwd>/.tiki`
+    pub fn tiki_dir(&self) -> Option<PathBuf> {
+        match (&self.tiki_path, &self.cwd) {
+            (Some(path), _) => Some(PathBuf::from(path)),
+            (None, Some(cwd)) => Some(Path::new(cwd).join(".tiki")),
+            (None, None) => None,
+        }
+    }
+
+    /// Issue number of the work item the session belongs to
+    pub fn issue_number(&self) -> Option<u32> {
+        self.work_id.as_deref()?.strip_prefix("issue:")?.parse().ok()
+    }
+
+    /// Start recording output and resizes to an asciicast file at `path`
+    pub fn start_recording(&self, path: &Path, issue: Option<u32>, title: &str) -> Result<(), String> {
+        let mut recorder = self.recorder.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if recorder.is_some() {
+            return Err("Terminal is already being recorded".to_string());
+        }
+        let size = self
+            .pty_pair
+            .master
+            .get_size()
+            .map_err(|e| format!("Failed to get PTY size: {}", e))?;
+        *recorder = Some(Recorder::create(path, issue, size.cols, size.rows, title, &self.shell)?);
+        Ok(())
+    }
+
+    pub fn stop_recording(&self) -> Result<RecordingInfo, String> {
+        let mut recorder = self.recorder.lock().map_err(|e| format!("Lock error: {}", e))?;
+        recorder
+            .take()
+            .ok_or("Terminal is not being recorded")?
+            .finish()
+    }
+
+    pub fn mark_reaped(&mut self) {
+        self.reaped = true;
+    }
+
+    fn info(&self, id: &str, alive: bool, exit: (Option<i32>, Option<String>)) -> TerminalInfo {
+        let (exit_code, signal) = exit;
+        TerminalInfo {
+            id: id.to_string(),
+            shell: self.shell.clone(),
+            cwd: self.cwd.clone(),
+            pid: self.pid,
+            started_at: millis_to_rfc3339(self.started_at),
+            last_activity: millis_to_rfc3339(self.last_activity.load(Ordering::Relaxed)),
+            alive,
+            exit_code,
+            signal,
+            work_id: self.work_id.clone(),
+            reaped: self.reaped,
+        }
+    }
+
     /// Signal the reader thread to stop
     pub fn stop_reader(&mut self) {
         if let Some(sender) = self.stop_signal.take() {
@@ -144,15 +522,31 @@ impl Drop for TerminalSession {
     fn drop(&mut self) {
         // Signal reader thread to stop
         self.stop_reader();
+        // A supervised process is being torn down before it exited on its own:
+        // don't leave it running unobserved, and tell the supervisor.
+        if let Some(hook) = self.on_exit.take() {
+            let _ = self.kill();
+            hook(None);
+        }
     }
 }
 
 /// Global terminal manager singleton
 static TERMINAL_MANAGER: OnceLock<Arc<Mutex<TerminalManager>>> = OnceLock::new();
 
+/// Sessions whose process exited on its own are kept listed, with their
+/// output for replay; oldest dropped first.
+const MAX_FINISHED_SESSIONS: usize = 16;
+
+struct FinishedSession {
+    info: TerminalInfo,
+    scrollback: Arc<Mutex<Scrollback>>,
+}
+
 /// Manages multiple terminal sessions
 pub struct TerminalManager {
     sessions: HashMap<String, TerminalSession>,
+    finished: VecDeque<FinishedSession>,
 }
 
 impl TerminalManager {
@@ -160,6 +554,7 @@ impl TerminalManager {
     fn new() -> Self {
         Self {
             sessions: HashMap::new(),
+            finished: VecDeque::new(),
         }
     }
 
@@ -167,20 +562,32 @@ impl TerminalManager {
     pub fn create_session(
         &mut self,
         id: String,
-        shell: Option<String>,
-        cwd: Option<String>,
+        spec: ShellSpec,
+        rows: u16,
+        cols: u16,
     ) -> Result<(), String> {
         if self.sessions.contains_key(&id) {
             return Err(format!("Terminal session '{}' already exists", id));
         }
 
         log::info!("Creating terminal session '{}'", id);
-        let session = TerminalSession::new(shell, cwd)?;
+        let session = TerminalSession::new(spec, rows, cols)?;
+        self.finished.retain(|f| f.info.id != id);
         self.sessions.insert(id.clone(), session);
         log::info!("Terminal session '{}' created successfully", id);
         Ok(())
     }
 
+    /// Add an already-spawned session under the given ID
+    pub fn add_session(&mut self, id: String, session: TerminalSession) -> Result<(), String> {
+        if self.sessions.contains_key(&id) {
+            return Err(format!("Terminal session '{}' already exists", id));
+        }
+        self.finished.retain(|f| f.info.id != id);
+        self.sessions.insert(id, session);
+        Ok(())
+    }
+
     /// Get a mutable reference to a terminal session
     pub fn get_session_mut(&mut self, id: &str) -> Option<&mut TerminalSession> {
         self.sessions.get_mut(id)
@@ -200,6 +607,184 @@ impl TerminalManager {
         }
         session
     }
+
+    /// Remove a session whose process exited, keeping its details and
+    /// scrollback
+    fn retire_session(&mut self, id: &str, exit: (Option<i32>, Option<String>)) {
+        if let Some(session) = self.remove_session(id) {
+            if self.finished.len() >= MAX_FINISHED_SESSIONS {
+                self.finished.pop_front();
+            }
+            self.finished.push_back(FinishedSession {
+                info: session.info(id, false, exit),
+                scrollback: session.scrollback(),
+            });
+        }
+    }
+
+    /// Live sessions (oldest first), then recently exited ones
+    pub fn list(&self) -> Vec<TerminalInfo> {
+        let mut live: Vec<_> = self.sessions.iter().collect();
+        live.sort_by_key(|(id, s)| (s.started_at, id.as_str()));
+        live.into_iter()
+            .map(|(id, s)| s.info(id, true, (None, None)))
+            .chain(self.finished.iter().map(|f| f.info.clone()))
+            .collect()
+    }
+
+    /// Unsupervised sessions idle for at least `timeout_ms`
+    pub fn idle_sessions(&self, now: u64, timeout_ms: u64) -> Vec<String> {
+        self.sessions
+            .iter()
+            .filter(|(_, s)| !s.supervised() && s.idle_millis(now) >= timeout_ms)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Kill an idle session. Its reader thread reports the exit as usual; a
+    /// session without one is retired here.
+    pub fn reap_session(&mut self, id: &str) {
+        let Some(session) = self.sessions.get_mut(id) else {
+            return;
+        };
+        log::info!("Reaping idle terminal session '{}'", id);
+        session.mark_reaped();
+        if let Err(e) = session.kill() {
+            log::warn!("Failed to kill idle terminal '{}': {}", id, e);
+        }
+        if !session.reader_active() {
+            self.retire_session(id, (None, None));
+        }
+    }
+
+    /// Snapshot of a live or recently exited session's output
+    pub fn scrollback(&self, id: &str) -> Option<TerminalScrollback> {
+        if let Some(session) = self.sessions.get(id) {
+            let sb = session.scrollback.lock().ok()?;
+            return Some(sb.snapshot(id, true));
+        }
+        let finished = self.finished.iter().rev().find(|f| f.info.id == id)?;
+        let sb = finished.scrollback.lock().ok()?;
+        Some(sb.snapshot(id, false))
+    }
+}
+
+/// How long `shutdown_session` waits after SIGKILL for the reader to see EOF
+const KILL_WAIT: Duration = Duration::from_secs(1);
+
+/// Poll until the reader thread has retired session `id`
+fn wait_for_exit(id: &str, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let gone = get_terminal_manager()
+            .lock()
+            .map(|guard| guard.get_session(id).is_none())
+            .unwrap_or(true);
+        if gone {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(25));
+    }
+}
+
+/// End a session in order: SIGHUP and SIGTERM, up to `grace` for the
+/// process to exit (its exit is reported as usual), then SIGKILL. A session
+/// still around after that is dropped. Blocks, so call it off the main thread.
+pub fn shutdown_session(id: &str, grace: Duration) -> Result<(), String> {
+    let watched = {
+        let manager = get_terminal_manager();
+        let mut guard = manager.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let session = guard
+            .get_session_mut(id)
+            .ok_or_else(|| format!("Terminal session '{}' not found. It may have already been destroyed or exited.", id))?;
+        let running = session.signal(TerminalSignal::Sighup).is_ok();
+        if running {
+            let _ = session.signal(TerminalSignal::Sigterm);
+        }
+        // Without a reader nothing will notice the exit; don't wait for it.
+        running && session.reader_active()
+    };
+    if watched && wait_for_exit(id, grace) {
+        return Ok(());
+    }
+
+    if watched {
+        log::info!("Terminal '{}' ignored SIGTERM for {:?}, killing it", id, grace);
+        if let Ok(mut guard) = get_terminal_manager().lock() {
+            if let Some(session) = guard.get_session_mut(id) {
+                let _ = session.signal(TerminalSignal::Sigkill);
+            }
+        }
+        if wait_for_exit(id, KILL_WAIT) {
+            return Ok(());
+        }
+    }
+
+    let manager = get_terminal_manager();
+    let mut guard = manager.lock().map_err(|e| format!("Lock error: {}", e))?;
+    guard.remove_session(id);
+    Ok(())
+}
+
+/// Everything a reader thread feeds output into besides the event itself
+struct OutputSinks {
+    scrollback: Arc<Mutex<Scrollback>>,
+    log: Option<SessionLog>,
+    activity: Arc<AtomicU64>,
+    triggers: TriggerMatcher,
+    recorder: Arc<Mutex<Option<Recorder>>>,
+    /// Raw bytes emitted so far, in binary mode
+    raw_offset: u64,
+}
+
+impl OutputSinks {
+    /// Record a chunk of output, emit it, then run any triggers it matched
+    fn emit(&mut self, app_handle: &AppHandle, id: &str, chunk: OutputChunk) {
+        let data = chunk.text;
+        self.activity.fetch_max(now_millis(), Ordering::Relaxed);
+        let offset = match self.scrollback.lock() {
+            Ok(mut sb) => sb.push(&data),
+            Err(_) => 0,
+        };
+        if let Some(log) = self.log.as_mut() {
+            log.write(&data);
+        }
+        if let Ok(mut recorder) = self.recorder.lock() {
+            if let Some(recorder) = recorder.as_mut() {
+                recorder.output(&data);
+            }
+        }
+        self.triggers.refresh();
+        let hits = self.triggers.feed(&data);
+        let emitted = match chunk.raw {
+            Some(raw) => {
+                let event = TerminalOutputBytesEvent {
+                    id: id.to_string(),
+                    data: BASE64.encode(&raw),
+                    offset: self.raw_offset,
+                };
+                self.raw_offset += raw.len() as u64;
+                app_handle.emit("terminal-output-bytes", event)
+            }
+            None => {
+                let event = TerminalOutputEvent {
+                    id: id.to_string(),
+                    data,
+                    offset,
+                };
+                app_handle.emit("terminal-output", event)
+            }
+        };
+        if let Err(e) = emitted {
+            log::error!("Failed to emit terminal output event: {}", e);
+        }
+        for hit in hits {
+            self.triggers.fire(app_handle, hit);
+        }
+    }
 }
 
 /// Get the global terminal manager instance
@@ -230,6 +815,18 @@ pub fn start_output_reader(
     let (stop_tx, stop_rx) = mpsc::channel::<()>();
     session.set_stop_signal(stop_tx);
 
+    let mut sinks = OutputSinks {
+        scrollback: session.scrollback(),
+        log: session.log.take(),
+        activity: session.last_activity.clone(),
+        triggers: TriggerMatcher::new(&id, session.work_id.clone(), session.tiki_path.clone()),
+        recorder: session.recorder.clone(),
+        raw_offset: 0,
+    };
+    let binary = session.binary;
+    let reader_active = session.reader_active.clone();
+    reader_active.store(true, Ordering::Relaxed);
+
     // Clone id for the thread
     let thread_id = id.clone();
 
@@ -237,20 +834,8 @@ pub fn start_output_reader(
     std::thread::spawn(move || {
         log::info!("Reader thread started for terminal '{}'", thread_id);
         let mut buffer = [0u8; 4096];
-        // Trailing bytes from a multi-byte UTF-8 sequence that landed across a
-        // read boundary. Prepended to the next read so split codepoints don't
-        // get mojibake'd into U+FFFD.
-        let mut leftover: Vec<u8> = Vec::with_capacity(8);
-        // Coalesce buffer — flushed every FLUSH_INTERVAL or at FLUSH_SIZE_BYTES.
-        // Stops PowerShell tab-completion from firing hundreds of IPC events.
-        let mut pending = String::new();
-        let mut last_flush = std::time::Instant::now();
+        let mut output = OutputCoalescer::new(binary);
         let mut was_stopped = false;
-        // Coalesce window. Short enough that keystroke echo / shell prompts feel
-        // immediate (~4ms floor), but the 64KB size threshold below still bounds
-        // IPC volume during bursts (tab-completion, file dumps). See #264.
-        const FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(4);
-        const FLUSH_SIZE_BYTES: usize = 64 * 1024;
 
         loop {
             // Check for stop signal (non-blocking)
@@ -266,59 +851,13 @@ pub fn start_output_reader(
             match reader.read(&mut buffer) {
                 Ok(0) => {
                     log::info!("Terminal '{}' EOF reached", thread_id);
-                    // Flush any incomplete UTF-8 leftover lossily — residual partial
-                    // bytes at EOF are genuine garbage and U+FFFD is the correct rendering.
-                    if !leftover.is_empty() {
-                        pending.push_str(&String::from_utf8_lossy(&leftover));
-                        leftover.clear();
-                    }
                     // Final emit of any pending bytes BEFORE the exit-event logic below.
-                    if !pending.is_empty() {
-                        let event = TerminalOutputEvent {
-                            id: thread_id.clone(),
-                            data: std::mem::take(&mut pending),
-                        };
-                        if let Err(e) = app_handle.emit("terminal-output", event) {
-                            log::error!("Failed to emit terminal-output event: {}", e);
-                        }
+                    if let Some(chunk) = output.finish() {
+                        sinks.emit(&app_handle, &thread_id, chunk);
                     }
                     should_break = true;
                 }
-                Ok(n) => {
-                    // Combine any leftover from the previous read with the freshly read
-                    // bytes, then peel off the longest valid-UTF-8 prefix.
-                    let combined: Vec<u8> = if leftover.is_empty() {
-                        buffer[..n].to_vec()
-                    } else {
-                        let mut c = std::mem::take(&mut leftover);
-                        c.extend_from_slice(&buffer[..n]);
-                        c
-                    };
-
-                    match std::str::from_utf8(&combined) {
-                        Ok(s) => pending.push_str(s),
-                        Err(e) => {
-                            let valid_up_to = e.valid_up_to();
-                            // SAFETY: bytes[..valid_up_to] is guaranteed valid UTF-8
-                            // by the Utf8Error contract.
-                            let valid = unsafe {
-                                std::str::from_utf8_unchecked(&combined[..valid_up_to])
-                            };
-                            pending.push_str(valid);
-                            match e.error_len() {
-                                None => {
-                                    // Incomplete multi-byte sequence at end — carry over.
-                                    leftover.extend_from_slice(&combined[valid_up_to..]);
-                                }
-                                Some(_) => {
-                                    // Genuine mid-stream garbage — lossy-replace this chunk's remainder.
-                                    // Don't try to carry over after mid-stream garbage; recovery is best-effort.
-                                    pending.push_str(&String::from_utf8_lossy(&combined[valid_up_to..]));
-                                }
-                            }
-                        }
-                    }
-                }
+                Ok(n) => output.push(&buffer[..n]),
                 Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
                     is_would_block = true;
                 }
@@ -335,17 +874,8 @@ pub fn start_output_reader(
             // Flush coalesce buffer if interval elapsed or size threshold met.
             // MUST run before the WouldBlock sleep — otherwise idle periods after
             // a burst would leave the final chunk stuck waiting for more output.
-            if !pending.is_empty()
-                && (last_flush.elapsed() >= FLUSH_INTERVAL || pending.len() >= FLUSH_SIZE_BYTES)
-            {
-                let event = TerminalOutputEvent {
-                    id: thread_id.clone(),
-                    data: std::mem::take(&mut pending),
-                };
-                if let Err(e) = app_handle.emit("terminal-output", event) {
-                    log::error!("Failed to emit terminal-output event: {}", e);
-                }
-                last_flush = std::time::Instant::now();
+            if let Some(chunk) = output.take_due(Instant::now()) {
+                sinks.emit(&app_handle, &thread_id, chunk);
             }
 
             // Only sleep on WouldBlock — non-WouldBlock iterations loop immediately
@@ -354,7 +884,7 @@ pub fn start_output_reader(
             // flush is imminent — keep echo latency low) vs 8ms when truly idle (keep
             // idle CPU low instead of busy-spinning).
             if is_would_block {
-                let nap = if pending.is_empty() { 8 } else { 2 };
+                let nap = if output.is_empty() { 8 } else { 2 };
                 std::thread::sleep(std::time::Duration::from_millis(nap));
             }
         }
@@ -362,47 +892,118 @@ pub fn start_output_reader(
         // Emit terminal-exit event (unless manually stopped via destroy_terminal)
         if !was_stopped {
             // Retrieve exit code from the child process handle
-            let exit_code = {
+            let mut exit_hook = None;
+            let (exit_code, signal) = {
                 let manager = get_terminal_manager();
                 let mut child = manager
                     .lock()
                     .ok()
                     .and_then(|mut guard| {
-                        guard.get_session_mut(&thread_id)
-                            .and_then(|session| session.take_child())
+                        guard.get_session_mut(&thread_id).and_then(|session| {
+                            exit_hook = session.take_exit_hook();
+                            session.take_child()
+                        })
                     });
                 // Process already exited (EOF reached), so wait() returns immediately
-                child.as_mut().and_then(|c| {
-                    match c.wait() {
-                        Ok(status) => Some(status.exit_code() as i32),
-                        Err(e) => {
-                            log::warn!("Failed to get exit code for '{}': {}", thread_id, e);
-                            None
-                        }
+                match child.as_mut().map(|c| c.wait()) {
+                    Some(Ok(status)) => {
+                        let (code, signal) = describe_exit(&status);
+                        (Some(code), signal)
+                    }
+                    Some(Err(e)) => {
+                        log::warn!("Failed to get exit code for '{}': {}", thread_id, e);
+                        (None, None)
                     }
-                })
+                    None => (None, None),
+                }
             };
 
-            log::info!("Emitting terminal-exit event for '{}' (exit_code: {:?})", thread_id, exit_code);
+            log::info!(
+                "Emitting terminal-exit event for '{}' (exit_code: {:?}, signal: {:?})",
+                thread_id,
+                exit_code,
+                signal
+            );
             let exit_event = TerminalExitEvent {
                 id: thread_id.clone(),
                 exit_code,
+                signal: signal.clone(),
             };
 
             if let Err(e) = app_handle.emit("terminal-exit", exit_event) {
                 log::error!("Failed to emit terminal-exit event: {}", e);
             }
 
-            // Auto-cleanup the session from the manager
+            // Auto-cleanup the session from the manager, keeping its output
             let manager = get_terminal_manager();
             let _ = manager.lock().map(|mut guard| {
-                guard.remove_session(&thread_id);
+                guard.retire_session(&thread_id, (exit_code, signal));
             });
+
+            // Run the supervisor hook last, once the session ID is free again.
+            if let Some(hook) = exit_hook {
+                hook(exit_code);
+            }
         }
 
+        reader_active.store(false, Ordering::Relaxed);
         log::info!("Reader thread for '{}' exiting", thread_id);
     });
 
     log::info!("Output reader started for terminal '{}'", id);
     Ok(())
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn sleeper() -> TerminalSession {
+        let mut cmd = CommandBuilder::new("sleep");
+        cmd.arg("30");
+        cmd.cwd(std::env::temp_dir());
+        TerminalSession::spawn(cmd).expect("spawn sleep")
+    }
+
+    #[test]
+    fn lists_sessions_and_reaps_idle_unsupervised_ones() {
+        let mut manager = TerminalManager::new();
+        manager.add_session("shell".to_string(), sleeper()).unwrap();
+        let mut run = sleeper();
+        run.set_work_id("issue:7");
+        run.set_exit_hook(Box::new(|_| {}));
+        manager.add_session("run:issue:7".to_string(), run).unwrap();
+
+        let list = manager.list();
+        assert_eq!(list.len(), 2);
+        assert!(list.iter().all(|t| t.alive && t.pid.is_some() && t.shell == "sleep"));
+        let run_info = list.iter().find(|t| t.id == "run:issue:7").unwrap();
+        assert_eq!(run_info.work_id.as_deref(), Some("issue:7"));
+
+        let hour = 60 * 60_000;
+        assert!(manager.idle_sessions(now_millis(), hour).is_empty());
+        // Only the unsupervised session is a candidate once the timeout passes.
+        assert_eq!(manager.idle_sessions(now_millis() + hour, hour), vec!["shell".to_string()]);
+
+        // No reader thread here, so reaping retires the session directly.
+        manager.reap_session("shell");
+        let list = manager.list();
+        let shell = list.iter().find(|t| t.id == "shell").unwrap();
+        assert!(!shell.alive && shell.reaped);
+        assert!(manager.scrollback("shell").is_some_and(|s| !s.running));
+
+        // Dropping a supervised session kills it.
+        manager.remove_session("run:issue:7");
+    }
+
+    #[test]
+    fn signals_reach_the_process_and_are_reported() {
+        let mut session = sleeper();
+        session.signal(TerminalSignal::Sigterm).unwrap();
+        let status = session.take_child().unwrap().wait().unwrap();
+        assert_eq!(describe_exit(&status), (128 + libc::SIGTERM, Some("SIGTERM".to_string())));
+        assert!(session.signal(TerminalSignal::Sigint).is_err());
+
+        assert_eq!(describe_exit(&ExitStatus::with_exit_code(3)), (3, None));
+    }
+}
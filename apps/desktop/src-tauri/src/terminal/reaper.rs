@@ -0,0 +1,30 @@
+use super::config::load_config;
+use super::pty::get_terminal_manager;
+use std::time::Duration;
+
+const REAP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Periodically kill sessions nobody is using. A UI that crashed or reloaded
+/// stops sending heartbeats, so its shells go idle and are reaped; supervised
+/// runs are left to their run.
+pub fn start_reaper() {
+    std::thread::spawn(|| loop {
+        std::thread::sleep(REAP_INTERVAL);
+        reap_idle_sessions();
+    });
+}
+
+fn reap_idle_sessions() {
+    let minutes = load_config().idle_timeout_minutes();
+    if minutes == 0 {
+        return;
+    }
+    let manager = get_terminal_manager();
+    let Ok(mut guard) = manager.lock() else {
+        return;
+    };
+    let now = chrono::Utc::now().timestamp_millis().max(0) as u64;
+    for id in guard.idle_sessions(now, minutes * 60_000) {
+        guard.reap_session(&id);
+    }
+}
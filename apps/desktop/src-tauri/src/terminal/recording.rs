@@ -0,0 +1,395 @@
+use super::pty::{TerminalExitEvent, TerminalOutputEvent};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+// --- Recording (asciicast v2: a JSON header line, then one
+// `[seconds, code, data]` array per line) ---
+
+/// Details of a recording, returned when it stops and by `list_recordings`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingInfo {
+    pub path: String,
+    /// Issue the recording is filed under, if any
+    pub issue: Option<u32>,
+    /// Seconds from the start to the last event
+    pub duration: f64,
+    pub bytes: u64,
+}
+
+/// Appends a session's output and resizes to an asciicast v2 file
+pub struct Recorder {
+    path: PathBuf,
+    issue: Option<u32>,
+    out: BufWriter<File>,
+    started: Instant,
+    last_event: f64,
+    failed: bool,
+}
+
+impl Recorder {
+    pub fn create(
+        path: &Path,
+        issue: Option<u32>,
+        cols: u16,
+        rows: u16,
+        title: &str,
+        shell: &str,
+    ) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let file = File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        let mut out = BufWriter::new(file);
+        let header = json!({
+            "version": 2,
+            "width": cols,
+            "height": rows,
+            "timestamp": chrono::Utc::now().timestamp(),
+            "title": title,
+            "env": { "SHELL": shell, "TERM": "xterm-256color" },
+        });
+        writeln!(out, "{}", header).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            issue,
+            out,
+            started: Instant::now(),
+            last_event: 0.0,
+            failed: false,
+        })
+    }
+
+    fn event(&mut self, code: &str, data: &str) {
+        if self.failed {
+            return;
+        }
+        self.last_event = self.started.elapsed().as_secs_f64();
+        let line = json!([(self.last_event * 1e6).round() / 1e6, code, data]);
+        if let Err(e) = writeln!(self.out, "{}", line) {
+            log::warn!("Recording to {} failed, stopping it: {}", self.path.display(), e);
+            self.failed = true;
+        }
+    }
+
+    pub fn output(&mut self, data: &str) {
+        self.event("o", data);
+    }
+
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.event("r", &format!("{}x{}", cols, rows));
+    }
+
+    /// Flush and close the file
+    pub fn finish(mut self) -> Result<RecordingInfo, String> {
+        self.out
+            .flush()
+            .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))?;
+        let bytes = std::fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0);
+        Ok(RecordingInfo {
+            path: self.path.to_string_lossy().into_owned(),
+            issue: self.issue,
+            duration: self.last_event,
+            bytes,
+        })
+    }
+}
+
+/// Where a new recording of `id` goes: `.tiki/recordings/issue-N/` when it
+/// belongs to an issue, `.tiki/recordings/` otherwise
+pub fn recording_path(tiki_dir: &Path, issue: Option<u32>, id: &str) -> PathBuf {
+    let mut dir = tiki_dir.join("recordings");
+    if let Some(n) = issue {
+        dir = dir.join(format!("issue-{}", n));
+    }
+    let stem: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+    dir.join(format!("{}-{}.cast", stamp, stem))
+}
+
+/// Recordings under `.tiki/recordings/`, newest first. With `issue`, only
+/// that issue's.
+pub fn list_recordings(tiki_dir: &Path, issue: Option<u32>) -> Vec<RecordingInfo> {
+    let root = tiki_dir.join("recordings");
+    let mut dirs: Vec<(PathBuf, Option<u32>)> = match issue {
+        Some(n) => vec![(root.join(format!("issue-{}", n)), Some(n))],
+        None => vec![(root.clone(), None)],
+    };
+    if issue.is_none() {
+        if let Ok(entries) = std::fs::read_dir(&root) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if let Some(n) = name.strip_prefix("issue-").and_then(|n| n.parse().ok()) {
+                    dirs.push((entry.path(), Some(n)));
+                }
+            }
+        }
+    }
+
+    let mut recordings = Vec::new();
+    for (dir, issue) in dirs {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("cast") {
+                continue;
+            }
+            let bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            let duration = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|c| parse_cast(&c).ok())
+                .and_then(|cast| cast.events.last().map(|e| e.time))
+                .unwrap_or(0.0);
+            recordings.push(RecordingInfo {
+                path: path.to_string_lossy().into_owned(),
+                issue,
+                duration,
+                bytes,
+            });
+        }
+    }
+    // File names start with a UTC timestamp.
+    recordings.sort_by(|a, b| {
+        let name = |r: &RecordingInfo| Path::new(&r.path).file_name().map(|n| n.to_os_string());
+        name(b).cmp(&name(a))
+    });
+    recordings
+}
+
+// --- Playback ---
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CastEvent {
+    pub time: f64,
+    pub code: String,
+    pub data: String,
+}
+
+#[derive(Debug)]
+pub struct Cast {
+    pub width: u16,
+    pub height: u16,
+    pub events: Vec<CastEvent>,
+}
+
+/// Parse an asciicast v2 file. Malformed event lines are skipped.
+pub fn parse_cast(content: &str) -> Result<Cast, String> {
+    let mut lines = content.lines();
+    let header: Value = lines
+        .next()
+        .and_then(|l| serde_json::from_str(l).ok())
+        .ok_or("Not an asciicast file: missing header")?;
+    if header.get("version").and_then(Value::as_u64) != Some(2) {
+        return Err("Only asciicast v2 recordings are supported".to_string());
+    }
+    let dimension = |key: &str, default: u16| {
+        header
+            .get(key)
+            .and_then(Value::as_u64)
+            .and_then(|v| u16::try_from(v).ok())
+            .unwrap_or(default)
+    };
+    let events = lines
+        .filter_map(|line| {
+            let value: Value = serde_json::from_str(line).ok()?;
+            let event = value.as_array()?;
+            Some(CastEvent {
+                time: event.first()?.as_f64()?,
+                code: event.get(1)?.as_str()?.to_string(),
+                data: event.get(2)?.as_str()?.to_string(),
+            })
+        })
+        .collect();
+    Ok(Cast {
+        width: dimension("width", 80),
+        height: dimension("height", 24),
+        events,
+    })
+}
+
+/// Emitted during replay when the recorded terminal changed size (and once
+/// at the start with the initial size)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalReplayResizeEvent {
+    pub id: String,
+    pub cols: u16,
+    pub rows: u16,
+}
+
+/// Cancel flags of running replays by terminal ID
+fn replays() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    static REPLAYS: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+    REPLAYS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn emit_resize(app: &AppHandle, id: &str, cols: u16, rows: u16) {
+    let event = TerminalReplayResizeEvent {
+        id: id.to_string(),
+        cols,
+        rows,
+    };
+    if let Err(e) = app.emit("terminal-replay-resize", event) {
+        log::error!("Failed to emit terminal-replay-resize event: {}", e);
+    }
+}
+
+/// Playback speeds `start_replay` accepts; others are clamped into range
+const REPLAY_SPEED_RANGE: (f64, f64) = (0.1, 100.0);
+
+/// Wait before an event `delta` seconds after the previous one
+fn replay_gap(delta: f64, speed: f64, max_idle: f64) -> Duration {
+    let (min, max) = REPLAY_SPEED_RANGE;
+    let gap = delta.clamp(0.0, max_idle) / speed.clamp(min, max);
+    Duration::try_from_secs_f64(gap).unwrap_or(Duration::ZERO)
+}
+
+/// Play `cast` back as `terminal-output` events for `id`, then a
+/// `terminal-exit`. Gaps are divided by `speed` (clamped to
+/// `REPLAY_SPEED_RANGE`) and capped at `max_idle` seconds.
+pub fn start_replay(app: AppHandle, id: String, cast: Cast, speed: f64, max_idle: f64) -> Result<(), String> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut map = replays().lock().map_err(|e| format!("Lock error: {}", e))?;
+        if map.contains_key(&id) {
+            return Err(format!("A replay is already running for '{}'", id));
+        }
+        map.insert(id.clone(), cancel.clone());
+    }
+
+    std::thread::spawn(move || {
+        emit_resize(&app, &id, cast.width, cast.height);
+        let mut offset = 0u64;
+        let mut previous = 0.0;
+        'events: for event in cast.events {
+            let gap = replay_gap(event.time - previous, speed, max_idle);
+            previous = event.time;
+            // Sleep in slices so a stop request takes effect promptly.
+            let now = Instant::now();
+            let deadline = now.checked_add(gap).unwrap_or(now);
+            loop {
+                if cancel.load(Ordering::Relaxed) {
+                    break 'events;
+                }
+                let left = deadline.saturating_duration_since(Instant::now());
+                if left.is_zero() {
+                    break;
+                }
+                std::thread::sleep(left.min(Duration::from_millis(100)));
+            }
+            match event.code.as_str() {
+                "o" => {
+                    let len = event.data.len() as u64;
+                    let output = TerminalOutputEvent {
+                        id: id.clone(),
+                        data: event.data,
+                        offset,
+                    };
+                    offset += len;
+                    if let Err(e) = app.emit("terminal-output", output) {
+                        log::error!("Failed to emit terminal-output event: {}", e);
+                    }
+                }
+                "r" => {
+                    let size = event.data.split_once('x').and_then(|(c, r)| Some((c.parse().ok()?, r.parse().ok()?)));
+                    if let Some((cols, rows)) = size {
+                        emit_resize(&app, &id, cols, rows);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if let Ok(mut map) = replays().lock() {
+            map.remove(&id);
+        }
+        let exit = TerminalExitEvent {
+            id,
+            exit_code: None,
+            signal: None,
+        };
+        if let Err(e) = app.emit("terminal-exit", exit) {
+            log::error!("Failed to emit terminal-exit event: {}", e);
+        }
+    });
+    Ok(())
+}
+
+/// Stop a running replay; false if there was none
+pub fn stop_replay(id: &str) -> bool {
+    let map = replays().lock().unwrap_or_else(|e| e.into_inner());
+    match map.get(id) {
+        Some(cancel) => {
+            cancel.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recordings_round_trip_and_are_filed_by_issue() {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let tiki = std::env::temp_dir().join(format!("tiki-recording-{}", nanos)).join(".tiki");
+        let path = recording_path(&tiki, Some(12), "run:issue:12");
+        assert!(path.starts_with(tiki.join("recordings").join("issue-12")));
+        assert!(path.to_string_lossy().ends_with("run_issue_12.cast"));
+
+        let mut recorder = Recorder::create(&path, Some(12), 100, 30, "run", "claude").unwrap();
+        recorder.output("hello \"world\"\r\n");
+        recorder.resize(120, 40);
+        recorder.output("\x1b[32mdone\x1b[0m");
+        let info = recorder.finish().unwrap();
+        assert_eq!(info.issue, Some(12));
+        assert!(info.bytes > 0);
+
+        let cast = parse_cast(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!((cast.width, cast.height), (100, 30));
+        let codes: Vec<_> = cast.events.iter().map(|e| (e.code.as_str(), e.data.as_str())).collect();
+        assert_eq!(
+            codes,
+            vec![("o", "hello \"world\"\r\n"), ("r", "120x40"), ("o", "\x1b[32mdone\x1b[0m")]
+        );
+        assert!(cast.events.windows(2).all(|w| w[0].time <= w[1].time));
+
+        let listed = list_recordings(&tiki, None);
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].issue, Some(12));
+        assert!(list_recordings(&tiki, Some(13)).is_empty());
+
+        assert!(parse_cast("{\"version\":1}\n").is_err());
+        let _ = std::fs::remove_dir_all(tiki.parent().unwrap());
+    }
+
+    #[test]
+    fn replay_gaps_stay_in_range() {
+        assert_eq!(replay_gap(1.0, 2.0, 2.0), Duration::from_millis(500));
+        assert_eq!(replay_gap(10.0, 1.0, 2.0), Duration::from_secs(2));
+        assert_eq!(replay_gap(-1.0, 1.0, 2.0), Duration::ZERO);
+        // Speed is clamped, so a tiny speed can't overflow the duration.
+        assert_eq!(replay_gap(1.0, 1e-300, 2.0), Duration::from_secs(10));
+        assert_eq!(replay_gap(1.0, 1e300, 2.0), Duration::from_millis(10));
+        assert_eq!(replay_gap(1e300, 1.0, f64::INFINITY), Duration::ZERO);
+    }
+}
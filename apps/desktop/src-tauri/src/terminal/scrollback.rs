@@ -0,0 +1,252 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Output retained per session for replay after a UI reload.
+pub const DEFAULT_SCROLLBACK_BYTES: usize = 2 * 1024 * 1024;
+
+/// Bounded ring of a session's most recent output.
+///
+/// Offsets count bytes since the session started, so a client that replays
+/// the scrollback can skip live `terminal-output` events it already has.
+pub struct Scrollback {
+    chunks: VecDeque<String>,
+    len: usize,
+    capacity: usize,
+    /// Offset just past the newest byte (total bytes ever pushed).
+    end: u64,
+}
+
+/// Snapshot returned by `get_terminal_scrollback`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalScrollback {
+    pub id: String,
+    pub data: String,
+    /// Stream offset of the first byte of `data`.
+    pub start_offset: u64,
+    /// Stream offset just past the last byte of `data`.
+    pub end_offset: u64,
+    /// Older output was dropped to stay within the cap.
+    pub truncated: bool,
+    /// False once the process has exited.
+    pub running: bool,
+}
+
+impl Scrollback {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            chunks: VecDeque::new(),
+            len: 0,
+            capacity,
+            end: 0,
+        }
+    }
+
+    /// Append output; returns its stream offset.
+    pub fn push(&mut self, data: &str) -> u64 {
+        let offset = self.end;
+        self.end += data.len() as u64;
+        self.len += data.len();
+        self.chunks.push_back(data.to_string());
+        self.evict();
+        offset
+    }
+
+    fn evict(&mut self) {
+        while self.len > self.capacity {
+            let excess = self.len - self.capacity;
+            let Some(front) = self.chunks.front_mut() else {
+                break;
+            };
+            if front.len() <= excess {
+                self.len -= front.len();
+                self.chunks.pop_front();
+                continue;
+            }
+            // Trim the oldest chunk, preferably at a line start so replay
+            // doesn't begin mid-line (or mid escape sequence).
+            let mut cut = excess;
+            while !front.is_char_boundary(cut) {
+                cut += 1;
+            }
+            if let Some(nl) = front[cut..].find('\n') {
+                cut += nl + 1;
+            }
+            front.drain(..cut);
+            self.len -= cut;
+            if front.is_empty() {
+                self.chunks.pop_front();
+            }
+        }
+    }
+
+    pub fn snapshot(&self, id: &str, running: bool) -> TerminalScrollback {
+        let start_offset = self.end - self.len as u64;
+        TerminalScrollback {
+            id: id.to_string(),
+            data: self.chunks.iter().map(String::as_str).collect(),
+            start_offset,
+            end_offset: self.end,
+            truncated: start_offset > 0,
+            running,
+        }
+    }
+}
+
+// --- ANSI stripping ---
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum StripState {
+    #[default]
+    Text,
+    Escape,
+    /// Inside `ESC [ ...` until a final byte.
+    Csi,
+    /// Inside `ESC ] ...` (or DCS/APC/PM) until BEL or `ESC \`.
+    String,
+    /// Saw ESC inside a string; `\` ends it.
+    StringEscape,
+}
+
+/// Removes terminal escape sequences and control characters from output,
+/// keeping state across chunks since a sequence can span reads.
+#[derive(Default)]
+pub struct AnsiStripper {
+    state: StripState,
+}
+
+impl AnsiStripper {
+    pub fn strip(&mut self, data: &str) -> String {
+        let mut out = String::with_capacity(data.len());
+        for c in data.chars() {
+            self.state = match (self.state, c) {
+                (StripState::Text, '\x1b') => StripState::Escape,
+                (StripState::Text, '\n' | '\t') => {
+                    out.push(c);
+                    StripState::Text
+                }
+                (StripState::Text, c) if c.is_control() => StripState::Text,
+                (StripState::Text, c) => {
+                    out.push(c);
+                    StripState::Text
+                }
+                (StripState::Escape, '[') => StripState::Csi,
+                (StripState::Escape, ']' | 'P' | '_' | '^' | 'X') => StripState::String,
+                // Two-byte sequences (charset selection etc.) end here.
+                (StripState::Escape, _) => StripState::Text,
+                (StripState::Csi, '\x40'..='\x7e') => StripState::Text,
+                (StripState::Csi, _) => StripState::Csi,
+                (StripState::String, '\x07') => StripState::Text,
+                (StripState::String, '\x1b') => StripState::StringEscape,
+                (StripState::String, _) => StripState::String,
+                (StripState::StringEscape, '\\') => StripState::Text,
+                (StripState::StringEscape, _) => StripState::String,
+            };
+        }
+        out
+    }
+}
+
+// --- On-disk session log ---
+
+/// Appends a session's output to `<dir>/<id>.log` as received and to
+/// `<dir>/<id>.plain.log` with escape sequences stripped.
+pub struct SessionLog {
+    raw: File,
+    plain: File,
+    stripper: AnsiStripper,
+    failed: bool,
+}
+
+/// File stem for a terminal ID; IDs like `run:issue:42` aren't valid file
+/// names everywhere.
+fn log_stem(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+        .collect()
+}
+
+/// Paths of the raw and ANSI-stripped logs for `id` in `dir`.
+pub fn log_paths(dir: &Path, id: &str) -> (PathBuf, PathBuf) {
+    let stem = log_stem(id);
+    (
+        dir.join(format!("{}.log", stem)),
+        dir.join(format!("{}.plain.log", stem)),
+    )
+}
+
+impl SessionLog {
+    pub fn open(dir: &Path, id: &str) -> Result<Self, String> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        let (raw_path, plain_path) = log_paths(dir, id);
+        let open = |path: &Path| {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+        };
+        let mut plain = open(&plain_path)?;
+        // Sessions reusing an ID append; mark where each one starts.
+        let _ = writeln!(plain, "--- {} started {} ---", id, chrono::Utc::now().to_rfc3339());
+        Ok(Self {
+            raw: open(&raw_path)?,
+            plain,
+            stripper: AnsiStripper::default(),
+            failed: false,
+        })
+    }
+
+    pub fn write(&mut self, data: &str) {
+        if self.failed {
+            return;
+        }
+        let stripped = self.stripper.strip(data);
+        let result = self
+            .raw
+            .write_all(data.as_bytes())
+            .and_then(|_| self.plain.write_all(stripped.as_bytes()));
+        if let Err(e) = result {
+            // Don't spam the log for every chunk of a full disk.
+            log::warn!("Terminal log write failed, disabling it: {}", e);
+            self.failed = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrollback_keeps_the_newest_output() {
+        let mut sb = Scrollback::new(16);
+        assert_eq!(sb.push("line one\n"), 0);
+        assert_eq!(sb.push("line two\n"), 9);
+        let snap = sb.snapshot("t", true);
+        // 18 bytes > 16: the partial first line goes entirely.
+        assert_eq!(snap.data, "line two\n");
+        assert_eq!((snap.start_offset, snap.end_offset), (9, 18));
+        assert!(snap.truncated);
+
+        // A single oversized chunk is trimmed on a char boundary.
+        sb.push("ééééééééééééééééééé");
+        let snap = sb.snapshot("t", false);
+        assert!(snap.data.len() <= 16);
+        assert!(snap.data.chars().all(|c| c == 'é'));
+        assert_eq!(snap.end_offset, 18 + 38);
+        assert_eq!(snap.start_offset, snap.end_offset - snap.data.len() as u64);
+    }
+
+    #[test]
+    fn strips_escape_sequences_across_chunks() {
+        let mut s = AnsiStripper::default();
+        let mut out = s.strip("\x1b[1;32mok\x1b[");
+        out += &s.strip("0m done\r\n\x1b]0;title\x07next\x1b]8;;http://x\x1b\\link\n");
+        assert_eq!(out, "ok done\nnextlink\n");
+    }
+}
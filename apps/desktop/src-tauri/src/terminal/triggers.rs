@@ -0,0 +1,399 @@
+use super::config::{load_config, save_config};
+use super::scrollback::AnsiStripper;
+use crate::fs_utils;
+use crate::state::{PhaseProgress, PhaseProgressStatus, TikiState, WorkContext, WorkStatus};
+use crate::state_transition::{state_transition, TransitionInput};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+
+/// A pattern watched for in terminal output and what to do when it appears.
+/// Matching runs on ANSI-stripped text, one line at a time; the unfinished
+/// last line is matched too, so prompts waiting for input are caught.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputTrigger {
+    pub name: String,
+    pub pattern: String,
+    /// Treat `pattern` as a regex rather than a literal
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    pub action: TriggerAction,
+    /// Only watch sessions that belong to a run
+    #[serde(default)]
+    pub runs_only: bool,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Every match emits `terminal-trigger`; actions add to that.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TriggerAction {
+    Event,
+    /// Show a desktop notification
+    Notify,
+    /// Move the session's work item to `to_status` via `state_transition`,
+    /// marking its current phase `phase_status` if given
+    #[serde(rename_all = "camelCase")]
+    Transition {
+        to_status: WorkStatus,
+        #[serde(default)]
+        phase_status: Option<PhaseProgressStatus>,
+    },
+}
+
+/// Used until the user saves their own list
+pub fn default_triggers() -> Vec<OutputTrigger> {
+    vec![OutputTrigger {
+        name: "Waiting for confirmation".to_string(),
+        pattern: r"Do you want to .*\?".to_string(),
+        regex: true,
+        case_sensitive: false,
+        action: TriggerAction::Notify,
+        runs_only: false,
+        enabled: true,
+    }]
+}
+
+/// Payload of the `terminal-trigger` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalTriggerEvent {
+    pub id: String,
+    pub trigger: String,
+    /// The matching line, ANSI-stripped and shortened
+    pub line: String,
+    pub work_id: Option<String>,
+}
+
+// --- Compiled trigger set ---
+
+/// A trigger firing again on the same session within this window (a prompt
+/// being redrawn, say) is ignored.
+const TRIGGER_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Longer unfinished lines are cut from the front before matching.
+const MAX_LINE_BYTES: usize = 4096;
+
+const MAX_REPORTED_LINE_CHARS: usize = 200;
+
+struct CompiledTrigger {
+    trigger: OutputTrigger,
+    matcher: Regex,
+}
+
+fn compile(trigger: &OutputTrigger) -> Result<Regex, String> {
+    let pattern = if trigger.regex {
+        trigger.pattern.clone()
+    } else {
+        regex::escape(&trigger.pattern)
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!trigger.case_sensitive)
+        .size_limit(1 << 20)
+        .build()
+        .map_err(|e| format!("Trigger '{}': {}", trigger.name, e))
+}
+
+/// Enabled triggers with their patterns compiled. Readers hold an `Arc` to
+/// the set they started matching with and notice when it's replaced.
+pub struct TriggerSet {
+    triggers: Vec<CompiledTrigger>,
+}
+
+impl TriggerSet {
+    fn new(triggers: &[OutputTrigger]) -> Self {
+        let triggers = triggers
+            .iter()
+            .filter(|t| t.enabled)
+            .filter_map(|t| match compile(t) {
+                Ok(matcher) => Some(CompiledTrigger {
+                    trigger: t.clone(),
+                    matcher,
+                }),
+                Err(e) => {
+                    log::warn!("Skipping output trigger: {}", e);
+                    None
+                }
+            })
+            .collect();
+        Self { triggers }
+    }
+}
+
+fn active_set() -> &'static Mutex<Option<Arc<TriggerSet>>> {
+    static SET: OnceLock<Mutex<Option<Arc<TriggerSet>>>> = OnceLock::new();
+    SET.get_or_init(|| Mutex::new(None))
+}
+
+fn configured_triggers() -> Vec<OutputTrigger> {
+    load_config().triggers.unwrap_or_else(default_triggers)
+}
+
+fn current_set() -> Arc<TriggerSet> {
+    let mut guard = active_set().lock().unwrap_or_else(|e| e.into_inner());
+    guard
+        .get_or_insert_with(|| Arc::new(TriggerSet::new(&configured_triggers())))
+        .clone()
+}
+
+pub fn get_triggers() -> Vec<OutputTrigger> {
+    configured_triggers()
+}
+
+/// Validate, save and start using `triggers`
+pub fn set_triggers(triggers: Vec<OutputTrigger>) -> Result<(), String> {
+    for trigger in &triggers {
+        if trigger.name.trim().is_empty() {
+            return Err("Trigger name cannot be empty".to_string());
+        }
+        if trigger.pattern.is_empty() {
+            return Err(format!("Trigger '{}' has an empty pattern", trigger.name));
+        }
+        compile(trigger)?;
+    }
+    let set = Arc::new(TriggerSet::new(&triggers));
+    let mut config = load_config();
+    config.triggers = Some(triggers);
+    save_config(&config)?;
+    *active_set().lock().unwrap_or_else(|e| e.into_inner()) = Some(set);
+    Ok(())
+}
+
+// --- Per-session matching ---
+
+/// A trigger that matched, with the line it matched on
+pub struct TriggerHit {
+    pub trigger: OutputTrigger,
+    pub line: String,
+}
+
+/// Trigger state for one session, fed from its reader thread
+pub struct TriggerMatcher {
+    id: String,
+    work_id: Option<String>,
+    tiki_path: Option<String>,
+    set: Arc<TriggerSet>,
+    stripper: AnsiStripper,
+    /// Text of the current, unfinished line
+    line: String,
+    /// Triggers that already matched `line`
+    fired_on_line: Vec<bool>,
+    last_fired: Vec<Option<Instant>>,
+}
+
+impl TriggerMatcher {
+    pub fn new(id: &str, work_id: Option<String>, tiki_path: Option<String>) -> Self {
+        Self::with_set(id, work_id, tiki_path, current_set())
+    }
+
+    fn with_set(
+        id: &str,
+        work_id: Option<String>,
+        tiki_path: Option<String>,
+        set: Arc<TriggerSet>,
+    ) -> Self {
+        let n = set.triggers.len();
+        Self {
+            id: id.to_string(),
+            work_id,
+            tiki_path,
+            set,
+            stripper: AnsiStripper::default(),
+            line: String::new(),
+            fired_on_line: vec![false; n],
+            last_fired: vec![None; n],
+        }
+    }
+
+    /// Pick up a replaced trigger set
+    pub fn refresh(&mut self) {
+        let set = current_set();
+        if !Arc::ptr_eq(&set, &self.set) {
+            let n = set.triggers.len();
+            self.set = set;
+            self.fired_on_line = vec![false; n];
+            self.last_fired = vec![None; n];
+        }
+    }
+
+    /// Match a chunk of raw output; returns the triggers that fired
+    pub fn feed(&mut self, data: &str) -> Vec<TriggerHit> {
+        let text = self.stripper.strip(data);
+        let mut hits = Vec::new();
+        for piece in text.split_inclusive('\n') {
+            let complete = piece.ends_with('\n');
+            self.line.push_str(piece.trim_end_matches('\n'));
+            if self.line.len() > MAX_LINE_BYTES {
+                let mut cut = self.line.len() - MAX_LINE_BYTES;
+                while !self.line.is_char_boundary(cut) {
+                    cut += 1;
+                }
+                self.line.drain(..cut);
+            }
+            self.match_line(&mut hits);
+            if complete {
+                self.line.clear();
+                self.fired_on_line.fill(false);
+            }
+        }
+        hits
+    }
+
+    fn match_line(&mut self, hits: &mut Vec<TriggerHit>) {
+        if self.line.trim().is_empty() {
+            return;
+        }
+        for (i, compiled) in self.set.triggers.iter().enumerate() {
+            if self.fired_on_line[i] || (compiled.trigger.runs_only && self.work_id.is_none()) {
+                continue;
+            }
+            if !compiled.matcher.is_match(&self.line) {
+                continue;
+            }
+            self.fired_on_line[i] = true;
+            let now = Instant::now();
+            if self.last_fired[i].is_some_and(|at| now.duration_since(at) < TRIGGER_COOLDOWN) {
+                continue;
+            }
+            self.last_fired[i] = Some(now);
+            hits.push(TriggerHit {
+                trigger: compiled.trigger.clone(),
+                line: self.line.trim().chars().take(MAX_REPORTED_LINE_CHARS).collect(),
+            });
+        }
+    }
+
+    /// Emit the event for a hit and run its action
+    pub fn fire(&self, app: &AppHandle, hit: TriggerHit) {
+        log::info!("Terminal '{}' matched trigger '{}': {}", self.id, hit.trigger.name, hit.line);
+        let event = TerminalTriggerEvent {
+            id: self.id.clone(),
+            trigger: hit.trigger.name.clone(),
+            line: hit.line.clone(),
+            work_id: self.work_id.clone(),
+        };
+        if let Err(e) = app.emit("terminal-trigger", event) {
+            log::error!("Failed to emit terminal-trigger event: {}", e);
+        }
+        match hit.trigger.action {
+            TriggerAction::Event => {}
+            TriggerAction::Notify => {
+                let title = match &self.work_id {
+                    Some(work_id) => format!("{} ({})", hit.trigger.name, work_id),
+                    None => hit.trigger.name.clone(),
+                };
+                if let Err(e) = app.notification().builder().title(title).body(hit.line).show() {
+                    log::warn!("Failed to show trigger notification: {}", e);
+                }
+            }
+            TriggerAction::Transition { to_status, phase_status } => {
+                if let Err(e) = self.transition(to_status, phase_status) {
+                    log::warn!("Trigger '{}' on '{}' could not update state: {}", hit.trigger.name, self.id, e);
+                }
+            }
+        }
+    }
+
+    fn transition(
+        &self,
+        to_status: WorkStatus,
+        phase_status: Option<PhaseProgressStatus>,
+    ) -> Result<(), String> {
+        let work_id = self
+            .work_id
+            .clone()
+            .ok_or("session isn't associated with a work item")?;
+        let phase = match phase_status {
+            Some(status) => current_phase(self.tiki_path.as_deref(), &work_id)?.map(|p| PhaseProgress {
+                total: p.total,
+                current: p.current,
+                status,
+            }),
+            None => None,
+        };
+        state_transition(TransitionInput {
+            work_id,
+            to_status,
+            to_step: None,
+            phase,
+            parallel_execution: None,
+            parent_release: None,
+            issue: None,
+            release: None,
+            tiki_path: self.tiki_path.clone(),
+        })
+        .map(|_| ())
+    }
+}
+
+/// Phase progress currently recorded for an issue
+fn current_phase(tiki_path: Option<&str>, work_id: &str) -> Result<Option<PhaseProgress>, String> {
+    let Some(tiki_path) = tiki_path else {
+        return Ok(None);
+    };
+    let state = fs_utils::read_json_resilient::<TikiState>(&Path::new(tiki_path).join("state.json"))?;
+    Ok(state.and_then(|s| match s.active_work.get(work_id) {
+        Some(WorkContext::Issue(ctx)) => ctx.phase.clone(),
+        _ => None,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trigger(name: &str, pattern: &str, regex: bool) -> OutputTrigger {
+        OutputTrigger {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            regex,
+            case_sensitive: false,
+            action: TriggerAction::Event,
+            runs_only: false,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn matches_stripped_lines_and_unfinished_prompts_once() {
+        let mut runs_only = trigger("run failure", "tests failed", false);
+        runs_only.runs_only = true;
+        let set = Arc::new(TriggerSet::new(&[
+            trigger("failure", "tests failed", false),
+            trigger("prompt", r"waiting for input\b", true),
+            runs_only,
+            trigger("bad", "(", true),
+        ]));
+        // The invalid regex is dropped rather than failing the whole set.
+        assert_eq!(set.triggers.len(), 3);
+
+        let mut m = TriggerMatcher::with_set("t", None, None, set);
+        let names = |hits: Vec<TriggerHit>| hits.into_iter().map(|h| h.trigger.name).collect::<Vec<_>>();
+
+        // Split across chunks and interleaved with colour codes.
+        assert!(m.feed("3 \x1b[31mtests fa").is_empty());
+        let hits = m.feed("iled\x1b[0m\r\n");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].line, "3 tests failed");
+
+        // A prompt matches before its line ends, and only once.
+        assert_eq!(names(m.feed("Waiting for input ")), vec!["prompt"]);
+        assert!(m.feed("...").is_empty());
+        assert!(m.feed("\n").is_empty());
+
+        // Within the cooldown a repeat is ignored.
+        assert!(m.feed("TESTS FAILED\n").is_empty());
+    }
+}
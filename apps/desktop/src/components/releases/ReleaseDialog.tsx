@@ -43,7 +43,7 @@ export function ReleaseDialog({
   const fetchOpenIssues = useCallback(async () => {
     setIssuesLoading(true);
     try {
-      const isAuthenticated = await invoke<boolean>("check_gh_auth");
+      const isAuthenticated = await invoke<boolean>("check_gh_auth", { projectPath });
       if (!isAuthenticated) {
         setOpenIssues([]);
         return;
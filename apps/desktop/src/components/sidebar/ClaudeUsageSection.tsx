@@ -1,6 +1,8 @@
 import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { CollapsibleSection } from "../ui/CollapsibleSection";
+import { useToastStore } from "../../stores";
 import "./ClaudeUsageSection.css";
 
 interface UsageLimit {
@@ -15,6 +17,26 @@ interface ClaudeApiUsage {
   sevenDaySonnet: UsageLimit | null;
 }
 
+interface CredentialStoreStatus {
+  backend: "keyring" | "encryptedFile";
+  initialized: boolean;
+  unlocked: boolean;
+}
+
+interface UsageThresholdEvent {
+  limit: "fiveHour" | "sevenDay" | "sevenDayOpus" | "sevenDaySonnet";
+  threshold: number;
+  utilization: number;
+  resetsAt: string | null;
+}
+
+const LIMIT_LABELS: Record<UsageThresholdEvent["limit"], string> = {
+  fiveHour: "5-hour",
+  sevenDay: "7-day",
+  sevenDayOpus: "7-day Opus",
+  sevenDaySonnet: "7-day Sonnet",
+};
+
 const REFRESH_INTERVAL_MS = 60_000;
 
 function getUsageColor(pct: number): string {
@@ -49,6 +71,20 @@ export function ClaudeUsageSection() {
   const [saving, setSaving] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [refreshing, setRefreshing] = useState(false);
+  const [store, setStore] = useState<CredentialStoreStatus | null>(null);
+  const [passphrase, setPassphrase] = useState("");
+
+  const addToast = useToastStore((s) => s.addToast);
+
+  const locked = store !== null && !store.unlocked;
+
+  const checkStore = useCallback(async () => {
+    try {
+      setStore(await invoke<CredentialStoreStatus>("get_credential_store_status"));
+    } catch (err) {
+      setError(String(err));
+    }
+  }, []);
 
   const checkKey = useCallback(async () => {
     try {
@@ -91,6 +127,22 @@ export function ClaudeUsageSection() {
     }
   }, [keyInput, fetchUsage]);
 
+  const handleUnlock = useCallback(async () => {
+    if (!passphrase) return;
+    setSaving(true);
+    try {
+      await invoke("unlock_credential_store", { passphrase });
+      setPassphrase("");
+      setError(null);
+      await checkStore();
+      await checkKey();
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setSaving(false);
+    }
+  }, [passphrase, checkStore, checkKey]);
+
   const handleClearKey = useCallback(async () => {
     try {
       await invoke("clear_claude_session_key");
@@ -102,18 +154,34 @@ export function ClaudeUsageSection() {
     }
   }, []);
 
-  // Check key on mount
+  // Check credential store and key on mount
   useEffect(() => {
+    checkStore();
     checkKey();
-  }, [checkKey]);
+  }, [checkStore, checkKey]);
+
+  // Threshold alerts from the backend usage monitor
+  useEffect(() => {
+    const unlisten = listen<UsageThresholdEvent>("claude-usage-threshold", (event) => {
+      const { limit, utilization, resetsAt } = event.payload;
+      const reset = formatResetTime(resetsAt);
+      addToast(
+        `Claude ${LIMIT_LABELS[limit]} usage at ${Math.round(utilization)}%${reset ? ` (resets in ${reset})` : ""}`,
+        "warning"
+      );
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [addToast]);
 
-  // Fetch usage + polling when key is set
+  // Fetch usage + polling when key is set and readable
   useEffect(() => {
-    if (!hasKey) return;
+    if (!hasKey || locked) return;
     fetchUsage();
     const interval = setInterval(fetchUsage, REFRESH_INTERVAL_MS);
     return () => clearInterval(interval);
-  }, [hasKey, fetchUsage]);
+  }, [hasKey, locked, fetchUsage]);
 
   const usageIcon = (
     <svg
@@ -167,7 +235,37 @@ export function ClaudeUsageSection() {
       defaultCollapsed
     >
       <div className="claude-usage-content">
-        {hasKey === null ? null : !hasKey ? (
+        {hasKey === null ? null : locked ? (
+          // No OS keyring - credentials live in a passphrase-encrypted file
+          <div className="claude-usage-setup">
+            <p className="claude-usage-setup-hint">
+              {store?.initialized
+                ? "Enter your passphrase to unlock saved credentials."
+                : "No system keyring found. Choose a passphrase to encrypt saved credentials."}
+            </p>
+            {error && <div className="claude-usage-error">{error}</div>}
+            <div className="claude-usage-key-row">
+              <input
+                type="password"
+                className="claude-usage-key-input"
+                value={passphrase}
+                onChange={(e) => setPassphrase(e.target.value)}
+                placeholder="Passphrase"
+                onKeyDown={(e) => {
+                  if (e.key === "Enter") handleUnlock();
+                }}
+              />
+              <button
+                className="claude-usage-key-save"
+                onClick={handleUnlock}
+                disabled={saving || !passphrase}
+                type="button"
+              >
+                {saving ? "..." : store?.initialized ? "Unlock" : "Set"}
+              </button>
+            </div>
+          </div>
+        ) : !hasKey ? (
           // No session key - show setup
           <div className="claude-usage-setup">
             <p className="claude-usage-setup-hint">
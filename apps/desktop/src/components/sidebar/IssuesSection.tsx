@@ -69,7 +69,7 @@ export function IssuesSection() {
 
     try {
       // First check if gh is authenticated
-      const isAuthenticated = await invoke<boolean>("check_gh_auth");
+      const isAuthenticated = await invoke<boolean>("check_gh_auth", { projectPath: activeProject.path });
 
       if (!isAuthenticated) {
         setError("Not authenticated with GitHub. Run 'gh auth login' in your terminal.");
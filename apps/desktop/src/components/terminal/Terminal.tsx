@@ -477,7 +477,7 @@ export function Terminal({ className = "", cwd, shell, terminalId, onStatusChang
         // path, and it routes through fitAndSync().
 
         // Create the PTY session
-        createTerminalRef.current().then(() => {
+        createTerminalRef.current({ rows: xterm.rows, cols: xterm.cols }).then(() => {
           if (cancelled) return;
           // Send initial size after connection
           const { rows, cols } = xterm;
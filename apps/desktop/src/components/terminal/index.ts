@@ -3,8 +3,25 @@ export { TerminalPane } from "./TerminalPane";
 export { TerminalTabs } from "./TerminalTabs";
 export {
   useTerminal,
+  listTerminals,
+  signalTerminal,
+  listTerminalProfiles,
+  saveTerminalProfile,
+  deleteTerminalProfile,
+  startRecording,
+  stopRecording,
+  listRecordings,
+  replayRecording,
+  stopReplay,
   type TerminalOutputEvent,
+  type TerminalOutputBytesEvent,
   type TerminalExitEvent,
+  type TerminalSignal,
+  type TerminalTriggerEvent,
+  type TerminalInfo,
+  type TerminalProfile,
+  type RecordingInfo,
+  type TerminalScrollback,
   type UseTerminalOptions,
   type UseTerminalReturn,
 } from "./useTerminal";
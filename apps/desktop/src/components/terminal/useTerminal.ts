@@ -1,32 +1,182 @@
 import { useCallback, useEffect, useRef, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
+import { useToastStore } from "../../stores";
 
 // Types matching Rust backend events
 export interface TerminalOutputEvent {
   id: string;
   data: string;
+  /** Byte offset of `data` in the session's output stream. */
+  offset: number;
+}
+
+/** Output of a session created with `binary`: base64 of the bytes as read. */
+export interface TerminalOutputBytesEvent {
+  id: string;
+  data: string;
+  /** Offset in the raw byte stream. */
+  offset: number;
+}
+
+function decodeBase64(data: string): Uint8Array {
+  const binary = atob(data);
+  const bytes = new Uint8Array(binary.length);
+  for (let i = 0; i < binary.length; i++) bytes[i] = binary.charCodeAt(i);
+  return bytes;
+}
+
+function encodeBase64(bytes: Uint8Array): string {
+  let binary = "";
+  // Chunked so large pastes don't overflow the argument limit.
+  for (let i = 0; i < bytes.length; i += 0x8000) {
+    binary += String.fromCharCode(...bytes.subarray(i, i + 0x8000));
+  }
+  return btoa(binary);
+}
+
+/** Recent output of a session, from `get_terminal_scrollback`. */
+export interface TerminalScrollback {
+  id: string;
+  data: string;
+  startOffset: number;
+  endOffset: number;
+  truncated: boolean;
+  running: boolean;
+}
+
+/** A session as reported by `list_terminals`. */
+export interface TerminalInfo {
+  id: string;
+  shell: string;
+  cwd: string | null;
+  pid: number | null;
+  startedAt: string;
+  lastActivity: string;
+  alive: boolean;
+  exitCode: number | null;
+  signal: string | null;
+  workId: string | null;
+  reaped: boolean;
+}
+
+export function listTerminals(): Promise<TerminalInfo[]> {
+  return invoke<TerminalInfo[]>("list_terminals");
+}
+
+/** Named shell/args/env/cwd template for new sessions. */
+export interface TerminalProfile {
+  name: string;
+  shell?: string | null;
+  args?: string[];
+  env?: Record<string, string>;
+  cwd?: string | null;
+}
+
+export function listTerminalProfiles(): Promise<TerminalProfile[]> {
+  return invoke<TerminalProfile[]>("list_terminal_profiles");
+}
+
+export function saveTerminalProfile(profile: TerminalProfile): Promise<void> {
+  return invoke("save_terminal_profile", { profile });
+}
+
+export function deleteTerminalProfile(name: string): Promise<void> {
+  return invoke("delete_terminal_profile", { name });
+}
+
+/** An asciicast v2 recording under `.tiki/recordings/`. */
+export interface RecordingInfo {
+  path: string;
+  issue: number | null;
+  /** Seconds. */
+  duration: number;
+  bytes: number;
+}
+
+/** Starts recording a session; resolves to the recording's path. */
+export function startRecording(id: string, issue?: number): Promise<string> {
+  return invoke<string>("start_recording", { id, issue: issue ?? null });
+}
+
+export function stopRecording(id: string): Promise<RecordingInfo> {
+  return invoke<RecordingInfo>("stop_recording", { id });
+}
+
+export function listRecordings(projectPath: string, issue?: number): Promise<RecordingInfo[]> {
+  return invoke<RecordingInfo[]>("list_recordings", { projectPath, issue: issue ?? null });
+}
+
+/**
+ * Plays a recording back as `terminal-output` events for `id` (plus
+ * `terminal-replay-resize`), ending with `terminal-exit`.
+ */
+export function replayRecording(
+  id: string,
+  path: string,
+  options: { speed?: number; maxIdle?: number } = {}
+): Promise<void> {
+  return invoke("replay_recording", {
+    id,
+    path,
+    speed: options.speed ?? null,
+    maxIdle: options.maxIdle ?? null,
+  });
+}
+
+export function stopReplay(id: string): Promise<void> {
+  return invoke("stop_replay", { id });
+}
+
+/** A configured output trigger matched, from `terminal-trigger`. */
+export interface TerminalTriggerEvent {
+  id: string;
+  trigger: string;
+  line: string;
+  workId: string | null;
 }
 
 export interface TerminalExitEvent {
   id: string;
+  /** 128 + the signal number when a signal ended the process. */
   exitCode: number | null;
+  /** e.g. "SIGINT" */
+  signal: string | null;
+}
+
+export type TerminalSignal = "SIGHUP" | "SIGINT" | "SIGTERM" | "SIGKILL";
+
+/** SIGINT reaches the foreground job, like Ctrl-C; the rest the whole session. */
+export function signalTerminal(id: string, signal: TerminalSignal): Promise<void> {
+  return invoke("signal_terminal", { id, signal });
 }
 
 export interface UseTerminalOptions {
   onOutput?: (data: string) => void;
+  /** Receives output in binary mode; `onOutput` then only gets scrollback replay. */
+  onOutputBytes?: (data: Uint8Array) => void;
+  /** Stream output as raw bytes instead of decoded text. */
+  binary?: boolean;
   onExit?: (exitCode: number | null) => void;
   shell?: string;
   cwd?: string;
   externalId?: string;
+  /** Saved profile supplying shell, args, env and cwd defaults. */
+  profile?: string;
+  args?: string[];
+  /** Environment overrides, applied over the profile's. */
+  env?: Record<string, string>;
+  /** Exported to the session as `TIKI_WORK_ID`. */
+  workId?: string;
 }
 
 export interface UseTerminalReturn {
   terminalId: string | null;
   isConnected: boolean;
   error: string | null;
-  createTerminal: () => Promise<void>;
+  createTerminal: (size?: { rows: number; cols: number }) => Promise<void>;
   writeTerminal: (data: string) => Promise<void>;
+  writeTerminalBytes: (data: Uint8Array) => Promise<void>;
   resizeTerminal: (rows: number, cols: number) => Promise<void>;
   destroyTerminal: () => Promise<void>;
 }
@@ -40,27 +190,54 @@ let terminalCounter = 0;
 // callback instead of all N open terminals' listeners.
 interface TerminalCallbacks {
   onOutput?: (data: string) => void;
+  onOutputBytes?: (data: Uint8Array) => void;
   onExit?: (exitCode: number | null) => void;
+  /** Output before this offset was already replayed from the scrollback. */
+  skipBefore?: number;
 }
 const terminalCallbacks = new Map<string, TerminalCallbacks>();
 let globalListeners: Promise<void> | null = null;
 
+// Sessions nobody heartbeats are killed by the backend's idle reaper, which is
+// how shells from a crashed or reloaded UI get cleaned up.
+const HEARTBEAT_INTERVAL_MS = 60_000;
+
 function ensureGlobalTerminalListeners(): Promise<void> {
   if (!globalListeners) {
     globalListeners = (async () => {
       await listen<TerminalOutputEvent>("terminal-output", (event) => {
-        terminalCallbacks.get(event.payload.id)?.onOutput?.(event.payload.data);
+        const callbacks = terminalCallbacks.get(event.payload.id);
+        if (callbacks?.skipBefore !== undefined && event.payload.offset < callbacks.skipBefore) {
+          return;
+        }
+        callbacks?.onOutput?.(event.payload.data);
+      });
+      await listen<TerminalOutputBytesEvent>("terminal-output-bytes", (event) => {
+        terminalCallbacks.get(event.payload.id)?.onOutputBytes?.(decodeBase64(event.payload.data));
       });
       await listen<TerminalExitEvent>("terminal-exit", (event) => {
         terminalCallbacks.get(event.payload.id)?.onExit?.(event.payload.exitCode);
       });
+      // Triggers mostly matter for tabs nobody is looking at, so surface them
+      // app-wide rather than in the terminal.
+      await listen<TerminalTriggerEvent>("terminal-trigger", (event) => {
+        const { trigger, line, workId } = event.payload;
+        useToastStore.getState().addToast(`${trigger}${workId ? ` (${workId})` : ""}: ${line}`, "warning");
+      });
+      setInterval(() => {
+        if (terminalCallbacks.size === 0) return;
+        invoke("terminal_heartbeat", { ids: [...terminalCallbacks.keys()] }).catch(() => {
+          // Missed heartbeats only matter after the idle timeout
+        });
+      }, HEARTBEAT_INTERVAL_MS);
     })();
   }
   return globalListeners;
 }
 
 export function useTerminal(options: UseTerminalOptions = {}): UseTerminalReturn {
-  const { onOutput, onExit, shell, cwd, externalId } = options;
+  const { onOutput, onOutputBytes, binary, onExit, shell, cwd, externalId, profile, args, env, workId } =
+    options;
 
   const [terminalId, setTerminalId] = useState<string | null>(null);
   const [isConnected, setIsConnected] = useState(false);
@@ -68,14 +245,16 @@ export function useTerminal(options: UseTerminalOptions = {}): UseTerminalReturn
 
   // Store callbacks in refs to avoid re-subscribing on every render
   const onOutputRef = useRef(onOutput);
+  const onOutputBytesRef = useRef(onOutputBytes);
   const onExitRef = useRef(onExit);
   onOutputRef.current = onOutput;
+  onOutputBytesRef.current = onOutputBytes;
   onExitRef.current = onExit;
 
   const currentIdRef = useRef<string | null>(null);
 
   // Create a new terminal session
-  const createTerminal = useCallback(async () => {
+  const createTerminal = useCallback(async (size?: { rows: number; cols: number }) => {
     try {
       setError(null);
 
@@ -89,18 +268,37 @@ export function useTerminal(options: UseTerminalOptions = {}): UseTerminalReturn
       await ensureGlobalTerminalListeners();
       terminalCallbacks.set(id, {
         onOutput: (data) => onOutputRef.current?.(data),
+        onOutputBytes: (data) => onOutputBytesRef.current?.(data),
         onExit: (exitCode) => {
           setIsConnected(false);
           onExitRef.current?.(exitCode);
         },
       });
 
-      // Create the terminal session
-      await invoke("create_terminal", {
-        id,
-        shell: shell ?? null,
-        cwd: cwd ?? null,
-      });
+      // Create the terminal session. A fixed ID may still have a live session
+      // from before a UI reload: reattach and replay its output instead.
+      try {
+        await invoke("create_terminal", {
+          id,
+          shell: shell ?? null,
+          cwd: cwd ?? null,
+          options: {
+            profile: profile ?? null,
+            args: args ?? [],
+            env: env ?? {},
+            rows: size?.rows ?? null,
+            cols: size?.cols ?? null,
+            workId: workId ?? null,
+            binaryOutput: binary ?? false,
+          },
+        });
+      } catch (err) {
+        if (!externalId || !String(err).includes("already exists")) throw err;
+        const scrollback = await invoke<TerminalScrollback>("attach_terminal", { id });
+        const callbacks = terminalCallbacks.get(id);
+        if (callbacks) callbacks.skipBefore = scrollback.endOffset;
+        if (scrollback.data) onOutputRef.current?.(scrollback.data);
+      }
 
       setTerminalId(id);
       setIsConnected(true);
@@ -112,7 +310,10 @@ export function useTerminal(options: UseTerminalOptions = {}): UseTerminalReturn
       // Unregister this terminal's callbacks on error (id is try-scoped; use the ref).
       if (currentIdRef.current) terminalCallbacks.delete(currentIdRef.current);
     }
-  }, [shell, cwd, externalId]);
+    // args/env are read at creation time only; changing them mid-session
+    // shouldn't recreate the callback on every render.
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [shell, cwd, externalId, profile, workId, binary]);
 
   // Write data to the terminal
   const writeTerminal = useCallback(async (data: string) => {
@@ -131,6 +332,23 @@ export function useTerminal(options: UseTerminalOptions = {}): UseTerminalReturn
     }
   }, []);
 
+  // Write raw bytes to the terminal
+  const writeTerminalBytes = useCallback(async (data: Uint8Array) => {
+    if (!currentIdRef.current) {
+      return;
+    }
+
+    try {
+      await invoke("write_terminal_bytes", {
+        id: currentIdRef.current,
+        data: encodeBase64(data),
+      });
+    } catch (err) {
+      const message = err instanceof Error ? err.message : String(err);
+      setError(message);
+    }
+  }, []);
+
   // Resize the terminal
   const resizeTerminal = useCallback(async (rows: number, cols: number) => {
     if (!currentIdRef.current) {
@@ -191,6 +409,7 @@ export function useTerminal(options: UseTerminalOptions = {}): UseTerminalReturn
     error,
     createTerminal,
     writeTerminal,
+    writeTerminalBytes,
     resizeTerminal,
     destroyTerminal,
   };
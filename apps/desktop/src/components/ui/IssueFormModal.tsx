@@ -1,5 +1,6 @@
 import { useState, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { useProjectsStore, useTikiReleasesStore, useSettingsStore, useToastStore, type GitHubIssue } from "../../stores";
 import "./IssueFormModal.css";
 
@@ -12,7 +13,19 @@ type Priority = "high" | "medium" | "low" | null;
 type BranchStrategy = "current" | "auto" | "custom";
 type ModelType = "sonnet" | "opus" | "haiku";
 type PlanningType = "skip" | "lite" | "spec" | "full";
-type EnhancementType = "clarity" | "technical" | "simplify" | "acceptance";
+
+/** An enhancement type: a built-in prompt or one from `.tiki/prompts/*.md`. */
+interface EnhancementPrompt {
+  id: string;
+  name: string;
+  description?: string;
+  source: "builtin" | "project";
+}
+
+interface EnhancementOutputEvent {
+  requestId: string;
+  chunk: string;
+}
 
 interface IssueFormModalProps {
   isOpen: boolean;
@@ -63,6 +76,8 @@ export function IssueFormModal({
   const [selectedRelease, setSelectedRelease] = useState<string>("");
   const [enhanceDropdownOpen, setEnhanceDropdownOpen] = useState(false);
   const [enhancing, setEnhancing] = useState(false);
+  const [enhancementPrompts, setEnhancementPrompts] = useState<EnhancementPrompt[]>([]);
+  const enhanceRequestId = useRef<string | null>(null);
   const [claudeCliAvailable, setClaudeCliAvailable] = useState<boolean | null>(null);
   const [currentBranchName, setCurrentBranchName] = useState<string | null>(null);
   const [loadingBranch, setLoadingBranch] = useState(false);
@@ -85,9 +100,22 @@ export function IssueFormModal({
       fetchLabels();
       fetchCurrentBranch();
       checkClaudeCli();
+      fetchEnhancementPrompts();
     }
   }, [isOpen]);
 
+  const fetchEnhancementPrompts = async () => {
+    try {
+      const prompts = await invoke<EnhancementPrompt[]>("list_enhancement_prompts", {
+        projectPath: activeProject?.path,
+      });
+      setEnhancementPrompts(prompts);
+    } catch (err) {
+      console.error("Failed to load enhancement prompts:", err);
+      setEnhancementPrompts([]);
+    }
+  };
+
   const checkClaudeCli = async () => {
     try {
       const available = await invoke<boolean>("check_claude_cli");
@@ -205,27 +233,57 @@ export function IssueFormModal({
     );
   };
 
-  const handleEnhanceDescription = async (type: EnhancementType) => {
+  const handleEnhanceDescription = async (type: string) => {
     setEnhanceDropdownOpen(false);
     if (!body.trim()) {
       setError("Please add a description before enhancing");
       return;
     }
+    const original = body;
+    const requestId = crypto.randomUUID();
+    enhanceRequestId.current = requestId;
     setEnhancing(true);
     setError(null);
+
+    // Show the output as it streams in; the final result replaces it.
+    let streamed = "";
+    const unlisten = await listen<EnhancementOutputEvent>("enhancement-output", (event) => {
+      if (event.payload.requestId !== requestId) return;
+      streamed += event.payload.chunk;
+      setBody(streamed);
+    });
     try {
       const enhanced = await invoke<string>("enhance_issue_description", {
-        description: body,
+        description: original,
         enhancementType: type,
+        requestId,
+        projectPath: activeProject?.path,
       });
       setBody(enhanced);
     } catch (err) {
-      setError(String(err));
+      setBody(original);
+      if (enhanceRequestId.current === requestId) {
+        setError(String(err));
+      }
     } finally {
+      unlisten();
+      enhanceRequestId.current = null;
       setEnhancing(false);
     }
   };
 
+  const handleCancelEnhance = async () => {
+    const requestId = enhanceRequestId.current;
+    if (!requestId) return;
+    // Cleared first so the resulting "cancelled" error isn't shown.
+    enhanceRequestId.current = null;
+    try {
+      await invoke<boolean>("cancel_issue_enhancement", { requestId });
+    } catch (err) {
+      console.error("Failed to cancel enhancement:", err);
+    }
+  };
+
   const handleSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
 
@@ -377,8 +435,8 @@ export function IssueFormModal({
                 <button
                   type="button"
                   className={`issue-form-enhance-btn${claudeCliAvailable === false ? " cli-unavailable" : ""}`}
-                  onClick={() => setEnhanceDropdownOpen(!enhanceDropdownOpen)}
-                  disabled={loading || enhancing || !body.trim() || claudeCliAvailable === false}
+                  onClick={enhancing ? handleCancelEnhance : () => setEnhanceDropdownOpen(!enhanceDropdownOpen)}
+                  disabled={loading || (!enhancing && (!body.trim() || claudeCliAvailable === false))}
                   aria-expanded={enhanceDropdownOpen}
                   aria-haspopup="menu"
                   title={claudeCliAvailable === false ? "Claude CLI not found on PATH or in common install locations. Install it from https://github.com/anthropics/claude-code, or ensure it's on your PATH." : undefined}
@@ -386,7 +444,7 @@ export function IssueFormModal({
                   {enhancing ? (
                     <>
                       <span className="issue-form-spinner issue-form-spinner-small" />
-                      Enhancing...
+                      Cancel enhancing
                     </>
                   ) : claudeCliAvailable === false ? (
                     <>
@@ -430,42 +488,20 @@ export function IssueFormModal({
                 </button>
                 {enhanceDropdownOpen && (
                   <div className="issue-form-enhance-dropdown" role="menu">
-                    <button
-                      type="button"
-                      className="issue-form-enhance-option"
-                      onClick={() => handleEnhanceDescription("clarity")}
-                      role="menuitem"
-                    >
-                      <span className="issue-form-enhance-option-title">Improve clarity</span>
-                      <span className="issue-form-enhance-option-desc">Make the description clearer and easier to understand</span>
-                    </button>
-                    <button
-                      type="button"
-                      className="issue-form-enhance-option"
-                      onClick={() => handleEnhanceDescription("technical")}
-                      role="menuitem"
-                    >
-                      <span className="issue-form-enhance-option-title">Add technical details</span>
-                      <span className="issue-form-enhance-option-desc">Add implementation hints and technical context</span>
-                    </button>
-                    <button
-                      type="button"
-                      className="issue-form-enhance-option"
-                      onClick={() => handleEnhanceDescription("simplify")}
-                      role="menuitem"
-                    >
-                      <span className="issue-form-enhance-option-title">Simplify language</span>
-                      <span className="issue-form-enhance-option-desc">Use simpler terms and reduce complexity</span>
-                    </button>
-                    <button
-                      type="button"
-                      className="issue-form-enhance-option"
-                      onClick={() => handleEnhanceDescription("acceptance")}
-                      role="menuitem"
-                    >
-                      <span className="issue-form-enhance-option-title">Add acceptance criteria</span>
-                      <span className="issue-form-enhance-option-desc">Generate testable acceptance criteria</span>
-                    </button>
+                    {enhancementPrompts.map((prompt) => (
+                      <button
+                        key={prompt.id}
+                        type="button"
+                        className="issue-form-enhance-option"
+                        onClick={() => handleEnhanceDescription(prompt.id)}
+                        role="menuitem"
+                      >
+                        <span className="issue-form-enhance-option-title">{prompt.name}</span>
+                        {prompt.description && (
+                          <span className="issue-form-enhance-option-desc">{prompt.description}</span>
+                        )}
+                      </button>
+                    ))}
                   </div>
                 )}
               </div>
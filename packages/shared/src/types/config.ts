@@ -8,6 +8,8 @@
  * defaults documented inline.
  */
 
+import type { WorkStatus } from './state.js';
+
 /** Error categories eligible for auto-heal */
 export type AutoHealCategory =
   | 'build-error'
@@ -71,6 +73,61 @@ export interface ChangelogConfig {
   includeAuthors?: boolean;
 }
 
+/** Git branch and worktree conventions (desktop git commands) */
+export interface GitConfig {
+  /**
+   * Branch name template for issue branches. Placeholders: `{number}`,
+   * `{slug}` (default 'issue-{number}-{slug}')
+   */
+  branchTemplate?: string;
+  /** Directory, relative to the project root, holding per-issue worktrees (default '../<repo>-worktrees') */
+  worktreeDir?: string;
+}
+
+/** How the desktop talks to GitHub for this project */
+export interface GitHubConfig {
+  /**
+   * Transport for issue/PR/label/release data: 'gh' (gh CLI, default) or
+   * 'api' (direct REST/GraphQL with a personal access token or gh's token)
+   */
+  backend?: "gh" | "api";
+  /**
+   * GitHub host for this project, e.g. a GitHub Enterprise Server hostname.
+   * Defaults to the host gh infers from the git remotes
+   */
+  host?: string;
+  /** 'owner/repo' to target instead of the repo gh picks from the git remotes */
+  repo?: string;
+  /** GitHub Projects (v2) board that mirrors Tiki work status */
+  project?: GitHubProjectConfig;
+  /** Label taxonomy applied to the repo by label sync */
+  labels?: LabelTemplate[];
+}
+
+/** One label in the `github.labels` template */
+export interface LabelTemplate {
+  name: string;
+  /** Hex color, with or without a leading '#' */
+  color: string;
+  /** Left untouched on existing labels when omitted */
+  description?: string;
+}
+
+/** GitHub Projects (v2) board that mirrors Tiki work status */
+export interface GitHubProjectConfig {
+  /** Project number, from the board URL (.../projects/<number>) */
+  number: number;
+  /** User or organization that owns the board. Defaults to the repository owner */
+  owner?: string;
+  /** Single-select field set from the work status. Defaults to "Status" */
+  statusField?: string;
+  /**
+   * Work status (e.g. 'executing') to board option name. Unmapped statuses
+   * use Todo / In Progress / Done
+   */
+  statusMap?: Partial<Record<WorkStatus, string>>;
+}
+
 /** Project-level Tiki configuration (`.tiki/config.json`) */
 export interface TikiConfig {
   /** Workflow behavior for the pipeline */
@@ -79,6 +136,10 @@ export interface TikiConfig {
   changelog?: ChangelogConfig;
   /** Number of timestamped state.json backups to keep (default 10) */
   backupRetention?: number;
+  /** Git branch and worktree conventions */
+  git?: GitConfig;
+  /** GitHub transport selection */
+  github?: GitHubConfig;
 }
 
 /** Default config values applied when a key is missing */
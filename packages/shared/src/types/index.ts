@@ -72,6 +72,10 @@ export type {
   ParallelConfig,
   WorkflowConfig,
   ChangelogConfig,
+  GitConfig,
+  GitHubConfig,
+  GitHubProjectConfig,
+  LabelTemplate,
   TikiConfig,
 } from './config.js';
 
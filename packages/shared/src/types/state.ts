@@ -129,6 +129,8 @@ export interface IssueWork {
   createdAt: Timestamp;
   lastActivity: Timestamp;
   error?: WorkError;
+  /** Number of the pull request opened for this issue */
+  pullRequest?: number;
   /** Parent release version if this issue is part of a release (e.g., 'v1.2') */
   parentRelease?: string;
 }